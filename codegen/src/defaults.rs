@@ -0,0 +1,93 @@
+//! Workspace-level defaults for all invocations, read from `defy.toml`.
+
+use std::path::PathBuf;
+use std::{env, fs};
+
+use proc_macro2::Span;
+use syn::{Error, Result};
+
+use crate::lint::{Level, Lint, Source};
+use crate::Config;
+
+/// Locates the defaults file.
+///
+/// `DEFY_CONFIG` specifies the path explicitly.
+/// Otherwise, the nearest `defy.toml` in `CARGO_MANIFEST_DIR` or its ancestors is used.
+pub fn find() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("DEFY_CONFIG") {
+        return Some(path.into());
+    }
+
+    let manifest_dir = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR")?);
+    manifest_dir.ancestors().map(|dir| dir.join("defy.toml")).find(|path| path.is_file())
+}
+
+/// Reads the defaults file, if any, into `config`.
+pub fn load(config: &mut Config) -> Result<()> {
+    let Some(path) = find() else { return Ok(()) };
+    let error = |message: String| Error::new(Span::call_site(), message);
+
+    let contents = fs::read_to_string(&path)
+        .map_err(|err| error(format!("DEFY0020: cannot read {}: {err}", path.display())))?;
+    let table: toml::Table = contents
+        .parse()
+        .map_err(|err| error(format!("DEFY0021: cannot parse {}: {err}", path.display())))?;
+    apply(config, &table)
+        .map_err(|message| error(format!("DEFY0023: in {}: {message}", path.display())))?;
+    if let (Some(diagnostics), Some(dir)) = (&mut config.diagnostics, path.parent()) {
+        *diagnostics = dir.join(&diagnostics);
+    }
+
+    config.tracked_files.push(path);
+    Ok(())
+}
+
+/// Applies the defaults in `table` to `config`.
+pub fn apply(config: &mut Config, table: &toml::Table) -> std::result::Result<(), String> {
+    for (key, value) in table {
+        match (key.as_str(), value) {
+            ("macro_path", toml::Value::String(path)) => {
+                config.macro_path = syn::parse_str(path)
+                    .map_err(|err| format!("invalid `macro_path` {path:?}: {err}"))?;
+            }
+            ("debug_loc", &toml::Value::Boolean(debug_loc)) => config.debug_loc = debug_loc,
+            ("emit_notes", &toml::Value::Boolean(emit_notes)) => config.emit_notes = emit_notes,
+            ("diagnostics", toml::Value::String(path)) => config.diagnostics = Some(path.into()),
+            ("trace", &toml::Value::Boolean(trace)) => config.trace = trace,
+            ("minify", &toml::Value::Boolean(minify)) => config.minify = minify,
+            ("sort_attrs", &toml::Value::Boolean(sort_attrs)) => config.sort_attrs = sort_attrs,
+            ("testids", &toml::Value::Boolean(testids)) => config.testids = testids,
+            ("strict", &toml::Value::Boolean(strict)) => {
+                config.lints.get_mut().strict = strict.then_some(Source::Defaults)
+            }
+            ("lints", toml::Value::Table(lints)) => {
+                for (name, level) in lints {
+                    let level = match level.as_str() {
+                        Some("allow") => Level::Allow,
+                        Some("warn") => Level::Warn,
+                        Some("deny") => Level::Deny,
+                        _ => {
+                            return Err(format!(
+                                "lint level of `{name}` must be \"allow\", \"warn\" or \"deny\""
+                            ))
+                        }
+                    };
+                    for lint in
+                        Lint::from_str(name).ok_or_else(|| format!("unknown defy lint `{name}`"))?
+                    {
+                        config.lints.get_mut().levels.insert(lint, (level, Source::Defaults));
+                    }
+                }
+            }
+            (
+                "macro_path" | "debug_loc" | "emit_notes" | "diagnostics" | "trace" | "minify"
+                | "sort_attrs" | "testids" | "strict" | "lints",
+                _,
+            ) => {
+                return Err(format!("`{key}` has an invalid type"));
+            }
+            _ => return Err(format!("unknown key `{key}`")),
+        }
+    }
+    Ok(())
+}
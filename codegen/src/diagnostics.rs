@@ -0,0 +1,117 @@
+//! The machine-readable diagnostics sidecar, configured with `diagnostics` in `defy.toml`.
+
+use std::fmt::Write as _;
+use std::path::Path;
+use std::{fs, io};
+
+use proc_macro2::Span;
+use syn::{Error, Result};
+
+use crate::lint::Warning;
+
+/// Writes the diagnostics of the invocation at `call_site` as JSON lines
+/// to a file in the directory `dir` named after the location of the invocation.
+///
+/// The file is replaced on each expansion, and removed if there are no diagnostics.
+pub fn write(
+    dir: &Path,
+    call_site: Span,
+    error: Option<&Error>,
+    warnings: &[Warning],
+) -> Result<()> {
+    let mut output = String::new();
+    for err in error.into_iter().flatten() {
+        let message = err.to_string();
+        let (code, message) = split_code(&message);
+        write_line(&mut output, "error", code, None, &message, err.span());
+    }
+    for warning in warnings {
+        let (lint, code) = (warning.lint.name(), warning.lint.code());
+        write_line(&mut output, "warning", Some(code), Some(lint), &warning.message, warning.span);
+    }
+
+    let path = dir.join(file_name(call_site));
+    let write = || {
+        if output.is_empty() {
+            return match fs::remove_file(&path) {
+                Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+                result => result,
+            };
+        }
+        fs::create_dir_all(dir)?;
+        fs::write(&path, &output)
+    };
+    write().map_err(|err| {
+        Error::new(
+            Span::call_site(),
+            format_args!("DEFY0022: cannot write {}: {err}", path.display()),
+        )
+    })
+}
+
+/// The name of the diagnostics file of an invocation,
+/// e.g. `src_main.rs-3-9.jsonl` for an invocation at line 3, column 9 of `src/main.rs`.
+pub fn file_name(call_site: Span) -> String {
+    let file: String = call_site
+        .file()
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() || ch == '.' || ch == '-' { ch } else { '_' })
+        .collect();
+    let start = call_site.start();
+    format!("{file}-{}-{}.jsonl", start.line, start.column + 1)
+}
+
+/// Splits the `DEFYxxxx` code from a message.
+///
+/// Codes may follow a prefix, e.g. `in templates/footer.defy: DEFY0101: ...`.
+pub fn split_code(message: &str) -> (Option<&str>, String) {
+    for (index, _) in message.match_indices("DEFY") {
+        let code = &message[index..];
+        if code.len() >= 10
+            && code.as_bytes()[4..8].iter().all(u8::is_ascii_digit)
+            && code[8..].starts_with(": ")
+        {
+            return (Some(&code[..8]), format!("{}{}", &message[..index], &code[10..]));
+        }
+    }
+    (None, message.to_string())
+}
+
+fn write_line(
+    output: &mut String,
+    level: &str,
+    code: Option<&str>,
+    lint: Option<&str>,
+    message: &str,
+    span: Span,
+) {
+    let start = span.start();
+    let code = code.map_or("null".to_string(), json_string);
+    let lint = lint.map_or("null".to_string(), json_string);
+    let _ = writeln!(
+        output,
+        r#"{{"level":{},"code":{code},"lint":{lint},"message":{},"file":{},"line":{},"column":{}}}"#,
+        json_string(level),
+        json_string(message),
+        json_string(&span.file()),
+        start.line,
+        start.column + 1,
+    );
+}
+
+fn json_string(value: &str) -> String {
+    let mut output = String::from('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            ch if ch.is_control() => {
+                let _ = write!(output, "\\u{:04x}", ch as u32);
+            }
+            ch => output.push(ch),
+        }
+    }
+    output.push('"');
+    output
+}
@@ -0,0 +1,264 @@
+//! `form` statements and `#[derive(Form)]`.
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::meta::ParseNestedMeta;
+use syn::spanned::Spanned;
+use syn::{Error, Result};
+
+use crate::{arg_name, args_to_html, ast, Config};
+
+/// The attributes set from the `Constraints` of a field, unless specified explicitly.
+const CONSTRAINTS: &[&str] = &["required", "minlength", "maxlength", "min", "max", "pattern"];
+
+/// Converts a `form for handle { ... }` statement into yew HTML tokens.
+pub fn to_html(config: &Config, form: ast::Form) -> Result<TokenStream> {
+    let ast::Form { form_, for_: _, expr, errors, braces: _, fields } = form;
+    let macro_path = &config.macro_path;
+
+    let fields = fields
+        .into_iter()
+        .map(|ast::FormField { field_, ident, args, semi: _ }| {
+            let name = ident.to_string();
+            let explicit: Vec<_> = match &args {
+                ast::NodeArgs::Named { paren: _, args } => {
+                    args.iter().map(|arg| arg_name(&arg.ident)).collect()
+                }
+                _ => Vec::new(),
+            };
+            let input_type = (!explicit.iter().any(|arg| arg == "type")).then(|| {
+                quote_spanned! { field_.span =>
+                    type={__defy_constraints.input_type.unwrap_or_else(|| {
+                        ::defy::__private::form_input_type(&(*__defy_form).#ident)
+                    })}
+                }
+            });
+            let constraints = CONSTRAINTS.iter().filter(|attr| !explicit.iter().any(|arg| arg == *attr)).map(|attr| {
+                let attr = syn::Ident::new(attr, field_.span);
+                quote_spanned! { field_.span => #attr={__defy_constraints.#attr} }
+            });
+            let args = args_to_html(config, args, true)?;
+            let errors = errors.is_some().then(|| {
+                quote_spanned! { field_.span =>
+                    { ::defy::__private::form_errors(__defy_form_errors, #name) }
+                }
+            });
+            Ok(quote_spanned! { field_.span =>
+                { {
+                    let __defy_constraints = ::defy::__private::form_constraints(&*__defy_form, #name);
+                    #macro_path! { <>
+                        <label for={#name}>{ ::defy::__private::form_label(&*__defy_form, #name) }</label>
+                        <input
+                            id={#name}
+                            name={#name}
+                            #input_type
+                            value={::defy::form::FormValue::to_input(&(*__defy_form).#ident)}
+                            checked={::defy::form::FormValue::is_checked(&(*__defy_form).#ident)}
+                            oninput={::defy::__private::form_oninput(
+                                &__defy_form,
+                                |form, value| form.#ident = value,
+                            )}
+                            #(#constraints)*
+                            #args
+                        />
+                        #errors
+                    </> }
+                } }
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let errors = errors.map(|ast::FormErrors { comma: _, errors_, eq: _, expr }| {
+        quote_spanned! { errors_.span => let __defy_form_errors = &#expr; }
+    });
+    Ok(quote_spanned! { form_.span =>
+        { {
+            let __defy_form = ::std::clone::Clone::clone(&#expr);
+            #errors
+            #macro_path! { <> #(#fields)* </> }
+        } }
+    })
+}
+
+/// Implements `defy::form::Form` for a struct with named fields.
+pub fn derive(ts: TokenStream) -> Result<TokenStream> {
+    let input: syn::DeriveInput = syn::parse2(ts)?;
+    let fields = match &input.data {
+        syn::Data::Struct(syn::DataStruct { fields: syn::Fields::Named(fields), .. }) => fields,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "DEFY0024: `Form` can only be derived for structs with named fields",
+            ))
+        }
+    };
+
+    let mut names = Vec::new();
+    let mut labels = Vec::new();
+    let mut constraints = Vec::new();
+    for field in &fields.named {
+        let ident = field.ident.as_ref().expect("fields are named");
+        let mut label = None;
+        let mut constraint = Constraints::default();
+        for attr in &field.attrs {
+            if attr.path().is_ident("form") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("label") {
+                        label = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                    } else if meta.path.is_ident("pattern") {
+                        constraint.pattern = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                    } else {
+                        return Err(Error::new(
+                            meta.path.span(),
+                            "DEFY0024: unknown `form` option; only `label = \"...\"` and `pattern \
+                             = \"...\"` are supported",
+                        ));
+                    }
+                    Ok(())
+                })?;
+            } else if attr.path().is_ident("validate") {
+                attr.parse_nested_meta(|meta| constraint.parse_validate(meta))?;
+            }
+        }
+        names.push(ident.to_string());
+        labels.push(label.unwrap_or_else(|| humanize(&ident.to_string())));
+        constraints.push(constraint.to_tokens());
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::defy::form::Form for #ident #ty_generics #where_clause {
+            fn label(field: &str) -> &'static str {
+                match field {
+                    #(#names => #labels,)*
+                    _ => "",
+                }
+            }
+
+            fn constraints(field: &str) -> ::defy::form::Constraints {
+                match field {
+                    #(#names => #constraints,)*
+                    _ => ::std::default::Default::default(),
+                }
+            }
+        }
+    })
+}
+
+/// The HTML constraints of a field, collected from `#[validate(...)]` and `#[form(...)]`.
+#[derive(Default)]
+struct Constraints {
+    input_type: Option<&'static str>,
+    required:   bool,
+    minlength:  Option<String>,
+    maxlength:  Option<String>,
+    min:        Option<String>,
+    max:        Option<String>,
+    pattern:    Option<String>,
+}
+
+impl Constraints {
+    /// Collects a `validator::Validate` rule.
+    ///
+    /// Rules without an HTML equivalent and bounds that are not literals are ignored.
+    fn parse_validate(&mut self, meta: ParseNestedMeta) -> Result<()> {
+        if meta.path.is_ident("email") {
+            self.input_type = Some("email");
+        } else if meta.path.is_ident("url") {
+            self.input_type = Some("url");
+        } else if meta.path.is_ident("required") {
+            self.required = true;
+        } else if meta.path.is_ident("length") {
+            meta.parse_nested_meta(|meta| {
+                let value = literal_bound(&meta)?;
+                if meta.path.is_ident("min") {
+                    self.minlength = value;
+                } else if meta.path.is_ident("max") {
+                    self.maxlength = value;
+                } else if meta.path.is_ident("equal") {
+                    self.minlength.clone_from(&value);
+                    self.maxlength = value;
+                }
+                Ok(())
+            })?;
+            return Ok(());
+        } else if meta.path.is_ident("range") {
+            meta.parse_nested_meta(|meta| {
+                let value = literal_bound(&meta)?;
+                if meta.path.is_ident("min") {
+                    self.min = value;
+                } else if meta.path.is_ident("max") {
+                    self.max = value;
+                }
+                Ok(())
+            })?;
+            return Ok(());
+        }
+        // skip the arguments of other rules, e.g. `custom(function = "...")`
+        if !meta.input.is_empty() && !meta.input.peek(syn::Token![,]) {
+            meta.input.parse::<proc_macro2::TokenTree>()?;
+        }
+        Ok(())
+    }
+
+    fn to_tokens(&self) -> TokenStream {
+        fn option(value: &Option<String>) -> TokenStream {
+            match value {
+                Some(value) => quote!(::std::option::Option::Some(#value)),
+                None => quote!(::std::option::Option::None),
+            }
+        }
+
+        let input_type = match self.input_type {
+            Some(input_type) => quote!(::std::option::Option::Some(#input_type)),
+            None => quote!(::std::option::Option::None),
+        };
+        let required = self.required;
+        let minlength = option(&self.minlength);
+        let maxlength = option(&self.maxlength);
+        let min = option(&self.min);
+        let max = option(&self.max);
+        let pattern = option(&self.pattern);
+        quote! {
+            ::defy::form::Constraints {
+                input_type: #input_type,
+                required: #required,
+                minlength: #minlength,
+                maxlength: #maxlength,
+                min: #min,
+                max: #max,
+                pattern: #pattern,
+            }
+        }
+    }
+}
+
+/// Parses `= value` of a bound, returning the value as a string if it is a numeric literal.
+fn literal_bound(meta: &ParseNestedMeta) -> Result<Option<String>> {
+    let value: syn::Expr = meta.value()?.parse()?;
+    let (negative, lit) = match &value {
+        syn::Expr::Lit(lit) => (false, &lit.lit),
+        syn::Expr::Unary(syn::ExprUnary { op: syn::UnOp::Neg(_), expr, .. }) => match &**expr {
+            syn::Expr::Lit(lit) => (true, &lit.lit),
+            _ => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+    let digits = match lit {
+        syn::Lit::Int(int) => int.base10_digits().to_string(),
+        syn::Lit::Float(float) => float.base10_digits().to_string(),
+        _ => return Ok(None),
+    };
+    Ok(Some(if negative { format!("-{digits}") } else { digits }))
+}
+
+/// Converts a field name like `first_name` to a label like `First name`.
+pub fn humanize(name: &str) -> String {
+    let words = name.trim_start_matches("r#").split('_').filter(|word| !word.is_empty());
+    let mut label = words.collect::<Vec<_>>().join(" ");
+    if let Some(first) = label.get(..1) {
+        label.replace_range(..1, &first.to_ascii_uppercase());
+    }
+    label
+}
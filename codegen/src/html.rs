@@ -0,0 +1,304 @@
+//! Knowledge about HTML and SVG elements.
+
+/// Standard HTML elements.
+#[rustfmt::skip]
+pub const HTML_ELEMENTS: &[&str] = &[
+    "a", "abbr", "address", "area", "article", "aside", "audio", "b", "base", "bdi", "bdo",
+    "blockquote", "body", "br", "button", "canvas", "caption", "cite", "code", "col", "colgroup",
+    "data", "datalist", "dd", "del", "details", "dfn", "dialog", "div", "dl", "dt", "em", "embed",
+    "fieldset", "figcaption", "figure", "footer", "form", "h1", "h2", "h3", "h4", "h5", "h6",
+    "head", "header", "hgroup", "hr", "html", "i", "iframe", "img", "input", "ins", "kbd", "label",
+    "legend", "li", "link", "main", "map", "mark", "menu", "meta", "meter", "nav", "noscript",
+    "object", "ol", "optgroup", "option", "output", "p", "picture", "pre", "progress", "q", "rp",
+    "rt", "ruby", "s", "samp", "script", "search", "section", "select", "slot", "small", "source",
+    "span", "strong", "style", "sub", "summary", "sup", "table", "tbody", "td", "template",
+    "textarea", "tfoot", "th", "thead", "time", "title", "tr", "track", "u", "ul", "var", "video",
+    "wbr",
+];
+
+/// HTML elements that cannot have children.
+#[rustfmt::skip]
+pub const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Boolean HTML attributes, whose values yew requires to be `bool`.
+///
+/// `checked` is handled separately by yew.
+#[rustfmt::skip]
+pub const BOOLEAN_ATTRIBUTES: &[&str] = &[
+    "allowfullscreen", "async", "autofocus", "autoplay", "controls", "default", "defer", "disabled",
+    "formnovalidate", "hidden", "ismap", "itemscope", "loop", "multiple", "muted", "nomodule",
+    "novalidate", "open", "playsinline", "readonly", "required", "reversed", "selected",
+    "truespeed", "webkitdirectory",
+];
+
+/// HTML elements that are interactive by default.
+#[rustfmt::skip]
+pub const INTERACTIVE_ELEMENTS: &[&str] = &[
+    "a", "button", "details", "input", "label", "option", "select", "summary", "textarea",
+];
+
+/// Obsolete HTML elements, with suggestions for their replacements.
+pub const OBSOLETE_ELEMENTS: &[(&str, &str)] = &[
+    ("acronym", "use `abbr` instead"),
+    ("applet", "use `object` or `embed` instead"),
+    ("basefont", "use the CSS `font` properties instead"),
+    ("big", "use the CSS `font-size` property instead"),
+    ("blink", "use the CSS `animation` property instead"),
+    ("center", "use the CSS `text-align` or `margin: auto` properties instead"),
+    ("dir", "use `ul` instead"),
+    ("font", "use the CSS `font` and `color` properties instead"),
+    ("frame", "use `iframe` instead"),
+    ("frameset", "use `iframe` or CSS layouts instead"),
+    ("marquee", "use the CSS `animation` property instead"),
+    ("nobr", "use the CSS `white-space: nowrap` property instead"),
+    ("noframes", "remove it along with the frames"),
+    ("param", "use the attributes of `object` instead"),
+    ("strike", "use `s` or `del` instead"),
+    ("tt", "use `code`, `kbd`, `samp` or the CSS `font-family` property instead"),
+];
+
+/// Deprecated presentational HTML attributes, with suggestions for their replacements.
+pub const DEPRECATED_ATTRIBUTES: &[(&str, &str)] = &[
+    ("align", "use the CSS `text-align` or flexbox alignment properties instead"),
+    ("background", "use the CSS `background-image` property instead"),
+    ("bgcolor", "use the CSS `background-color` property instead"),
+    ("border", "use the CSS `border` property instead"),
+    ("cellpadding", "use the CSS `padding` property instead"),
+    ("cellspacing", "use the CSS `border-spacing` property instead"),
+    ("color", "use the CSS `color` property instead"),
+    ("valign", "use the CSS `vertical-align` property instead"),
+];
+
+/// The replacement suggestion for an obsolete element.
+pub fn obsolete_element(name: &str) -> Option<&'static str> {
+    OBSOLETE_ELEMENTS.iter().find(|&&(element, _)| element == name).map(|&(_, hint)| hint)
+}
+
+/// The replacement suggestion for a deprecated attribute.
+pub fn deprecated_attribute(attr: &str) -> Option<&'static str> {
+    DEPRECATED_ATTRIBUTES.iter().find(|&&(name, _)| name == attr).map(|&(_, hint)| hint)
+}
+
+/// SVG elements.
+#[rustfmt::skip]
+pub const SVG_ELEMENTS: &[&str] = &[
+    "animate", "animateMotion", "animateTransform", "circle", "clipPath", "defs", "desc", "ellipse",
+    "feBlend", "feColorMatrix", "feComponentTransfer", "feComposite", "feConvolveMatrix",
+    "feDiffuseLighting", "feDisplacementMap", "feDistantLight", "feDropShadow", "feFlood",
+    "feFuncA", "feFuncB", "feFuncG", "feFuncR", "feGaussianBlur", "feImage", "feMerge",
+    "feMergeNode", "feMorphology", "feOffset", "fePointLight", "feSpecularLighting", "feSpotLight",
+    "feTile", "feTurbulence", "filter", "foreignObject", "g", "image", "line", "linearGradient",
+    "marker", "mask", "metadata", "mpath", "path", "pattern", "polygon", "polyline",
+    "radialGradient", "rect", "set", "stop", "svg", "switch", "symbol", "text", "textPath", "tspan",
+    "use", "view",
+];
+
+/// MathML elements.
+#[rustfmt::skip]
+pub const MATHML_ELEMENTS: &[&str] = &[
+    "math", "mi", "mn", "mo", "ms", "mspace", "mtext", "mrow", "mfrac", "msqrt", "mroot", "mstyle",
+    "merror", "mpadded", "mphantom", "msub", "msup", "msubsup", "munder", "mover", "munderover",
+    "mtable", "mtr", "mtd", "semantics", "annotation",
+];
+
+/// Attributes allowed on all HTML elements.
+#[rustfmt::skip]
+pub const GLOBAL_ATTRIBUTES: &[&str] = &[
+    "accesskey", "autocapitalize", "autocorrect", "autofocus", "class", "contenteditable", "dir",
+    "draggable", "enterkeyhint", "exportparts", "hidden", "id", "inert", "inputmode", "is",
+    "itemid", "itemprop", "itemref", "itemscope", "itemtype", "lang", "nonce", "part", "popover",
+    "role", "slot", "spellcheck", "style", "tabindex", "title", "translate", "writingsuggestions",
+    "xmlns",
+];
+
+/// Attributes specific to each HTML element, in addition to [`GLOBAL_ATTRIBUTES`].
+#[rustfmt::skip]
+pub const ELEMENT_ATTRIBUTES: &[(&str, &[&str])] = &[
+    ("a", &["href", "target", "download", "ping", "rel", "hreflang", "type", "referrerpolicy"]),
+    ("area", &[
+        "alt", "coords", "shape", "href", "target", "download", "ping", "rel", "referrerpolicy",
+    ]),
+    ("audio", &["src", "crossorigin", "preload", "autoplay", "loop", "muted", "controls"]),
+    ("base", &["href", "target"]),
+    ("blockquote", &["cite"]),
+    ("q", &["cite"]),
+    ("button", &[
+        "command", "commandfor", "disabled", "form", "formaction", "formenctype", "formmethod",
+        "formnovalidate", "formtarget", "name", "popovertarget", "popovertargetaction", "type",
+        "value",
+    ]),
+    ("canvas", &["width", "height"]),
+    ("col", &["span"]),
+    ("colgroup", &["span"]),
+    ("data", &["value"]),
+    ("del", &["cite", "datetime"]),
+    ("ins", &["cite", "datetime"]),
+    ("details", &["open", "name"]),
+    ("dialog", &["open", "closedby"]),
+    ("embed", &["src", "type", "width", "height"]),
+    ("fieldset", &["disabled", "form", "name"]),
+    ("form", &[
+        "accept-charset", "action", "autocomplete", "enctype", "method", "name", "novalidate",
+        "rel", "target",
+    ]),
+    ("iframe", &[
+        "src", "srcdoc", "name", "sandbox", "allow", "allowfullscreen", "width", "height",
+        "referrerpolicy", "loading",
+    ]),
+    ("img", &[
+        "alt", "src", "srcset", "sizes", "crossorigin", "usemap", "ismap", "width", "height",
+        "referrerpolicy", "decoding", "loading", "fetchpriority",
+    ]),
+    ("input", &[
+        "accept", "alt", "autocomplete", "checked", "dirname", "disabled", "form", "formaction",
+        "formenctype", "formmethod", "formnovalidate", "formtarget", "height", "list", "max",
+        "maxlength", "min", "minlength", "multiple", "name", "pattern", "placeholder",
+        "popovertarget", "popovertargetaction", "readonly", "required", "size", "src", "step",
+        "type", "value", "width",
+    ]),
+    ("label", &["for"]),
+    ("li", &["value"]),
+    ("link", &[
+        "href", "crossorigin", "rel", "as", "media", "hreflang", "type", "sizes", "imagesrcset",
+        "imagesizes", "referrerpolicy", "integrity", "blocking", "color", "disabled",
+        "fetchpriority",
+    ]),
+    ("map", &["name"]),
+    ("meta", &["name", "http-equiv", "content", "charset", "media", "property"]),
+    ("meter", &["value", "min", "max", "low", "high", "optimum"]),
+    ("object", &["data", "type", "name", "form", "width", "height"]),
+    ("ol", &["reversed", "start", "type"]),
+    ("optgroup", &["disabled", "label"]),
+    ("option", &["disabled", "label", "selected", "value"]),
+    ("output", &["for", "form", "name"]),
+    ("progress", &["value", "max"]),
+    ("script", &[
+        "src", "type", "nomodule", "async", "defer", "crossorigin", "integrity", "referrerpolicy",
+        "blocking", "fetchpriority",
+    ]),
+    // `value` of `select` and `textarea` is set as a property by yew
+    ("select", &[
+        "autocomplete", "disabled", "form", "multiple", "name", "required", "size", "value",
+    ]),
+    ("slot", &["name"]),
+    ("source", &["type", "media", "src", "srcset", "sizes", "width", "height"]),
+    ("style", &["media", "blocking"]),
+    ("td", &["colspan", "rowspan", "headers"]),
+    ("th", &["colspan", "rowspan", "headers", "scope", "abbr"]),
+    ("template", &[
+        "shadowrootmode", "shadowrootdelegatesfocus", "shadowrootclonable",
+        "shadowrootserializable",
+    ]),
+    ("textarea", &[
+        "autocomplete", "cols", "dirname", "disabled", "form", "maxlength", "minlength", "name",
+        "placeholder", "readonly", "required", "rows", "value", "wrap",
+    ]),
+    ("time", &["datetime"]),
+    ("track", &["default", "kind", "label", "src", "srclang"]),
+    ("video", &[
+        "src", "crossorigin", "poster", "preload", "autoplay", "playsinline", "loop", "muted",
+        "controls", "width", "height",
+    ]),
+];
+
+pub fn is_known_element(name: &str) -> bool {
+    [HTML_ELEMENTS, SVG_ELEMENTS, MATHML_ELEMENTS].iter().any(|elements| elements.contains(&name))
+        || obsolete_element(name).is_some()
+}
+
+/// Whether `attr` is a valid attribute of the HTML element `element`.
+///
+/// Returns `None` if the attributes of `element` are not known,
+/// e.g. for SVG elements and components.
+pub fn is_valid_attribute(element: &str, attr: &str) -> Option<bool> {
+    if !HTML_ELEMENTS.contains(&element) {
+        return None;
+    }
+
+    // `key` and `ref` are interpreted by yew instead of being rendered
+    let always_valid = ["key", "ref"].contains(&attr)
+        || attr.starts_with("data-")
+        || attr.starts_with("aria-")
+        || attr.starts_with("on");
+    let element_specific = ELEMENT_ATTRIBUTES
+        .iter()
+        .find(|&&(name, _)| name == element)
+        .is_some_and(|(_, attrs)| attrs.contains(&attr));
+    Some(always_valid || element_specific || GLOBAL_ATTRIBUTES.contains(&attr))
+}
+
+/// The modifier keys of key filters, e.g. `@keydown("k" with ctrl)`.
+pub const MODIFIER_KEYS: &[&str] = &["ctrl", "shift", "alt", "meta"];
+
+/// Attributes whose values are URLs that the browser may navigate to or load.
+pub const URL_ATTRIBUTES: &[&str] =
+    &["href", "src", "action", "formaction", "poster", "cite", "data", "ping"];
+
+/// The scheme of `url` if it runs scripts when used as the value of `attr`.
+///
+/// Like browsers, leading whitespace and control characters, tabs and newlines are ignored.
+pub fn unsafe_url_scheme(attr: &str, url: &str) -> Option<&'static str> {
+    let url: String = url
+        .trim_start_matches(|ch: char| ch <= ' ')
+        .chars()
+        .filter(|ch| !matches!(ch, '\t' | '\n' | '\r'))
+        .take(16)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if url.starts_with("javascript:") {
+        Some("javascript:")
+    } else if url.starts_with("vbscript:") {
+        Some("vbscript:")
+    } else if url.starts_with("data:")
+        && (["href", "action", "formaction"].contains(&attr) || url.starts_with("data:text/html"))
+    {
+        // data URLs are commonly used for images, but navigating to them can render HTML
+        Some("data:")
+    } else {
+        None
+    }
+}
+
+/// Named character references supported by `entity` statements.
+#[rustfmt::skip]
+pub const ENTITIES: &[(&str, char)] = &[
+    ("nbsp", '\u{a0}'), ("ensp", '\u{2002}'), ("emsp", '\u{2003}'), ("thinsp", '\u{2009}'),
+    ("zwnj", '\u{200c}'), ("zwj", '\u{200d}'), ("lrm", '\u{200e}'), ("rlm", '\u{200f}'),
+    ("shy", '\u{ad}'), ("amp", '&'), ("lt", '<'), ("gt", '>'), ("quot", '"'), ("apos", '\''),
+    ("ndash", '–'), ("mdash", '—'), ("hellip", '…'), ("bull", '•'), ("middot", '·'),
+    ("lsquo", '‘'), ("rsquo", '’'), ("sbquo", '‚'), ("ldquo", '“'), ("rdquo", '”'),
+    ("bdquo", '„'), ("laquo", '«'), ("raquo", '»'), ("lsaquo", '‹'), ("rsaquo", '›'),
+    ("prime", '′'), ("Prime", '″'), ("dagger", '†'), ("Dagger", '‡'), ("permil", '‰'),
+    ("sect", '§'), ("para", '¶'), ("copy", '©'), ("reg", '®'), ("trade", '™'),
+    ("deg", '°'), ("plusmn", '±'), ("times", '×'), ("divide", '÷'), ("minus", '−'),
+    ("frac14", '¼'), ("frac12", '½'), ("frac34", '¾'), ("sup1", '¹'), ("sup2", '²'),
+    ("sup3", '³'), ("micro", 'µ'), ("not", '¬'), ("iexcl", '¡'), ("iquest", '¿'),
+    ("cent", '¢'), ("pound", '£'), ("yen", '¥'), ("euro", '€'), ("curren", '¤'),
+    ("larr", '←'), ("uarr", '↑'), ("rarr", '→'), ("darr", '↓'), ("harr", '↔'),
+    ("lArr", '⇐'), ("uArr", '⇑'), ("rArr", '⇒'), ("dArr", '⇓'), ("hArr", '⇔'),
+    ("le", '≤'), ("ge", '≥'), ("ne", '≠'), ("asymp", '≈'), ("equiv", '≡'), ("infin", '∞'),
+    ("sum", '∑'), ("prod", '∏'), ("radic", '√'), ("part", '∂'), ("nabla", '∇'),
+    ("isin", '∈'), ("notin", '∉'), ("cap", '∩'), ("cup", '∪'), ("sub", '⊂'), ("sup", '⊃'),
+    ("and", '∧'), ("or", '∨'), ("forall", '∀'), ("exist", '∃'), ("empty", '∅'),
+    ("alpha", 'α'), ("beta", 'β'), ("gamma", 'γ'), ("delta", 'δ'), ("epsilon", 'ε'),
+    ("lambda", 'λ'), ("mu", 'μ'), ("pi", 'π'), ("sigma", 'σ'), ("tau", 'τ'), ("phi", 'φ'),
+    ("omega", 'ω'), ("Delta", 'Δ'), ("Sigma", 'Σ'), ("Omega", 'Ω'),
+    ("spades", '♠'), ("clubs", '♣'), ("hearts", '♥'), ("diams", '♦'), ("check", '✓'),
+    ("star", '☆'), ("starf", '★'),
+];
+
+/// Resolves a named character reference like `nbsp`
+/// or a numeric one like `#8212` or `#x2014`.
+pub fn entity(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+    ENTITIES.iter().find(|&&(entity, _)| entity == name).map(|&(_, ch)| ch)
+}
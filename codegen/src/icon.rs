@@ -0,0 +1,113 @@
+//! Inlining SVG files for `icon` statements.
+
+use std::collections::HashSet;
+
+use proc_macro2::{Punct, Spacing, Span, TokenStream};
+use quote::{quote_spanned, TokenStreamExt};
+use syn::{Error, Result};
+
+const SVG_NS: &str = "http://www.w3.org/2000/svg";
+const XLINK_NS: &str = "http://www.w3.org/1999/xlink";
+
+/// Converts the SVG document `contents` into yew HTML tokens.
+///
+/// `root_args` are emitted as additional attributes of the root `svg` element,
+/// replacing the attributes in the file with names in `overridden`.
+pub fn to_html(
+    lit: &syn::LitStr,
+    contents: &str,
+    root_args: TokenStream,
+    overridden: &HashSet<String>,
+) -> Result<TokenStream> {
+    let doc = roxmltree::Document::parse(contents).map_err(|err| {
+        Error::new_spanned(lit, format_args!("DEFY0021: cannot parse SVG file: {err}"))
+    })?;
+
+    let root = doc.root_element();
+    if !is_svg_element(root) || root.tag_name().name() != "svg" {
+        return Err(Error::new_spanned(
+            lit,
+            "DEFY0013: the root element of an icon file must be `<svg>`",
+        ));
+    }
+
+    element_to_html(lit.span(), root, root_args, overridden)
+}
+
+fn is_svg_element(node: roxmltree::Node) -> bool {
+    matches!(node.tag_name().namespace(), None | Some(SVG_NS))
+}
+
+fn element_to_html(
+    span: Span,
+    node: roxmltree::Node,
+    extra_args: TokenStream,
+    overridden: &HashSet<String>,
+) -> Result<TokenStream> {
+    let name = dashed_name(span, node.tag_name().name())?;
+
+    let attrs = node.attributes().filter_map(|attr| {
+        let attr_name = match attr.namespace() {
+            None => attr.name(),
+            Some(XLINK_NS) if attr.name() == "href" => "href",
+            // editor metadata and other foreign attributes are meaningless in inline SVG
+            Some(_) => return None,
+        };
+        if overridden.contains(attr_name) {
+            return None;
+        }
+
+        let attr_name = match dashed_name(span, attr_name) {
+            Ok(attr_name) => attr_name,
+            Err(err) => return Some(Err(err)),
+        };
+        let value = attr.value();
+        Some(Ok(quote_spanned! { span => #attr_name = #value }))
+    });
+    let attrs = attrs.collect::<Result<Vec<_>>>()?;
+
+    let children = node.children().filter_map(|child| {
+        if child.is_element() {
+            is_svg_element(child)
+                .then(|| element_to_html(span, child, TokenStream::new(), &HashSet::new()))
+        } else if child.is_text() {
+            let text = child.text().unwrap_or_default().trim();
+            (!text.is_empty()).then(|| Ok(quote_spanned! { span => { #text } }))
+        } else {
+            None
+        }
+    });
+    let children = children.collect::<Result<Vec<_>>>()?;
+
+    Ok(quote_spanned! { span =>
+        <#name #(#attrs)* #extra_args>
+            #(#children)*
+        </#name>
+    })
+}
+
+/// Converts an element or attribute name into dash-separated identifiers.
+fn dashed_name(span: Span, name: &str) -> Result<TokenStream> {
+    let mut ts = TokenStream::new();
+    for (i, part) in name.split('-').enumerate() {
+        let valid = part.starts_with(|ch: char| ch.is_ascii_alphabetic() || ch == '_')
+            && part.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+            && part != "_";
+        if !valid {
+            return Err(Error::new(
+                span,
+                format_args!(
+                    "DEFY0040: `{name}` in the icon file cannot be written in yew, since `{part}` \
+                     is not an identifier"
+                ),
+            ));
+        }
+        if i > 0 {
+            let mut punct = Punct::new('-', Spacing::Alone);
+            punct.set_span(span);
+            ts.append(punct);
+        }
+        ts.append(syn::Ident::new(part, span));
+    }
+    Ok(ts)
+}
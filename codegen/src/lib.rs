@@ -0,0 +1,2460 @@
+//! Code generation for the [`defy!`](https://docs.rs/defy) macro.
+//!
+//! This crate is an implementation detail of `defy`.
+//! It is separate from the proc-macro crate so that `defy::testing` can expand templates at runtime.
+
+#![cfg_attr(feature = "nightly", feature(proc_macro_diagnostic))]
+
+extern crate proc_macro;
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::{env, fs, mem};
+
+use defy_syntax as ast;
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Error, Result};
+
+mod defaults;
+mod diagnostics;
+mod form;
+mod html;
+mod icon;
+mod lint;
+#[cfg(feature = "markdown")]
+mod minify;
+mod og;
+mod picture;
+mod raw;
+mod story;
+mod tests;
+mod theme;
+mod xml;
+
+/// Expands the contents of a `defy!` invocation.
+///
+/// Errors are reported as `compile_error!` invocations in the output.
+pub fn expand(ts: TokenStream) -> TokenStream { run(ts).unwrap_or_else(Error::into_compile_error) }
+
+/// Expands the contents of a `defy_iter!` invocation.
+///
+/// Errors are reported as `compile_error!` invocations in the output.
+pub fn expand_iter(ts: TokenStream) -> TokenStream {
+    let mut config = Config::new();
+    config.iter = true;
+    run_config(config, ts).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Expands `#[derive(Form)]`.
+///
+/// Errors are reported as `compile_error!` invocations in the output.
+pub fn derive_form(ts: TokenStream) -> TokenStream {
+    form::derive(ts).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Expands `#[stories]` on a module.
+///
+/// Errors are reported as `compile_error!` invocations in the output.
+pub fn stories(attr: TokenStream, item: TokenStream) -> TokenStream {
+    story::stories(attr, item).unwrap_or_else(Error::into_compile_error)
+}
+
+/// Expands `#[story]` outside a `#[stories]` module, which is an error.
+pub fn story(item: TokenStream) -> TokenStream {
+    story::story(item).unwrap_or_else(Error::into_compile_error)
+}
+
+struct Config {
+    debug_print:    Option<DebugPrint>,
+    debug_loc:      bool,
+    emit_notes:     bool,
+    trace:          bool,
+    /// Whether static HTML, e.g. from markdown files, is minified.
+    minify:         bool,
+    /// Whether attributes are emitted in alphabetical order instead of source order.
+    sort_attrs:     bool,
+    /// Whether `data-testid` attributes are derived for HTML elements without a `testid`.
+    testids:        bool,
+    /// The test IDs of the enclosing nodes, from which the test IDs of children are derived.
+    testid_scope:   RefCell<Vec<String>>,
+    diagnostics:    Option<PathBuf>,
+    macro_path:     syn::Path,
+    i18n:           Option<syn::Path>,
+    i18n_messages:  Option<HashSet<String>>,
+    asset_resolver: Option<syn::Path>,
+    scope:          Option<String>,
+    scoped_classes: RefCell<BTreeSet<String>>,
+    theme:          Option<toml::Table>,
+    tracked_files:  Vec<PathBuf>,
+    lints:          RefCell<lint::Lints>,
+    /// The number of enclosing `for` loops lowered to Rust loops,
+    /// in which `break` and `continue` can be used.
+    loop_depth:     Cell<usize>,
+    /// The value of the enclosing `select` element, which `options from` compares against.
+    select_value:   RefCell<Option<TokenStream>>,
+    /// The `nonce` of all `script` and `style` elements.
+    csp_nonce:      Option<syn::Expr>,
+    /// The `Context` of the struct component that `@event` handlers send messages to.
+    link:           Option<syn::Expr>,
+    /// Whether elements are rendered as XML tags instead of yew elements.
+    xml:            bool,
+    /// The `Blocks` that the `block` statements of a layout look up their overrides in.
+    blocks:         Option<syn::Expr>,
+    /// Whether the top-level statements are emitted as the items of an iterator
+    /// instead of a fragment, for `defy_iter!`.
+    iter:           bool,
+}
+
+impl Config {
+    fn new() -> Self {
+        Config {
+            debug_print:    None,
+            debug_loc:      false,
+            emit_notes:     false,
+            trace:          false,
+            minify:         false,
+            sort_attrs:     false,
+            testids:        false,
+            testid_scope:   RefCell::default(),
+            diagnostics:    None,
+            macro_path:     syn::parse2(quote!(::yew::html)).unwrap(),
+            i18n:           None,
+            i18n_messages:  None,
+            asset_resolver: None,
+            scope:          None,
+            scoped_classes: RefCell::default(),
+            theme:          None,
+            tracked_files:  Vec::new(),
+            lints:          RefCell::default(),
+            loop_depth:     Cell::new(0),
+            select_value:   RefCell::new(None),
+            csp_nonce:      None,
+            link:           None,
+            xml:            false,
+            blocks:         None,
+            iter:           false,
+        }
+    }
+}
+
+fn run(ts: TokenStream) -> Result<TokenStream> { run_config(Config::new(), ts) }
+
+fn run_config(mut config: Config, ts: TokenStream) -> Result<TokenStream> {
+    defaults::load(&mut config)?;
+    let result = run_with_config(&mut config, ts);
+    if let Some(dir) = &config.diagnostics {
+        let warnings = &config.lints.borrow().warnings;
+        diagnostics::write(dir, Span::call_site(), result.as_ref().err(), warnings)?;
+    }
+    result
+}
+
+fn run_with_config(config: &mut Config, ts: TokenStream) -> Result<TokenStream> {
+    let input: ast::Input = syn::parse2(ts)?;
+
+    let mut export = None;
+    let mut exported_configs = TokenStream::new();
+    for (ast_config, tokens) in input.configs {
+        if let ast::Config::Export { at: _, kw: _, name } = ast_config {
+            export = Some(name);
+            continue;
+        }
+        // debugging output is only wanted for this invocation,
+        // and the macro path is exported after the defaults and all configs are applied
+        if !matches!(
+            ast_config,
+            ast::Config::DebugPrint { .. }
+                | ast::Config::DebugLoc { .. }
+                | ast::Config::EmitNotes { .. }
+                | ast::Config::MacroPath { .. }
+        ) {
+            exported_configs.extend(tokens);
+        }
+
+        match ast_config {
+            ast::Config::Export { .. } => unreachable!(),
+            ast::Config::DebugPrint { at: _, kw, target } => {
+                config.debug_print = Some(match target {
+                    None => DebugPrint::Stdout,
+                    Some((_, ast::DebugPrintTarget::File(lit))) => DebugPrint::File(lit),
+                    Some((_, ast::DebugPrintTarget::Note(_))) => DebugPrint::Note(kw.span()),
+                });
+            }
+            ast::Config::DebugLoc { at: _, kw: _ } => config.debug_loc = true,
+            ast::Config::EmitNotes { at: _, kw: _ } => config.emit_notes = true,
+            ast::Config::Trace { at: _, kw: _ } => config.trace = true,
+            ast::Config::Minify { at: _, kw: _ } => config.minify = true,
+            ast::Config::SortAttrs { at: _, kw: _ } => config.sort_attrs = true,
+            ast::Config::Testids { at: _, kw: _ } => config.testids = true,
+            ast::Config::Xml { at: _, kw: _ } => config.xml = true,
+            ast::Config::CspNonce { at: _, kw: _, nonce } => config.csp_nonce = Some(nonce),
+            ast::Config::Link { at: _, kw: _, ctx } => config.link = Some(ctx),
+            ast::Config::Blocks { at: _, kw: _, blocks } => config.blocks = Some(blocks),
+            ast::Config::MacroPath { at: _, kw: _, path } => config.macro_path = path,
+            ast::Config::I18n { at: _, kw: _, path } => config.i18n = Some(path),
+            ast::Config::AssetResolver { at: _, kw: _, path } => config.asset_resolver = Some(path),
+            ast::Config::Scope { at: _, kw: _, name } => config.scope = Some(name.value()),
+            ast::Config::I18nResources { at: _, kw: _, path } => {
+                config.i18n_messages = Some(read_fluent_messages(&path, &mut config.tracked_files)?)
+            }
+            ast::Config::Strict { at: _, kw: _ } => {
+                config.lints.get_mut().strict = Some(lint::Source::Strict)
+            }
+            ast::Config::LintLevel { at: _, level, paren: _, lints } => {
+                let level = match level {
+                    ast::LintLevel::Allow(_) => lint::Level::Allow,
+                    ast::LintLevel::Warn(_) => lint::Level::Warn,
+                    ast::LintLevel::Deny(_) => lint::Level::Deny,
+                };
+                for name in &lints {
+                    for lint in lint::Lint::from_name(name)? {
+                        config.lints.get_mut().levels.insert(lint, (level, lint::Source::Config));
+                    }
+                }
+            }
+            ast::Config::Theme { at: _, kw: _, path } => {
+                let (file, contents) = read_source_file(&path)?;
+                config.theme = Some(contents.parse().map_err(|err| {
+                    Error::new_spanned(
+                        &path,
+                        format_args!("DEFY0021: cannot parse {}: {err}", file.display()),
+                    )
+                })?);
+                config.tracked_files.push(file);
+            }
+        }
+    }
+
+    let export = export.map(|name| {
+        let macro_path = &config.macro_path;
+        quote! {
+            #[allow(unused_macros)]
+            macro_rules! #name {
+                ($($tt:tt)*) => {
+                    ::defy::defy! { @macro_path #macro_path #exported_configs $($tt)* }
+                };
+            }
+        }
+    });
+    if let (Some(export), true) = (&export, input.nodes.stmts.is_empty()) {
+        // used in item position to share the configs with sibling invocations
+        return Ok(export.clone());
+    }
+
+    let mut output = if config.iter {
+        emit_iter(config, Span::call_site(), input.nodes, config.trace)?
+    } else {
+        emit_block(config, Span::call_site(), input.nodes, config.trace)?
+    };
+    if let Some(export) = export {
+        output = quote! {
+            {
+                #export
+                #output
+            }
+        };
+    }
+    let warnings = config.lints.get_mut().finish()?;
+    if !config.tracked_files.is_empty() || !warnings.is_empty() {
+        let files = config.tracked_files.iter().map(|file| file.to_string_lossy());
+        output = quote! {
+            {
+                #(const _: &str = ::std::include_str!(#files);)*
+                #warnings
+                #output
+            }
+        };
+    }
+    if let Some(scope) = &config.scope {
+        write_scope_map(scope, &config.scoped_classes.borrow())?;
+    }
+    match &config.debug_print {
+        None => {}
+        Some(DebugPrint::Stdout) => println!("{}", pretty_print(&output)),
+        Some(DebugPrint::File(lit)) => {
+            let mut path = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default());
+            path.push(lit.value());
+            let write = || {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&path, pretty_print(&output))
+            };
+            write().map_err(|err| {
+                Error::new_spanned(
+                    lit,
+                    format_args!("DEFY0022: cannot write {}: {err}", path.display()),
+                )
+            })?;
+        }
+        Some(DebugPrint::Note(span)) => {
+            let note =
+                lint::emit_note(*span, &format!("defy expansion:\n{}", pretty_print(&output)));
+            output = quote! {
+                {
+                    #note
+                    #output
+                }
+            };
+        }
+    }
+    Ok(output)
+}
+
+/// Formats the expansion with prettyplease, falling back to the raw tokens if it cannot be parsed.
+///
+/// The expansion is an expression, so it is printed as the body of a function.
+pub fn pretty_print(output: &TokenStream) -> String {
+    match syn::parse2(quote!(fn expansion() { #output })) {
+        Ok(item) => prettyplease::unparse(&syn::File {
+            shebang: None,
+            attrs:   Vec::new(),
+            items:   vec![item],
+        }),
+        Err(_) => output.to_string(),
+    }
+}
+
+/// Where `@debug_print` writes the expansion to.
+enum DebugPrint {
+    Stdout,
+    File(syn::LitStr),
+    Note(Span),
+}
+
+fn emit(config: &Config, span: Span, nodes: ast::Nodes) -> Result<TokenStream> {
+    emit_block(config, span, nodes, false)
+}
+
+/// Emits a block of statements rendered in a closure, where `break` and `continue` cannot be used.
+fn emit_closure(config: &Config, span: Span, nodes: ast::Nodes) -> Result<TokenStream> {
+    let depth = config.loop_depth.replace(0);
+    let body = emit(config, span, nodes);
+    config.loop_depth.set(depth);
+    body
+}
+
+/// Emits a block of statements, wrapping each statement in a tracing span if `trace` is set.
+fn emit_block(config: &Config, span: Span, nodes: ast::Nodes, trace: bool) -> Result<TokenStream> {
+    let macro_path = &config.macro_path;
+    let (prelude, node_html) = emit_stmts(config, nodes, trace)?;
+    Ok(quote_spanned! { span =>
+        {
+            #prelude
+            #macro_path! {
+                <>
+                    #(#node_html)*
+                </>
+            }
+        }
+    })
+}
+
+/// Emits the statements of a `defy_iter!` invocation as a `vec::IntoIter`
+/// with an item for each statement, rendered with the configured macro.
+fn emit_iter(
+    config: &Config,
+    span: Span,
+    mut nodes: ast::Nodes,
+    trace: bool,
+) -> Result<TokenStream> {
+    let macro_path = &config.macro_path;
+
+    // `cfg` attributes exclude the item instead of rendering an empty node,
+    // and `emit_stmts` keeps the order of the statements other than `let` statements and items
+    let mut cfgs = Vec::new();
+    for stmt in &mut nodes.stmts {
+        match stmt {
+            ast::Stmt::Let(_) | ast::Stmt::Item(_) => {}
+            ast::Stmt::Attributed(ast::Attributed { attrs: _, stmt })
+                if matches!(**stmt, ast::Stmt::Let(_)) => {}
+            ast::Stmt::Attributed(ast::Attributed { attrs, stmt: _ }) => {
+                let (cfg, others): (Vec<_>, Vec<_>) =
+                    mem::take(attrs).into_iter().partition(|attr| attr.path().is_ident("cfg"));
+                *attrs = others;
+                cfgs.push(cfg);
+            }
+            _ => cfgs.push(Vec::new()),
+        }
+    }
+
+    let (prelude, node_html) = emit_stmts(config, nodes, trace)?;
+    // statements that render nothing, such as notes, are not items
+    let pushes = cfgs
+        .into_iter()
+        .zip(node_html)
+        .filter(|(_, html)| !html.is_empty())
+        .map(|(cfg, html)| quote! { #(#cfg)* __defy_items.push(#macro_path! { #html }); });
+    Ok(quote_spanned! { span =>
+        {
+            #prelude
+            // the item type is inferred from the configured macro even if no items are pushed
+            #[allow(unused_mut)]
+            let mut __defy_items = ::std::vec![#macro_path! {}; 0];
+            #(#pushes)*
+            ::std::iter::IntoIterator::into_iter(__defy_items)
+        }
+    })
+}
+
+/// Emits the items and `let` statements of a block, followed by the HTML of each other statement.
+fn emit_stmts(
+    config: &Config,
+    nodes: ast::Nodes,
+    trace: bool,
+) -> Result<(TokenStream, Vec<TokenStream>)> {
+    let macro_path = &config.macro_path;
+
+    // items are visible in the whole block in Rust, so they are not ordered with other statements
+    let (items, stmts): (Vec<_>, Vec<_>) =
+        nodes.stmts.into_iter().partition(|stmt| matches!(stmt, ast::Stmt::Item(_)));
+    let items = items.into_iter().map(|stmt| match stmt {
+        ast::Stmt::Item(item) => item,
+        _ => unreachable!(),
+    });
+    let mut stmts = stmts.into_iter().peekable();
+
+    let mut locals = Vec::new();
+    // notes between `let` statements are emitted after them
+    let mut notes = Vec::new();
+    while let Some(stmt) = stmts.peek() {
+        let (attrs, let_) = match stmt {
+            ast::Stmt::Let(_) => match stmts.next() {
+                Some(ast::Stmt::Let(let_)) => (Vec::new(), let_),
+                _ => unreachable!(),
+            },
+            ast::Stmt::Attributed(ast::Attributed { stmt, .. })
+                if matches!(**stmt, ast::Stmt::Let(_)) =>
+            {
+                match stmts.next() {
+                    Some(ast::Stmt::Attributed(ast::Attributed { attrs, stmt })) => match *stmt {
+                        ast::Stmt::Let(let_) => (attrs, let_),
+                        _ => unreachable!(),
+                    },
+                    _ => unreachable!(),
+                }
+            }
+            ast::Stmt::Note(_) => {
+                notes.extend(stmts.next());
+                continue;
+            }
+            _ => break,
+        };
+        let ast::Let { let_, pat, ty, eq, expr, semi } = let_;
+        let ty = ty.map(|(colon, ty)| quote!(#colon #ty));
+        locals.push(quote_spanned! { let_.span() =>
+            #(#attrs)* #let_ #pat #ty #eq #expr #semi
+        });
+    }
+
+    let node_html: Vec<_> = notes
+        .into_iter()
+        .chain(stmts)
+        .map(|stmt| {
+            // without the `trace` feature, `@trace` compiles to nothing
+            if !trace || !cfg!(feature = "trace") {
+                return stmt_to_html(config, stmt);
+            }
+            let guard = trace_guard(&stmt);
+            let html = stmt_to_html(config, stmt)?;
+            Ok(quote! {
+                { {
+                    #guard
+                    #macro_path! { #html }
+                } }
+            })
+        })
+        .collect::<Result<_>>()?;
+    Ok((quote!(#(#items)* #(#locals)*), node_html))
+}
+
+/// Enters a tracing span for the construction of a top-level statement.
+fn trace_guard(stmt: &ast::Stmt) -> TokenStream {
+    let node = match stmt {
+        ast::Stmt::Attributed(attributed) => return trace_guard(&attributed.stmt),
+        ast::Stmt::Node(node) => node.element.to_token_stream().to_string().replace(' ', ""),
+        ast::Stmt::If(_) => "if".to_string(),
+        ast::Stmt::Match(_) => "match".to_string(),
+        ast::Stmt::For(_) => "for".to_string(),
+        ast::Stmt::Let(_) => "let".to_string(),
+        ast::Stmt::Item(_) => "item".to_string(),
+        ast::Stmt::Text(_) => "text".to_string(),
+        ast::Stmt::Embed(_) => "embed".to_string(),
+        ast::Stmt::Dyn(_) => "dyn".to_string(),
+        ast::Stmt::Include(_) => "include".to_string(),
+        ast::Stmt::Markdown(_) => "markdown".to_string(),
+        ast::Stmt::Translate(_) => "t".to_string(),
+        ast::Stmt::Icon(_) => "icon".to_string(),
+        ast::Stmt::Picture(_) => "picture".to_string(),
+        ast::Stmt::JsonScript(_) => "json_script".to_string(),
+        ast::Stmt::Og(_) => "og".to_string(),
+        ast::Stmt::DocumentHead(_) => "document_head".to_string(),
+        ast::Stmt::Entity(_) => "entity".to_string(),
+        ast::Stmt::Space(_) => "space".to_string(),
+        ast::Stmt::Verbatim(_) => "verbatim".to_string(),
+        ast::Stmt::Raw(_) => "raw".to_string(),
+        ast::Stmt::Passthrough(_) => "passthrough".to_string(),
+        ast::Stmt::Style(_) => "style".to_string(),
+        ast::Stmt::Memo(_) => "memo".to_string(),
+        ast::Stmt::Once(_) => "once".to_string(),
+        ast::Stmt::Defer(_) => "defer".to_string(),
+        ast::Stmt::Await(_) => "await".to_string(),
+        ast::Stmt::Try(_) => "try".to_string(),
+        ast::Stmt::Repeat(_) => "repeat".to_string(),
+        ast::Stmt::With(_) => "with".to_string(),
+        ast::Stmt::WrapIf(_) => "wrap_if".to_string(),
+        ast::Stmt::Transition(_) => "transition".to_string(),
+        ast::Stmt::VirtualFor(_) => "virtual for".to_string(),
+        ast::Stmt::Island(_) => "island".to_string(),
+        ast::Stmt::Lazy(_) => "lazy".to_string(),
+        ast::Stmt::XmlDecl(_) => "xml_decl".to_string(),
+        ast::Stmt::Doctype(_) => "doctype".to_string(),
+        ast::Stmt::Block(block) => format!("block {}", block.name),
+        ast::Stmt::Extend(_) => "extend".to_string(),
+        ast::Stmt::Maybe(_) => "maybe".to_string(),
+        ast::Stmt::Form(_) => "form".to_string(),
+        ast::Stmt::Table(_) => "table".to_string(),
+        ast::Stmt::Options(_) => "options".to_string(),
+        ast::Stmt::Do(_) => "do".to_string(),
+        ast::Stmt::Note(_) => "note".to_string(),
+        ast::Stmt::Break(_) => "break".to_string(),
+        ast::Stmt::Continue(_) => "continue".to_string(),
+    };
+    let span = stmt.span();
+    let loc = format!("{}:{}", span.file(), span.start().line);
+    quote! {
+        let _guard = ::defy::__private::tracing::trace_span!("defy", node = #node, loc = #loc).entered();
+    }
+}
+
+/// Warns at runtime in debug builds when the single root node of a `for` body has no `key`,
+/// the first time the loop renders an item.
+fn unkeyed_list_warning(for_: &syn::Token![for], body: &ast::Nodes) -> TokenStream {
+    let mut roots = body.stmts.iter().filter(|stmt| {
+        !matches!(stmt, ast::Stmt::Let(_) | ast::Stmt::Item(_) | ast::Stmt::Note(_))
+    });
+    let (Some(ast::Stmt::Node(node)), None) = (roots.next(), roots.next()) else {
+        return TokenStream::new();
+    };
+    match &node.args {
+        ast::NodeArgs::None => {}
+        ast::NodeArgs::Named { paren: _, args } => {
+            if args.iter().any(|arg| arg_name(&arg.ident) == "key") {
+                return TokenStream::new();
+            }
+        }
+        ast::NodeArgs::Rest { .. } => return TokenStream::new(),
+    }
+
+    let loc = format!("{}:{}", for_.span.file(), for_.span.start().line);
+    quote! {
+        #[cfg(debug_assertions)]
+        {
+            static WARNED: ::std::sync::atomic::AtomicBool =
+                ::std::sync::atomic::AtomicBool::new(false);
+            if !WARNED.swap(true, ::std::sync::atomic::Ordering::Relaxed) {
+                ::defy::__private::warn_unkeyed_list(#loc);
+            }
+        }
+    }
+}
+
+/// Emits a `for` loop.
+///
+/// Loops are normally lowered to `Iterator::map`.
+/// Loops containing `break` or `continue` are lowered to a Rust loop collecting the items instead.
+fn for_to_html(config: &Config, for_: ast::For, warn_unkeyed: bool) -> Result<TokenStream> {
+    let macro_path = &config.macro_path;
+    let ast::For { for_, pat, in_, iter, braces, body, else_ } = for_;
+    let warning =
+        if warn_unkeyed { unkeyed_list_warning(&for_, &body) } else { TokenStream::new() };
+
+    if has_loop_control(&body) {
+        let depth = config.loop_depth.get();
+        config.loop_depth.set(depth + 1);
+        let body = emit(config, braces.span.join(), body);
+        config.loop_depth.set(depth);
+        let body = body?;
+
+        let span = in_.span();
+        let nodes = quote_spanned!(span => #macro_path! { { for __defy_nodes } });
+        let output = match else_ {
+            Some(ast::Else { else_, braces, body }) => {
+                let else_body = emit(config, braces.span.join(), body)?;
+                quote_spanned!(span => if __defy_nodes.is_empty() { #else_body } #else_ { #nodes })
+            }
+            None => nodes,
+        };
+        return Ok(quote_spanned! { span =>
+            { {
+                let mut __defy_nodes = ::std::vec::Vec::new();
+                #for_ #pat #in_ #iter {
+                    #warning
+                    __defy_nodes.push(#body);
+                }
+                #output
+            } }
+        });
+    }
+
+    // `break` and `continue` in nested loops inside the closure would not refer to this loop
+    let depth = config.loop_depth.replace(0);
+    let body = emit(config, braces.span.join(), body);
+    config.loop_depth.set(depth);
+    let body = body?;
+
+    Ok(match else_ {
+        None => quote_spanned! { in_.span() =>
+            { #for_ ::std::iter::IntoIterator::into_iter(#iter).map(|#pat| { #warning #body }) }
+        },
+        Some(ast::Else { else_, braces, body: else_body }) => {
+            let else_body = emit(config, braces.span.join(), else_body)?;
+            quote_spanned! { in_.span() =>
+                { {
+                    let mut __defy_items =
+                        ::std::iter::Iterator::peekable(::std::iter::IntoIterator::into_iter(#iter));
+                    if __defy_items.peek().is_none() { #else_body } #else_ {
+                        #macro_path! {
+                            { #for_ __defy_items.map(|#pat| { #warning #body }) }
+                        }
+                    }
+                } }
+            }
+        }
+    })
+}
+
+/// Whether a `for` body contains `break` or `continue` outside nested loops.
+fn has_loop_control(nodes: &ast::Nodes) -> bool { nodes.stmts.iter().any(stmt_has_loop_control) }
+
+fn stmt_has_loop_control(stmt: &ast::Stmt) -> bool {
+    match stmt {
+        ast::Stmt::Break(_) | ast::Stmt::Continue(_) => true,
+        ast::Stmt::If(if_) if if_.media.is_some() => false,
+        ast::Stmt::If(if_) => {
+            has_loop_control(&if_.body)
+                || if_.else_.as_ref().is_some_and(|else_| has_loop_control(&else_.body))
+        }
+        ast::Stmt::Match(match_) => match_.arms.iter().any(|arm| has_loop_control(&arm.body)),
+        ast::Stmt::Attributed(attributed) => stmt_has_loop_control(&attributed.stmt),
+        ast::Stmt::Node(ast::Node { body: ast::NodeBody::Braced { children, .. }, .. }) => {
+            has_loop_control(children)
+        }
+        ast::Stmt::Maybe(maybe) => {
+            has_loop_control(&maybe.body)
+                || maybe.else_.as_ref().is_some_and(|else_| has_loop_control(&else_.body))
+        }
+        ast::Stmt::DocumentHead(head) => has_loop_control(&head.body),
+        ast::Stmt::Transition(transition) => has_loop_control(&transition.body),
+        ast::Stmt::With(with) => has_loop_control(&with.body),
+        ast::Stmt::WrapIf(wrap_if) => match &wrap_if.node.body {
+            ast::NodeBody::Braced { children, .. } => has_loop_control(children),
+            ast::NodeBody::Semi(_) => false,
+        },
+        _ => false,
+    }
+}
+
+/// The names that the dependencies of a `memo` or `await` block are bound to, and their values.
+fn memo_deps(
+    deps: Punctuated<ast::MemoDep, syn::Token![,]>,
+) -> (Vec<syn::Ident>, Vec<TokenStream>) {
+    deps.into_iter()
+        .map(|ast::MemoDep { ident, value }| {
+            let value = match value {
+                Some((_, value)) => value.into_token_stream(),
+                None => ident.to_token_stream(),
+            };
+            (ident, value)
+        })
+        .unzip()
+}
+
+/// The values of the `name = value` arguments of a `block` in the order of `names`.
+fn block_args<'a, const N: usize>(
+    block: &str,
+    args: &'a Punctuated<ast::BlockArg, syn::Token![,]>,
+    names: [&str; N],
+) -> Result<[Option<&'a syn::Expr>; N]> {
+    let mut values = [None; N];
+    for ast::BlockArg { ident, eq: _, value } in args {
+        let Some(index) = names.iter().position(|name| ident == name) else {
+            let expected: Vec<_> = names.iter().map(|name| format!("`{name}`")).collect();
+            return Err(Error::new_spanned(
+                ident,
+                format_args!(
+                    "DEFY0033: unknown `{block}` argument `{ident}`, expected {}",
+                    expected.join(", ")
+                ),
+            ));
+        };
+        if values[index].replace(&**value).is_some() {
+            return Err(Error::new_spanned(
+                ident,
+                format_args!("DEFY0033: duplicate `{block}` argument `{ident}`"),
+            ));
+        }
+    }
+    Ok(values)
+}
+
+/// Emits `break` or `continue` in a loop lowered by [`for_to_html`].
+fn loop_control(config: &Config, keyword: impl ToTokens) -> Result<TokenStream> {
+    if config.loop_depth.get() == 0 {
+        return Err(Error::new_spanned(
+            &keyword,
+            format_args!(
+                "DEFY0016: `{}` can only be used in the body of a `for` loop",
+                keyword.to_token_stream()
+            ),
+        ));
+    }
+    let macro_path = &config.macro_path;
+    // `if true` prevents the rest of the loop body from being reported as unreachable
+    Ok(quote_spanned! { keyword.span() =>
+        { { if true { #keyword } #macro_path! {} } }
+    })
+}
+
+fn stmt_to_html(config: &Config, stmt: ast::Stmt) -> Result<TokenStream> {
+    let macro_path = &config.macro_path;
+    Ok(match stmt {
+        ast::Stmt::If(ast::If { if_, media: Some(_), expr, braces, body, else_ }) => {
+            // both branches are rendered eagerly,
+            // so `break` and `continue` would be evaluated regardless of the query
+            let depth = config.loop_depth.replace(0);
+            let bodies = emit(config, braces.span.join(), body).and_then(|body| {
+                let else_body = match else_ {
+                    Some(ast::Else { else_: _, braces, body }) => {
+                        emit(config, braces.span.join(), body)?
+                    }
+                    None => quote!(#macro_path! {}),
+                };
+                Ok((body, else_body))
+            });
+            config.loop_depth.set(depth);
+            let (body, else_body) = bodies?;
+            quote_spanned! { if_.span() =>
+                { ::defy::__private::media(#expr, #body, #else_body) }
+            }
+        }
+        ast::Stmt::If(ast::If {
+            if_,
+            media: None,
+            expr,
+            braces: if_braces,
+            body: if_body,
+            else_: Some(ast::Else { else_, braces: else_braces, body: else_body }),
+        }) => {
+            let if_body = emit(config, if_braces.span.join(), if_body)?;
+            let if_part = quote_spanned! { if_braces.span =>
+                #if_ #expr { #if_body }
+            };
+
+            let else_body = emit(config, else_braces.span.join(), else_body)?;
+            let else_part = quote_spanned! { else_braces.span =>
+                #else_ { #else_body }
+            };
+
+            quote_spanned! { if_.span() =>
+                { #if_part #else_part }
+            }
+        }
+        ast::Stmt::If(ast::If { if_, media: None, expr, braces, body, else_: None }) => {
+            let body = emit(config, braces.span.join(), body)?;
+            quote_spanned! { if_.span() =>
+                { #if_ #expr { #body } else { #macro_path! {} } }
+            }
+        }
+        ast::Stmt::Match(ast::Match { match_, expr, braces, arms }) => {
+            let arms: TokenStream = arms
+                .into_iter()
+                .map(|ast::Arm { pat, guard, fat_arrow, braces, body }| {
+                    let guard = guard.map(|(if_, expr)| quote!(#if_ #expr));
+                    let body = emit(config, braces.span.join(), body)?;
+                    Ok(quote_spanned! { braces.span =>
+                        #pat #guard #fat_arrow { #body }
+                    })
+                })
+                .collect::<Result<_>>()?;
+
+            quote_spanned! { braces.span =>
+                { #match_ #expr {
+                    #arms
+                } }
+            }
+        }
+        ast::Stmt::For(for_) => for_to_html(config, for_, true)?,
+        ast::Stmt::Repeat(ast::Repeat { repeat_, count, as_, braces, body }) => {
+            let pat = match as_ {
+                Some((_, ident)) => syn::parse_quote!(#ident),
+                None => syn::parse_quote!(_),
+            };
+            let for_ = ast::For {
+                for_: syn::Token![for](repeat_.span),
+                pat: Box::new(pat),
+                in_: syn::Token![in](repeat_.span),
+                iter: syn::parse_quote_spanned!(count.span() => 0..#count),
+                braces,
+                body,
+                else_: None,
+            };
+            // the items are interchangeable, so keys would not help yew reuse them
+            for_to_html(config, for_, false)?
+        }
+        ast::Stmt::With(ast::With { with_, expr, as_: _, ident, braces, body }) => {
+            let body = emit(config, braces.span.join(), body)?;
+            quote_spanned! { with_.span =>
+                { {
+                    let #ident = #expr;
+                    #body
+                } }
+            }
+        }
+        ast::Stmt::Maybe(ast::Maybe { maybe_, expr, as_: _, ident, braces, body, else_ }) => {
+            let body = emit(config, braces.span.join(), body)?;
+            let else_body = match else_ {
+                Some(ast::Else { else_: _, braces, body }) => {
+                    emit(config, braces.span.join(), body)?
+                }
+                None => quote!(#macro_path! {}),
+            };
+            quote_spanned! { maybe_.span =>
+                { if let ::std::option::Option::Some(#ident) = #expr { #body } else { #else_body } }
+            }
+        }
+        ast::Stmt::Form(form) => form::to_html(config, form)?,
+        ast::Stmt::Table(ast::Table { table_, args, for_, pat, in_, iter, braces, columns }) => {
+            let mut headers = Vec::new();
+            let mut cells = Vec::new();
+            for ast::Column { column_, header, braces, body } in columns {
+                headers.push(quote_spanned! { column_.span =>
+                    <th scope="col">{ #header }</th>
+                });
+                cells.push(ast::Stmt::Node(ast::Node {
+                    attrs:   Vec::new(),
+                    element: syn::parse_quote_spanned!(column_.span => td),
+                    args:    ast::NodeArgs::None,
+                    body:    ast::NodeBody::Braced { braces, children: body },
+                }));
+            }
+            let row = ast::Stmt::Node(ast::Node {
+                attrs:   Vec::new(),
+                element: syn::parse_quote_spanned!(for_.span => tr),
+                args:    ast::NodeArgs::None,
+                body:    ast::NodeBody::Braced { braces, children: ast::Nodes { stmts: cells } },
+            });
+            let body = ast::Nodes { stmts: vec![row] };
+            // the rows cannot be keyed, so the unkeyed list warning would not be actionable
+            let rows = for_to_html(
+                config,
+                ast::For { for_, pat, in_, iter, braces, body, else_: None },
+                false,
+            )?;
+            let args = args_to_html(config, args, true)?;
+            quote_spanned! { table_.span =>
+                <table #args>
+                    <thead><tr>#(#headers)*</tr></thead>
+                    <tbody>#rows</tbody>
+                </table>
+            }
+        }
+        ast::Stmt::WrapIf(ast::WrapIf { wrap_if_, expr, node }) => {
+            let mut node = *node;
+            let children = ast::Nodes {
+                stmts: vec![ast::Stmt::Text(ast::Text {
+                    add:  syn::Token![+](wrap_if_.span),
+                    expr: Box::new(syn::parse_quote_spanned!(wrap_if_.span => __defy_children)),
+                    semi: syn::Token![;](wrap_if_.span),
+                })],
+            };
+            let body = match &mut node.body {
+                ast::NodeBody::Braced { braces, children: body } => {
+                    emit(config, braces.span.join(), mem::replace(body, children))?
+                }
+                ast::NodeBody::Semi(_) => unreachable!("rejected by the parser"),
+            };
+            let wrapper = stmt_to_html(config, ast::Stmt::Node(node))?;
+            quote_spanned! { wrap_if_.span =>
+                { {
+                    let __defy_children = #body;
+                    if #expr { #macro_path! { #wrapper } } else { __defy_children }
+                } }
+            }
+        }
+        ast::Stmt::Transition(ast::Transition {
+            transition_,
+            paren: _,
+            args,
+            if_: _,
+            cond,
+            braces,
+            body,
+        }) => {
+            let [enter, leave, duration] =
+                block_args("transition", &args, ["enter", "leave", "duration"])?;
+            let class = |value: Option<&syn::Expr>| match value {
+                Some(value) => quote_spanned! { value.span() =>
+                    ::std::option::Option::Some(::yew::AttrValue::from(#value))
+                },
+                None => quote!(::std::option::Option::None),
+            };
+            let (enter, leave) = (class(enter), class(leave));
+            let duration = duration.map_or(quote!(500), ToTokens::to_token_stream);
+            let body = emit(config, braces.span.join(), body)?;
+            quote_spanned! { transition_.span =>
+                { ::defy::__private::transition(
+                    if #cond { ::std::option::Option::Some(#body) } else { ::std::option::Option::None },
+                    #enter,
+                    #leave,
+                    #duration,
+                ) }
+            }
+        }
+        ast::Stmt::VirtualFor(ast::VirtualFor {
+            virtual_,
+            for_: _,
+            pat,
+            in_: _,
+            iter,
+            paren: _,
+            args,
+            braces,
+            body,
+        }) => {
+            let [height, viewport] = block_args("virtual for", &args, ["height", "viewport"])?;
+            let (Some(height), Some(viewport)) = (height, viewport) else {
+                return Err(Error::new(
+                    virtual_.span,
+                    "DEFY0033: `virtual for` requires `height` and `viewport` arguments",
+                ));
+            };
+            let body = emit_closure(config, braces.span.join(), body)?;
+            quote_spanned! { virtual_.span =>
+                { ::defy::__private::virtual_list(#iter, #height, #viewport, move |#pat| #body) }
+            }
+        }
+        ast::Stmt::Do(ast::Do { do_, expr, semi }) => {
+            quote_spanned! { do_.span =>
+                { {
+                    #expr #semi
+                    #macro_path! {}
+                } }
+            }
+        }
+        ast::Stmt::Note(note) => {
+            if !config.emit_notes {
+                return Ok(TokenStream::new());
+            }
+            // `--` cannot appear in HTML comments
+            let comment = format!("<!-- {} -->", note.text().replace("--", "- -"));
+            quote_spanned! { note.span() =>
+                { ::yew::Html::from_html_unchecked(::yew::AttrValue::from(#comment)) }
+            }
+        }
+        ast::Stmt::Attributed(ast::Attributed { attrs, stmt }) => {
+            let html = stmt_to_html(config, *stmt)?;
+            with_attrs(config, attrs, html)?
+        }
+        ast::Stmt::Break(ast::Break { break_, semi: _ }) => loop_control(config, break_)?,
+        ast::Stmt::Continue(ast::Continue { continue_, semi: _ }) => {
+            loop_control(config, continue_)?
+        }
+        ast::Stmt::Let(ast::Let { let_, .. }) => {
+            return Err(Error::new_spanned(
+                let_,
+                "DEFY0001: let statements must precede all other statements in a block",
+            ))
+        }
+        ast::Stmt::Item(_) => unreachable!("items are emitted by emit_block"),
+        ast::Stmt::Text(ast::Text { add, expr, semi: _ }) => {
+            let expr = substitute_theme_tokens(config, *expr)?;
+            quote_spanned! { add.span =>
+                { ::defy::__private::text(#expr) }
+            }
+        }
+        ast::Stmt::Dyn(ast::Dyn { dyn_, expr, semi: _ }) => {
+            let (component, props) = dyn_props(*expr)?;
+            quote_spanned! { dyn_.span =>
+                { ::defy::dynamic::DynComponent::render(&(#component), #props) }
+            }
+        }
+        ast::Stmt::Embed(ast::Embed { embed_: _, expr, semi: _ }) => {
+            quote_spanned! { expr.span() =>
+                { ::defy::__private::embed(#expr) }
+            }
+        }
+        ast::Stmt::Include(ast::Include { include_, path, semi: _ }) => {
+            let (path, contents) = read_source_file(&path)?;
+            let nodes: ast::Nodes = syn::parse_str(&contents).map_err(|err| {
+                Error::new(err.span(), format_args!("in {}: {err}", path.display()))
+            })?;
+            let body = emit(config, include_.span, nodes)?;
+
+            let path = path.to_string_lossy();
+            quote_spanned! { include_.span =>
+                { {
+                    const _: &str = ::std::include_str!(#path);
+                    #body
+                } }
+            }
+        }
+        ast::Stmt::Markdown(ast::Markdown { markdown_, source, semi: _ }) => {
+            markdown_to_html(config, markdown_, source)?
+        }
+        ast::Stmt::Translate(ast::Translate { t, id, args, semi: _ }) => {
+            let Some(lookup) = &config.i18n else {
+                return Err(Error::new(
+                    t.span,
+                    "DEFY0006: `t` statements require a lookup function specified with `@i18n`",
+                ));
+            };
+            if let Some(messages) = &config.i18n_messages {
+                if !messages.contains(&id.value()) {
+                    return Err(Error::new_spanned(
+                        &id,
+                        format_args!(
+                            "DEFY0007: message {:?} is not defined in the i18n resources",
+                            id.value()
+                        ),
+                    ));
+                }
+            }
+
+            let args = args.into_iter().flat_map(|(_, args)| args).map(
+                |ast::TranslateArg { name, eq, value }| {
+                    let name = name.to_string();
+                    quote_spanned! { eq.span =>
+                        (#name, ::std::string::ToString::to_string(&#value))
+                    }
+                },
+            );
+            quote_spanned! { t.span =>
+                { #lookup(#id, &[#(#args),*]) }
+            }
+        }
+        ast::Stmt::Picture(picture) => picture::to_html(config, picture)?,
+        ast::Stmt::Og(og) => og::to_html(config, og)?,
+        ast::Stmt::DocumentHead(head) => head_to_html(config, head)?,
+        ast::Stmt::Verbatim(ast::Verbatim { verbatim_: _, text, semi: _ }) => {
+            let text = syn::LitStr::new(&dedent(&text.value()), text.span());
+            quote_spanned! { text.span() => { #text } }
+        }
+        ast::Stmt::Raw(raw) => raw::to_html(raw)?,
+        ast::Stmt::Passthrough(ast::Passthrough { passthrough_, braces: _, tokens }) => {
+            quote_spanned! { passthrough_.span => { #macro_path! { <>#tokens</> } } }
+        }
+        ast::Stmt::Space(ast::Space { space_, semi: _ }) => {
+            quote_spanned! { space_.span => { " " } }
+        }
+        ast::Stmt::Entity(entity) => {
+            let name = entity.name();
+            let Some(ch) = html::entity(&name) else {
+                return Err(Error::new_spanned(
+                    entity,
+                    format_args!("DEFY0028: unknown character reference `&{name};`"),
+                ));
+            };
+            let text = syn::LitStr::new(&ch.to_string(), entity.span());
+            quote_spanned! { entity.span() => { #text } }
+        }
+        ast::Stmt::JsonScript(json_script) => json_script_to_html(config, json_script)?,
+        ast::Stmt::Island(island) => island_to_html(island)?,
+        ast::Stmt::Lazy(lazy) => lazy_to_html(config, lazy)?,
+        ast::Stmt::XmlDecl(decl) => xml::decl_to_html(decl)?,
+        ast::Stmt::Block(ast::Block { block_, name, braces, body }) => {
+            let Some(blocks) = &config.blocks else {
+                return Err(Error::new(
+                    block_.span,
+                    "DEFY0036: `block` statements require the blocks of the layout specified with \
+                     `@blocks`",
+                ));
+            };
+            // the default is rendered in a closure, where `break` and `continue` cannot be used
+            let depth = config.loop_depth.replace(0);
+            let body = emit(config, braces.span.join(), body);
+            config.loop_depth.set(depth);
+            let body = body?;
+            let name = name.to_string();
+            quote_spanned! { block_.span =>
+                { ::defy::layout::Blocks::get(&#blocks, #name).unwrap_or_else(|| #body) }
+            }
+        }
+        ast::Stmt::Extend(extend) => extend_to_html(config, extend)?,
+        ast::Stmt::Doctype(doctype) => quote_spanned! { doctype.doctype_.span =>
+            { ::yew::Html::from_html_unchecked(::yew::AttrValue::Static("<!DOCTYPE html>")) }
+        },
+        ast::Stmt::Icon(ast::Icon { icon_, path, args, semi: _ }) => {
+            let overridden = match &args {
+                ast::NodeArgs::None => HashSet::new(),
+                ast::NodeArgs::Named { paren: _, args } => {
+                    args.iter().map(|arg| arg_name(&arg.ident)).collect()
+                }
+                ast::NodeArgs::Rest { eq, arg: _ } => {
+                    return Err(Error::new(eq.span, "DEFY0004: icons do not accept rest arguments"))
+                }
+            };
+            let (file, contents) = read_source_file(&path)?;
+            let svg =
+                icon::to_html(&path, &contents, args_to_html(config, args, true)?, &overridden)?;
+
+            let file = file.to_string_lossy();
+            quote_spanned! { icon_.span =>
+                { {
+                    const _: &str = ::std::include_str!(#file);
+                    #macro_path! { #svg }
+                } }
+            }
+        }
+        ast::Stmt::Style(ast::Style { style_, .. }) => {
+            return Err(Error::new(
+                style_.span,
+                "DEFY0002: style blocks must be placed directly inside the element they apply to",
+            ))
+        }
+        ast::Stmt::Memo(ast::Memo { memo_, paren: _, deps, braces, body }) => {
+            let (names, values) = memo_deps(deps);
+            let body = emit_closure(config, braces.span.join(), body)?;
+            quote_spanned! { memo_.span =>
+                { ::defy::__private::memo(
+                    (#(::std::clone::Clone::clone(&(#values)),)*),
+                    move |(#(#names,)*)| #body,
+                ) }
+            }
+        }
+        ast::Stmt::Once(ast::Once { once_, braces, body }) => {
+            // a memo block without dependencies is never re-rendered
+            let body = emit_closure(config, braces.span.join(), body)?;
+            quote_spanned! { once_.span =>
+                { ::defy::__private::memo((), move |()| #body) }
+            }
+        }
+        ast::Stmt::Defer(ast::Defer { defer_, braces, body, placeholder }) => {
+            let body = emit_closure(config, braces.span.join(), body)?;
+            let placeholder = match placeholder {
+                Some(ast::Placeholder { placeholder_: _, braces, body }) => {
+                    emit(config, braces.span.join(), body)?
+                }
+                None => quote!(#macro_path! {}),
+            };
+            quote_spanned! { defer_.span =>
+                { ::defy::__private::defer(move || #body, #placeholder) }
+            }
+        }
+        ast::Stmt::Await(ast::Await {
+            await_,
+            deps,
+            expr,
+            as_: _,
+            ident,
+            braces,
+            body,
+            pending,
+        }) => {
+            let (names, values) = memo_deps(deps.map(|(_, deps)| deps).unwrap_or_default());
+            let body = emit_closure(config, braces.span.join(), body)?;
+            let pending = match pending {
+                Some(ast::Pending { pending_: _, braces, body }) => {
+                    emit(config, braces.span.join(), body)?
+                }
+                None => quote!(#macro_path! {}),
+            };
+            quote_spanned! { await_.span =>
+                { ::defy::__private::suspend(
+                    (#(::std::clone::Clone::clone(&(#values)),)*),
+                    move |(#(#names,)*)| #expr,
+                    move |#ident| #body,
+                    #pending,
+                ) }
+            }
+        }
+        ast::Stmt::Try(ast::Try { try_, braces, body, catch }) => {
+            let body = emit_closure(config, braces.span.join(), body)?;
+            let (ty, catch) = match catch {
+                Some(ast::Catch { catch_: _, ident, ty, braces, body }) => {
+                    let ty = ty.map(|(_, ty)| ty.into_token_stream());
+                    let body = emit(config, braces.span.join(), body)?;
+                    (ty, quote!(::std::result::Result::Err(#ident) => #body))
+                }
+                None => (None, quote!(::std::result::Result::Err(_) => #macro_path! {})),
+            };
+            let ty = ty.unwrap_or_else(|| quote!(::std::boxed::Box<dyn ::std::error::Error>));
+            quote_spanned! { try_.span =>
+                { match ::defy::__private::try_render::<#ty>(|| {
+                    let html = #body;
+                    ::std::result::Result::Ok(html)
+                }) {
+                    ::std::result::Result::Ok(html) => html,
+                    #catch,
+                } }
+            }
+        }
+        ast::Stmt::Options(options) => options_to_html(config, options)?,
+        ast::Stmt::Node(ast::Node { attrs, element, args, body }) => {
+            let attrs = config.lints.borrow_mut().push_scope(attrs);
+            let select_value = element.is_ident("select").then(|| select_value(&args)).flatten();
+            let select_value = select_value.map(|value| config.select_value.replace(Some(value)));
+            let output = node_to_html(config, element, args, body);
+            if let Some(outer) = select_value {
+                config.select_value.replace(outer);
+            }
+            config.lints.borrow_mut().pop_scope();
+            if attrs.is_empty() {
+                output?
+            } else {
+                with_attrs(config, attrs, output?)?
+            }
+        }
+    })
+}
+
+/// The expression of the current value from the `bind:value` or `value` argument of a `select`.
+fn select_value(args: &ast::NodeArgs) -> Option<TokenStream> {
+    let ast::NodeArgs::Named { args, .. } = args else { return None };
+    args.iter().find_map(|arg| match &arg.value {
+        Some((_, value)) if arg_name(&arg.ident) == "value" => Some(match arg.bind {
+            Some(_) => quote!(*#value),
+            None => quote!(#value),
+        }),
+        _ => None,
+    })
+}
+
+/// Renders an `option` element for each item of `options from`,
+/// which is `selected` if its value is the value of the enclosing `select`.
+fn options_to_html(config: &Config, options: ast::Options) -> Result<TokenStream> {
+    let macro_path = &config.macro_path;
+    let value = options.value().expect("checked by the parser").clone();
+    let ast::Options { options_, from_: _, iter, braces: _, or1: _, pat, or2: _, mut args, body } =
+        options;
+
+    let current = config.select_value.borrow().clone();
+    let selected = current.map(|current| {
+        let ast::NodeArgs::Named { args, .. } = &mut args else { unreachable!() };
+        if !args.iter().any(|arg| arg_name(&arg.ident) == "selected") {
+            args.push(ast::NodeArg {
+                bind:      None,
+                at:        None,
+                prefix:    None,
+                ident:     std::iter::once(syn::Ident::new("selected", options_.span)).collect(),
+                keys:      None,
+                modifiers: Vec::new(),
+                value:     Some((
+                    Default::default(),
+                    syn::parse_quote_spanned!(options_.span => __defy_selected),
+                )),
+            });
+        }
+        quote_spanned! { options_.span =>
+            let __defy_selected = ::defy::__private::is_selected(&(#current), &(#value));
+        }
+    });
+
+    let option = stmt_to_html(
+        config,
+        ast::Stmt::Node(ast::Node {
+            attrs: Vec::new(),
+            element: syn::parse_quote_spanned!(options_.span => option),
+            args,
+            body,
+        }),
+    )?;
+
+    Ok(quote_spanned! { options_.span =>
+        { for ::std::iter::IntoIterator::into_iter(#iter).map(|#pat| {
+            #selected
+            #macro_path! { #option }
+        }) }
+    })
+}
+
+/// Forwards outer attributes to a statement evaluating to the HTML.
+///
+/// The statement is replaced by an empty node if `cfg` attributes exclude it.
+fn with_attrs(
+    config: &Config,
+    attrs: Vec<syn::Attribute>,
+    html: TokenStream,
+) -> Result<TokenStream> {
+    let macro_path = &config.macro_path;
+    let preds =
+        attrs.iter().filter(|attr| attr.path().is_ident("cfg")).map(|attr| match &attr.meta {
+            syn::Meta::List(list) => Ok(list.tokens.clone()),
+            _ => Err(Error::new_spanned(attr, "expected `#[cfg(...)]`")),
+        });
+    let preds = preds.collect::<Result<Vec<_>>>()?;
+    let fallback = (!preds.is_empty()).then(|| {
+        quote! {
+            #[cfg(not(all(#(#preds),*)))]
+            let __defy_html = #macro_path! {};
+        }
+    });
+    Ok(quote! {
+        { {
+            #(#attrs)*
+            let __defy_html = #macro_path! { #html };
+            #fallback
+            __defy_html
+        } }
+    })
+}
+
+fn node_to_html(
+    config: &Config,
+    element: syn::Path,
+    mut args: ast::NodeArgs,
+    body: ast::NodeBody,
+) -> Result<TokenStream> {
+    if config.xml {
+        return xml::node_to_html(config, element, args, body);
+    }
+    let passive = passive_listeners(config, &element, &mut args)?;
+    // rewritten before the checks, which see `@click` as `onclick`
+    if let ast::NodeArgs::Named { paren: _, args: named } = &mut args {
+        *named = mem::take(named)
+            .into_iter()
+            .map(|arg| event_arg(config, arg))
+            .collect::<Result<_>>()?;
+    }
+    let testid = add_testid(config, &element, &mut args)?;
+    config.testid_scope.borrow_mut().push(testid);
+    let html = checked_node_to_html(config, element, args, body);
+    config.testid_scope.borrow_mut().pop();
+    if passive.is_empty() {
+        return html;
+    }
+
+    let macro_path = &config.macro_path;
+    let html = html?;
+    Ok(quote! {
+        { {
+            let mut __defy_node: ::yew::Html = #macro_path! { #html };
+            #(#passive)*
+            __defy_node
+        } }
+    })
+}
+
+fn checked_node_to_html(
+    config: &Config,
+    element: syn::Path,
+    mut args: ast::NodeArgs,
+    mut body: ast::NodeBody,
+) -> Result<TokenStream> {
+    let mut warnings = Vec::new();
+    lint::check_node(&element, &args, &mut warnings);
+    config.lints.borrow_mut().report(warnings);
+    if config.debug_loc {
+        add_debug_loc(&element, &mut args)?;
+    }
+    if let (Some(ident), ast::NodeBody::Braced { braces, .. }) = (element.get_ident(), &body) {
+        if html::VOID_ELEMENTS.contains(&ident.to_string().as_str()) {
+            return Err(Error::new(
+                braces.span.join(),
+                format_args!(
+                    "DEFY0003: `<{ident}>` is a void element and cannot have children; write \
+                     `{ident}(...);` instead"
+                ),
+            ));
+        }
+    }
+
+    let mut styles = Vec::new();
+    if let ast::NodeBody::Braced { children, .. } = &mut body {
+        let (style_stmts, stmts) =
+            children.stmts.drain(..).partition(|stmt| matches!(stmt, ast::Stmt::Style(_)));
+        children.stmts = stmts;
+        styles = style_stmts;
+    }
+    if !styles.is_empty() {
+        return style_node(config, element, args, body, styles);
+    }
+
+    emit_node(config, element, args, body)
+}
+
+/// Splits the expression of a `dyn` statement into the component and its props.
+///
+/// `component(props = value)` passes `value`, and other expressions are passed `()`.
+fn dyn_props(expr: syn::Expr) -> Result<(syn::Expr, TokenStream)> {
+    let syn::Expr::Call(call) = expr else { return Ok((expr, quote!(()))) };
+    let is_named = |arg: &syn::Expr| matches!(arg, syn::Expr::Assign(_));
+    if !call.args.iter().any(is_named) {
+        // e.g. `dyn registry.get(kind);`
+        return Ok((syn::Expr::Call(call), quote!(())));
+    }
+    let mut args = call.args.into_iter();
+    let (Some(syn::Expr::Assign(assign)), None) = (args.next(), args.next()) else {
+        return Err(Error::new(
+            call.paren_token.span.join(),
+            "DEFY0038: `dyn` takes a single `props = value` argument, e.g. `dyn component(props = \
+             props);`",
+        ));
+    };
+    let syn::Expr::Path(syn::ExprPath { path, .. }) = &*assign.left else {
+        return Err(Error::new_spanned(&assign.left, "DEFY0038: expected `props = value`"));
+    };
+    if !path.is_ident("props") {
+        return Err(Error::new_spanned(path, "DEFY0038: expected `props = value`"));
+    }
+    Ok((*call.func, assign.right.to_token_stream()))
+}
+
+/// Converts `extend layout { block name { ... } ... }` into a call to the layout function.
+fn extend_to_html(config: &Config, extend: ast::Extend) -> Result<TokenStream> {
+    let ast::Extend { extend_, layout, braces: _, blocks } = extend;
+    let mut names = Vec::new();
+    let mut overrides = Vec::new();
+    for stmt in blocks.stmts {
+        let ast::Stmt::Block(ast::Block { block_, name, braces, body }) = stmt else {
+            return Err(Error::new(
+                stmt.span(),
+                "DEFY0036: `extend` can only contain `block name { ... }` statements",
+            ));
+        };
+        let key = name.to_string();
+        if names.contains(&key) {
+            return Err(Error::new(
+                name.span(),
+                format_args!("DEFY0036: duplicate block `{key}` in `extend`"),
+            ));
+        }
+        names.push(key.clone());
+        // the overrides are rendered before the layout, outside the enclosing loop
+        let depth = config.loop_depth.replace(0);
+        let body = emit(config, braces.span.join(), body);
+        config.loop_depth.set(depth);
+        let body = body?;
+        overrides.push(quote_spanned! { block_.span => .with(#key, #body) });
+    }
+    Ok(quote_spanned! { extend_.span =>
+        { #layout(::defy::layout::Blocks::new() #(#overrides)*) }
+    })
+}
+
+/// Emits a node after its checks and statement-level rewrites have been applied.
+fn emit_node(
+    config: &Config,
+    element: syn::Path,
+    args: ast::NodeArgs,
+    body: ast::NodeBody,
+) -> Result<TokenStream> {
+    let nonce = csp_nonce(config, &element, &args);
+    let html_element = element
+        .get_ident()
+        .is_some_and(|ident| ident.to_string().starts_with(|ch: char| ch.is_ascii_lowercase()));
+    let args = args_to_html(config, args, html_element)?;
+    let args = quote!(#args #nonce);
+    Ok(match body {
+        ast::NodeBody::Semi(semi) => quote_spanned! { semi.span =>
+            <#element #args />
+        },
+        ast::NodeBody::Braced { braces, children } => {
+            let children = emit(config, braces.span.join(), children)?;
+            quote_spanned! { braces.span =>
+                <#element #args>
+                    { #children }
+                </#element>
+            }
+        }
+    })
+}
+
+/// Emits a node with stylist-scoped styles applied to its `class` attribute.
+fn style_node(
+    config: &Config,
+    element: syn::Path,
+    mut args: ast::NodeArgs,
+    body: ast::NodeBody,
+    styles: Vec<ast::Stmt>,
+) -> Result<TokenStream> {
+    let macro_path = &config.macro_path;
+
+    let mut style_defs = Vec::new();
+    let mut style_classes = Vec::new();
+    for (i, stmt) in styles.into_iter().enumerate() {
+        let ast::Stmt::Style(ast::Style { style_, braces: _, css }) = stmt else { unreachable!() };
+        let var = quote::format_ident!("__defy_style_{i}", span = style_.span);
+        style_defs.push(quote_spanned! { css.span() =>
+            let #var = ::stylist::Style::new(::stylist::css!(#css)).expect("invalid style block");
+        });
+        style_classes.push(quote!(#var.get_class_name().to_string()));
+    }
+
+    let class_arg = match &mut args {
+        ast::NodeArgs::None => {
+            args = ast::NodeArgs::Named { paren: Default::default(), args: Punctuated::new() };
+            None
+        }
+        ast::NodeArgs::Named { paren: _, args } => {
+            args.iter_mut().find(|arg| arg_name(&arg.ident) == "class")
+        }
+        ast::NodeArgs::Rest { eq, arg: _ } => {
+            return Err(Error::new(
+                eq.span,
+                "DEFY0005: style blocks cannot be applied to rest arguments",
+            ))
+        }
+    };
+    match class_arg {
+        Some(arg) => {
+            let class = match arg.value.take() {
+                Some((_, value)) => quote!(#value),
+                None => arg.ident.to_token_stream(),
+            };
+            let value = syn::parse2(quote!(::yew::classes!(#class, #(#style_classes),*)))?;
+            arg.value = Some((Default::default(), value));
+        }
+        None => {
+            let ast::NodeArgs::Named { args: named, .. } = &mut args else { unreachable!() };
+            named.push(ast::NodeArg {
+                bind:      None,
+                at:        None,
+                prefix:    None,
+                ident:     std::iter::once(syn::Ident::new("class", Span::call_site())).collect(),
+                keys:      None,
+                modifiers: Vec::new(),
+                value:     Some((
+                    Default::default(),
+                    syn::parse2(quote!(::yew::classes!(#(#style_classes),*)))?,
+                )),
+            });
+        }
+    }
+
+    let node = emit_node(config, element, args, body)?;
+    Ok(quote! {
+        { {
+            #(#style_defs)*
+            #macro_path! { #node }
+        } }
+    })
+}
+
+/// Adds a `data-defy-loc` attribute with the source location to an HTML element in debug builds.
+fn add_debug_loc(element: &syn::Path, args: &mut ast::NodeArgs) -> Result<()> {
+    let Some(ident) = element.get_ident() else { return Ok(()) };
+    if !ident.to_string().starts_with(|ch: char| ch.is_ascii_lowercase()) {
+        return Ok(());
+    }
+
+    let start = ident.span().start();
+    let loc = format!("{}:{}", ident.span().file(), start.line);
+    let value = syn::parse2(quote! {
+        if ::std::cfg!(debug_assertions) { ::std::option::Option::Some(#loc) } else { ::std::option::Option::None }
+    })?;
+    let arg = ast::NodeArg {
+        bind:      None,
+        at:        None,
+        prefix:    None,
+        ident:     ["data", "defy", "loc"]
+            .map(|part| syn::Ident::new(part, ident.span()))
+            .into_iter()
+            .collect(),
+        keys:      None,
+        modifiers: Vec::new(),
+        value:     Some((Default::default(), value)),
+    };
+    match args {
+        ast::NodeArgs::None => {
+            *args = ast::NodeArgs::Named {
+                paren: Default::default(),
+                args:  [arg].into_iter().collect(),
+            };
+        }
+        ast::NodeArgs::Named { paren: _, args } => args.push(arg),
+        ast::NodeArgs::Rest { .. } => {}
+    }
+    Ok(())
+}
+
+/// Replaces the `testid` argument of an HTML element with a `data-testid` attribute
+/// that is only rendered in test and debug builds.
+///
+/// With `@testids`, elements without a `testid` are given one
+/// derived from the names of the element and its ancestors, e.g. `login-form-button`.
+/// Returns the test ID from which the test IDs of children are derived.
+fn add_testid(config: &Config, element: &syn::Path, args: &mut ast::NodeArgs) -> Result<String> {
+    let name = element
+        .segments
+        .last()
+        .map_or_else(String::new, |segment| kebab_case(&segment.ident.to_string()));
+    let derived = match config.testid_scope.borrow().last() {
+        Some(parent) if !parent.is_empty() => format!("{parent}-{name}"),
+        _ => name,
+    };
+    let Some(ident) = element.get_ident() else { return Ok(derived) };
+    if !ident.to_string().starts_with(|ch: char| ch.is_ascii_lowercase()) {
+        return Ok(derived);
+    }
+
+    let mut explicit = None;
+    if let ast::NodeArgs::Named { paren: _, args } = args {
+        let mut rest = Punctuated::new();
+        for arg in mem::take(args) {
+            if arg_name(&arg.ident) == "testid" && arg.bind.is_none() && explicit.is_none() {
+                explicit = Some(arg);
+            } else {
+                rest.push(arg);
+            }
+        }
+        *args = rest;
+    }
+    let (testid, value) = match explicit {
+        Some(arg) => {
+            let Some((_, value)) = arg.value else {
+                return Err(Error::new_spanned(
+                    &arg.ident,
+                    "DEFY0029: `testid` requires a value, e.g. `testid = \"submit\"`",
+                ));
+            };
+            let testid = match &*value {
+                syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) => lit.value(),
+                _ => derived,
+            };
+            (testid, value)
+        }
+        None if config.testids => {
+            let value = syn::parse2(quote_spanned!(ident.span() => #derived))?;
+            (derived, Box::new(value))
+        }
+        None => return Ok(derived),
+    };
+
+    let value = syn::parse2(quote_spanned! { value.span() =>
+        if ::std::cfg!(any(test, debug_assertions)) {
+            ::std::option::Option::Some(#value)
+        } else {
+            ::std::option::Option::None
+        }
+    })?;
+    let arg = ast::NodeArg {
+        bind:      None,
+        at:        None,
+        prefix:    None,
+        ident:     ["data", "testid"]
+            .map(|part| syn::Ident::new(part, ident.span()))
+            .into_iter()
+            .collect(),
+        keys:      None,
+        modifiers: Vec::new(),
+        value:     Some((Default::default(), value)),
+    };
+    match args {
+        ast::NodeArgs::None => {
+            *args = ast::NodeArgs::Named {
+                paren: Default::default(),
+                args:  [arg].into_iter().collect(),
+            };
+        }
+        ast::NodeArgs::Named { paren: _, args } => args.push(arg),
+        ast::NodeArgs::Rest { .. } => {}
+    }
+    Ok(testid)
+}
+
+/// Converts a component name like `UserCard` to `user-card`.
+fn kebab_case(name: &str) -> String {
+    let mut output = String::new();
+    for (index, ch) in name.trim_start_matches("r#").char_indices() {
+        if ch.is_ascii_uppercase() {
+            if index > 0 {
+                output.push('-');
+            }
+            output.push(ch.to_ascii_lowercase());
+        } else if ch == '_' {
+            output.push('-');
+        } else {
+            output.push(ch);
+        }
+    }
+    output
+}
+
+/// Returns the attribute name of a node argument as written in HTML.
+fn arg_name(ident: &Punctuated<syn::Ident, syn::Token![-]>) -> String {
+    ident.iter().map(|ident| ident.to_string()).collect::<Vec<_>>().join("-")
+}
+
+#[cfg_attr(not(feature = "markdown"), allow(unused_variables))]
+fn markdown_to_html(
+    config: &Config,
+    markdown_: ast::kw::markdown,
+    source: ast::MarkdownSource,
+) -> Result<TokenStream> {
+    match source {
+        #[cfg(feature = "markdown")]
+        ast::MarkdownSource::File(lit) => {
+            let (path, contents) = read_source_file(&lit)?;
+
+            let mut html = String::new();
+            pulldown_cmark::html::push_html(&mut html, pulldown_cmark::Parser::new(&contents));
+            if config.minify {
+                html = minify::minify(&html);
+            }
+
+            let path = path.to_string_lossy();
+            Ok(quote_spanned! { lit.span() =>
+                { {
+                    const _: &str = ::std::include_str!(#path);
+                    ::yew::Html::from_html_unchecked(::yew::AttrValue::Static(#html))
+                } }
+            })
+        }
+        #[cfg(not(feature = "markdown"))]
+        ast::MarkdownSource::File(lit) => Err(Error::new_spanned(
+            lit,
+            "DEFY0008: markdown files can only be included with the `markdown` feature of defy",
+        )),
+        #[cfg(feature = "markdown-runtime")]
+        ast::MarkdownSource::Expr(expr) => Ok(quote_spanned! { markdown_.span =>
+            { {
+                let mut html = ::std::string::String::new();
+                ::pulldown_cmark::html::push_html(&mut html, ::pulldown_cmark::Parser::new(&#expr));
+                ::yew::Html::from_html_unchecked(::yew::AttrValue::from(html))
+            } }
+        }),
+        #[cfg(not(feature = "markdown-runtime"))]
+        ast::MarkdownSource::Expr(expr) => Err(Error::new(
+            markdown_.span.join(expr.span()).unwrap_or(markdown_.span),
+            "DEFY0009: dynamic markdown requires the `markdown-runtime` feature of defy; use a \
+             string literal to include a markdown file at compile time",
+        )),
+    }
+}
+
+/// The `nonce` attribute from `@csp_nonce` for a `script` or `style` element
+/// that does not have one yet.
+fn csp_nonce(config: &Config, element: &syn::Path, args: &ast::NodeArgs) -> Option<TokenStream> {
+    let nonce = config.csp_nonce.as_ref()?;
+    if !(element.is_ident("script") || element.is_ident("style")) {
+        return None;
+    }
+    match args {
+        ast::NodeArgs::Named { paren: _, args }
+            if args.iter().any(|arg| arg_name(&arg.ident) == "nonce") =>
+        {
+            None
+        }
+        ast::NodeArgs::Rest { .. } => None,
+        _ => Some(quote_spanned! { nonce.span() =>
+            nonce={::std::clone::Clone::clone(&(#nonce))}
+        }),
+    }
+}
+
+/// Removes the line break after the opening quote of a `verbatim` literal,
+/// the indentation before the closing quote, and the indentation common to all lines.
+fn dedent(text: &str) -> String {
+    let text = text.strip_prefix('\n').unwrap_or(text);
+    let text = text.trim_end_matches([' ', '\t']);
+    let indent = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start_matches([' ', '\t']).len())
+        .min()
+        .unwrap_or(0);
+    let lines: Vec<_> = text.split('\n').map(|line| line.get(indent..).unwrap_or("")).collect();
+    lines.join("\n")
+}
+
+/// Renders the contents of a `document_head` block into the document head.
+///
+/// A `title` with only text children sets the document title
+/// instead of rendering a `<title>` element.
+fn head_to_html(config: &Config, head: ast::DocumentHead) -> Result<TokenStream> {
+    let ast::DocumentHead { document_head_, braces, body } = head;
+    let mut title = None;
+    let mut stmts = Vec::new();
+    for stmt in body.stmts {
+        match stmt {
+            ast::Stmt::Node(ast::Node {
+                attrs,
+                element,
+                args: ast::NodeArgs::None,
+                body: ast::NodeBody::Braced { braces, children },
+            }) if attrs.is_empty()
+                && element.is_ident("title")
+                && children.stmts.iter().all(|stmt| matches!(stmt, ast::Stmt::Text(_))) =>
+            {
+                let texts = children.stmts.into_iter().map(|stmt| match stmt {
+                    ast::Stmt::Text(ast::Text { add, expr, semi: _ }) => {
+                        quote_spanned! { add.span =>
+                            __defy_title.push_str(&::std::string::ToString::to_string(&(#expr)));
+                        }
+                    }
+                    _ => unreachable!("checked above"),
+                });
+                title = Some(quote_spanned! { braces.span.join() =>
+                    ::std::option::Option::Some({
+                        let mut __defy_title = ::std::string::String::new();
+                        #(#texts)*
+                        __defy_title
+                    })
+                });
+            }
+            stmt => stmts.push(stmt),
+        }
+    }
+    let title = title
+        .unwrap_or_else(|| quote_spanned! { document_head_.span => ::std::option::Option::None });
+    let body = emit(config, braces.span.join(), ast::Nodes { stmts })?;
+    Ok(quote_spanned! { document_head_.span =>
+        { ::defy::__private::head(#title, #body) }
+    })
+}
+
+#[cfg(feature = "json")]
+fn json_script_to_html(config: &Config, json_script: ast::JsonScript) -> Result<TokenStream> {
+    let ast::JsonScript { json_script_, args, eq: _, expr, semi: _ } = json_script;
+    let macro_path = &config.macro_path;
+    let script = syn::Path::from(syn::Ident::new("script", json_script_.span));
+    let nonce = csp_nonce(config, &script, &args);
+    let args = args_to_html(config, args, true)?;
+    Ok(quote_spanned! { json_script_.span =>
+        { #macro_path! {
+            <script type="application/json" #args #nonce>
+                { ::defy::__private::json_script(&#expr) }
+            </script>
+        } }
+    })
+}
+
+#[cfg(not(feature = "json"))]
+fn json_script_to_html(_: &Config, json_script: ast::JsonScript) -> Result<TokenStream> {
+    Err(Error::new(
+        json_script.json_script_.span,
+        "DEFY0026: `json_script` requires the `json` feature of defy",
+    ))
+}
+
+#[cfg(feature = "json")]
+fn island_to_html(island: ast::Island) -> Result<TokenStream> {
+    let ast::Island { island_, node } = island;
+    let ast::Node { attrs: _, element, args, body } = *node;
+    if element.get_ident().is_some_and(|ident| ident.to_string().starts_with(char::is_lowercase)) {
+        return Err(Error::new_spanned(
+            element,
+            "DEFY0034: `island` requires a component, e.g. `island Counter(start = 1);`",
+        ));
+    }
+    if let ast::NodeBody::Braced { braces, .. } = body {
+        return Err(Error::new(
+            braces.span.join(),
+            "DEFY0034: the component of an `island` cannot have children, since they cannot be \
+             sent to the client",
+        ));
+    }
+
+    let ty = quote!(<#element as ::yew::html::BaseComponent>::Properties);
+    let props = match args {
+        ast::NodeArgs::None => quote!(::yew::props!(#ty {})),
+        ast::NodeArgs::Rest { eq: _, arg } => arg.into_token_stream(),
+        ast::NodeArgs::Named { paren: _, args } => {
+            let fields = args
+                .into_iter()
+                .map(|arg| match arg {
+                    ast::NodeArg {
+                        bind: None,
+                        at: None,
+                        prefix: None,
+                        ident,
+                        keys: None,
+                        modifiers,
+                        value: Some((_, value)),
+                    } if ident.len() == 1 && modifiers.is_empty() => {
+                        let ident = &ident[0];
+                        Ok(quote!(#ident: #value))
+                    }
+                    arg => Err(Error::new_spanned(
+                        &arg.ident,
+                        "DEFY0034: the arguments of an `island` must be `name = value` props",
+                    )),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            quote!(::yew::props!(#ty { #(#fields),* }))
+        }
+    };
+    Ok(quote_spanned! { island_.span =>
+        { ::defy::__private::island::<#element>(#props) }
+    })
+}
+
+#[cfg(not(feature = "json"))]
+fn island_to_html(island: ast::Island) -> Result<TokenStream> {
+    Err(Error::new(island.island_.span, "DEFY0034: `island` requires the `json` feature of defy"))
+}
+
+/// Converts `lazy Component(...) { fallback { ... } }`
+/// into the component rendered in a `Suspense` after `defy::lazy::load` completes.
+fn lazy_to_html(config: &Config, lazy: ast::Lazy) -> Result<TokenStream> {
+    let macro_path = &config.macro_path;
+    let ast::Lazy { lazy_, node } = lazy;
+    let ast::Node { attrs, element, args, body } = *node;
+    if element.get_ident().is_some_and(|ident| ident.to_string().starts_with(char::is_lowercase)) {
+        return Err(Error::new_spanned(
+            element,
+            "DEFY0039: `lazy` requires a component, e.g. `lazy Settings { fallback { spinner; } }`",
+        ));
+    }
+    let fallback = match body {
+        ast::NodeBody::Semi(_) => None,
+        ast::NodeBody::Braced { braces, children } => {
+            let mut stmts = children.stmts.into_iter();
+            match (stmts.next(), stmts.next()) {
+                (None, _) => None,
+                (
+                    Some(ast::Stmt::Node(ast::Node {
+                        attrs: _,
+                        element: fallback,
+                        args: ast::NodeArgs::None,
+                        body: ast::NodeBody::Braced { braces, children },
+                    })),
+                    None,
+                ) if fallback.is_ident("fallback") => {
+                    Some(emit(config, braces.span.join(), children)?)
+                }
+                _ => {
+                    return Err(Error::new(
+                        braces.span.join(),
+                        "DEFY0039: the body of `lazy` can only contain a `fallback { ... }` \
+                         block, since the component cannot have children",
+                    ))
+                }
+            }
+        }
+    };
+    let fallback = fallback.unwrap_or_else(|| quote!(#macro_path! {}));
+
+    let ty = element.clone();
+    let node =
+        ast::Node { attrs, element, args, body: ast::NodeBody::Semi(syn::Token![;](lazy_.span)) };
+    // the component is rendered in a closure, where `break` and `continue` cannot be used
+    let depth = config.loop_depth.replace(0);
+    let component = emit(config, lazy_.span, ast::Nodes { stmts: vec![ast::Stmt::Node(node)] });
+    config.loop_depth.set(depth);
+    let component = component?;
+    Ok(quote_spanned! { lazy_.span =>
+        { ::defy::__private::suspend((), move |()| ::defy::lazy::load::<#ty>(), move |()| #component, #fallback) }
+    })
+}
+
+/// Converts node arguments into yew attributes or props.
+///
+/// The values of HTML attributes are converted with `__private::attr`,
+/// whose type errors name the attribute value instead of `IntoPropValue`.
+fn args_to_html(config: &Config, args: ast::NodeArgs, html_element: bool) -> Result<TokenStream> {
+    Ok(match args {
+        ast::NodeArgs::None => TokenStream::new(),
+        ast::NodeArgs::Named { paren: _, args } => {
+            let mut args: Vec<_> =
+                args.into_iter().map(|arg| event_arg(config, arg)).collect::<Result<_>>()?;
+            if config.sort_attrs {
+                // stable, so duplicate attributes keep their relative order
+                args.sort_by_cached_key(|arg| arg_name(&arg.ident));
+            }
+            args.into_iter()
+                .map(|ast::NodeArg { bind, at: _, prefix, ident, keys: _, modifiers: _, value }| {
+                    if let Some((prefix, colon)) = prefix {
+                        return Err(Error::new(
+                            prefix.span().join(colon.span).unwrap_or_else(|| prefix.span()),
+                            "DEFY0035: namespaced attributes like `xmlns:image` require `@xml`",
+                        ));
+                    }
+                    if let Some((bind, _)) = bind {
+                        return bind_arg(bind, &ident, value);
+                    }
+                    Ok(match value {
+                        None => quote_spanned! { ident.span() =>
+                            {#ident}
+                        },
+                        Some((eq, value)) => {
+                            // type errors in the value should point at the value itself
+                            let span = value.span();
+                            if let Some(map) = class_map(&value) {
+                                let classes = class_map_to_html(config, &ident, map);
+                                return Ok(quote_spanned! { eq.span => #ident = {#classes} });
+                            }
+                            let value = substitute_theme_tokens(config, *value)?;
+                            let converted = html_element && is_converted_attr(&arg_name(&ident));
+                            let value = match resolve_route(&value)?
+                                .or_else(|| resolve_asset(config, &value))
+                                .or_else(|| scope_classes(config, &ident, &value))
+                            {
+                                Some(value) => value,
+                                // literals are kept, so that yew renders static attributes
+                                None if !converted || matches!(value, syn::Expr::Lit(_)) => {
+                                    quote!(#value)
+                                }
+                                None => quote_spanned! { span => ::defy::__private::attr(#value) },
+                            };
+                            let value = quote_spanned! { span => {#value} };
+                            quote_spanned! { eq.span =>
+                                #ident = #value
+                            }
+                        }
+                    })
+                })
+                .collect::<Result<_>>()?
+        }
+        ast::NodeArgs::Rest { eq, arg } => quote_spanned! { eq.span =>
+            ..#arg
+        },
+    })
+}
+
+/// Whether the value of the HTML attribute `name` is an `Option<AttrValue>`,
+/// as opposed to the attributes that yew converts to other types,
+/// such as `class`, `value`, event listeners and boolean attributes.
+fn is_converted_attr(name: &str) -> bool {
+    !(["class", "value", "checked", "key", "ref"].contains(&name)
+        || name.starts_with("on")
+        || html::BOOLEAN_ATTRIBUTES.contains(&name))
+}
+
+/// Rewrites `@event = handler` to `onevent = handler`.
+fn event_arg(config: &Config, arg: ast::NodeArg) -> Result<ast::NodeArg> {
+    let ast::NodeArg { bind, at: Some(at), prefix, mut ident, keys, modifiers, value } = arg else {
+        return Ok(arg);
+    };
+    let event = arg_name(&ident);
+    if let Some(first) = ident.first_mut() {
+        *first = syn::Ident::new(&format!("on{first}"), first.span());
+    }
+    let handler = event_handler(config, &event, at.span, keys, modifiers, value)?;
+    Ok(ast::NodeArg {
+        bind,
+        at: None,
+        prefix,
+        ident,
+        keys: None,
+        modifiers: Vec::new(),
+        value: Some((syn::Token![=](at.span), handler)),
+    })
+}
+
+/// Converts the handler of an `@event` argument to the value of the `onevent` attribute.
+///
+/// With `@link ctx`, the value is the message sent to the struct component for the event,
+/// or a closure from the event to the message.
+/// With key filters, e.g. `@keydown(Enter)`, the handler is only called for the given keys,
+/// and the `.prevent` and `.stop` modifiers call `prevent_default` and `stop_propagation`
+/// before the handler.
+fn event_handler(
+    config: &Config,
+    event: &str,
+    span: Span,
+    keys: Option<(syn::token::Paren, Punctuated<ast::KeyFilter, syn::Token![|]>)>,
+    modifiers: Vec<(syn::Token![.], syn::Ident)>,
+    value: Option<(syn::Token![=], Box<syn::Expr>)>,
+) -> Result<Box<syn::Expr>> {
+    // declared with the call site span, since the other tokens may come from different macros
+    let (event_var, message_var, handler_var) =
+        (quote!(__defy_event), quote!(__defy_message), quote!(__defy_handler));
+    let mut actions = Vec::new();
+    for (_, modifier) in &modifiers {
+        let action = match modifier.to_string().as_str() {
+            "prevent" => quote_spanned!(modifier.span() => prevent_default),
+            "stop" => quote_spanned!(modifier.span() => stop_propagation),
+            _ => {
+                return Err(Error::new(
+                    modifier.span(),
+                    format_args!(
+                        "DEFY0032: unknown event modifier `.{modifier}`; expected `.prevent`, \
+                         `.stop` or `.passive`"
+                    ),
+                ))
+            }
+        };
+        actions.push(quote_spanned! { modifier.span() =>
+            ::defy::__private::#action(&#event_var);
+        });
+    }
+    let filter =
+        keys.map(|(paren, keys)| key_filter(event, &event_var, paren, keys)).transpose()?;
+    let value = value.map(|(_, value)| value);
+    let span = value.as_ref().map_or(span, |value| value.span());
+    let event_type = syn::Ident::new(&format!("on{event}"), span);
+    let event_type = quote_spanned!(span => ::yew::html::#event_type::Event);
+
+    let handler = match (&config.link, value) {
+        (None, Some(value)) if filter.is_none() && actions.is_empty() => return Ok(value),
+        (Some(ctx), Some(value)) if filter.is_none() && actions.is_empty() => {
+            let callback = match *value {
+                syn::Expr::Closure(closure) => quote!(#closure),
+                value => quote_spanned!(span => move |_| #value),
+            };
+            quote_spanned!(span => (#ctx).link().callback(#callback))
+        }
+        (Some(ctx), Some(value)) => {
+            let (closure, message) = match *value {
+                syn::Expr::Closure(closure) => (
+                    Some(quote_spanned!(span => let #message_var = #closure;)),
+                    quote_spanned!(span => #message_var(#event_var)),
+                ),
+                value => (None, quote!(#value)),
+            };
+            let body = quote_spanned! { span =>
+                #(#actions)*
+                ::std::option::Option::Some(#message)
+            };
+            let body = match filter {
+                Some(filter) => quote_spanned! { span =>
+                    if #filter { #body } else { ::std::option::Option::None }
+                },
+                None => body,
+            };
+            quote_spanned! { span =>
+                {
+                    #closure
+                    (#ctx).link().batch_callback(move |#event_var: #event_type| { #body })
+                }
+            }
+        }
+        (_, value) => {
+            let handler = value.map(|value| {
+                quote_spanned! { span =>
+                    let #handler_var =
+                        ::yew::html::IntoEventCallback::<#event_type>::into_event_callback(#value);
+                }
+            });
+            let emit = handler.is_some().then(|| {
+                quote_spanned! { span =>
+                    if let ::std::option::Option::Some(handler) = &#handler_var {
+                        handler.emit(#event_var);
+                    }
+                }
+            });
+            let body = quote!(#(#actions)* #emit);
+            let body = match filter {
+                Some(filter) => quote_spanned!(span => if #filter { #body }),
+                None => body,
+            };
+            quote_spanned! { span =>
+                {
+                    #handler
+                    ::yew::Callback::from(move |#event_var: #event_type| { #body })
+                }
+            }
+        }
+    };
+    Ok(Box::new(syn::parse2(handler)?))
+}
+
+/// Moves `@event.passive` arguments of an HTML element out of `args`,
+/// returning the statements that add them to `__defy_node` as passive listeners.
+fn passive_listeners(
+    config: &Config,
+    element: &syn::Path,
+    args: &mut ast::NodeArgs,
+) -> Result<Vec<TokenStream>> {
+    let ast::NodeArgs::Named { paren: _, args } = args else { return Ok(Vec::new()) };
+    let mut listeners = Vec::new();
+    let mut rest = Punctuated::new();
+    for mut arg in mem::take(args) {
+        let Some(index) = arg.modifiers.iter().position(|(_, modifier)| modifier == "passive")
+        else {
+            rest.push(arg);
+            continue;
+        };
+        let (_, passive) = arg.modifiers.remove(index);
+        let is_html = element
+            .get_ident()
+            .is_some_and(|ident| ident.to_string().starts_with(|ch: char| ch.is_ascii_lowercase()));
+        if !is_html {
+            return Err(Error::new(
+                passive.span(),
+                "DEFY0032: `.passive` can only be used on HTML elements",
+            ));
+        }
+        if let Some((_, prevent)) = arg.modifiers.iter().find(|(_, modifier)| modifier == "prevent")
+        {
+            return Err(Error::new(
+                prevent.span(),
+                "DEFY0032: passive listeners cannot prevent the default action",
+            ));
+        }
+        let event = arg_name(&arg.ident);
+        let span = arg.at.map_or_else(|| passive.span(), |at| at.span);
+        let event_type = syn::Ident::new(&format!("on{event}"), span);
+        let event_type = quote_spanned!(span => ::yew::html::#event_type::Event);
+        let handler = event_handler(config, &event, span, arg.keys, arg.modifiers, arg.value)?;
+        // `__defy_node` is declared with the call site span
+        listeners.push(quote! {
+            ::defy::__private::add_passive_listener(
+                &mut __defy_node,
+                #event,
+                ::yew::html::IntoEventCallback::<#event_type>::into_event_callback(#handler),
+            );
+        });
+    }
+    *args = rest;
+    Ok(listeners)
+}
+
+/// The condition that the `KeyboardEvent` in `event_var` matches one of `keys`.
+fn key_filter(
+    event: &str,
+    event_var: &TokenStream,
+    paren: syn::token::Paren,
+    keys: Punctuated<ast::KeyFilter, syn::Token![|]>,
+) -> Result<TokenStream> {
+    if !["keydown", "keyup", "keypress"].contains(&event) {
+        return Err(Error::new(
+            paren.span.join(),
+            format_args!(
+                "DEFY0031: key filters only apply to `@keydown`, `@keyup` and `@keypress`, not \
+                 `@{event}`"
+            ),
+        ));
+    }
+    let conditions = keys
+        .into_iter()
+        .map(|ast::KeyFilter { key, modifiers }| {
+            let modifiers =
+                modifiers.map_or_else(Vec::new, |(_, modifiers)| modifiers.into_iter().collect());
+            for modifier in &modifiers {
+                if !html::MODIFIER_KEYS.contains(&modifier.to_string().as_str()) {
+                    return Err(Error::new(
+                        modifier.span(),
+                        format_args!(
+                            "DEFY0031: unknown modifier key `{modifier}`; expected one of {}",
+                            html::MODIFIER_KEYS.join(", ")
+                        ),
+                    ));
+                }
+            }
+            let modifiers = modifiers.iter().map(ToString::to_string);
+            let name = key.value();
+            Ok(quote_spanned! { key.span() =>
+                ::defy::__private::key_matches(&#event_var, #name, &[#(#modifiers),*])
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(quote!(#(#conditions)||*))
+}
+
+/// Expands `bind:value = handle` or `bind:checked = handle`
+/// to the attribute and an event handler that updates the `UseStateHandle`.
+fn bind_arg(
+    bind: ast::kw::bind,
+    ident: &Punctuated<syn::Ident, syn::Token![-]>,
+    value: Option<(syn::Token![=], Box<syn::Expr>)>,
+) -> Result<TokenStream> {
+    let Some((_, handle)) = value else {
+        return Err(Error::new_spanned(
+            ident,
+            format_args!("DEFY0041: `bind:{}` requires a `UseStateHandle`", arg_name(ident)),
+        ));
+    };
+    Ok(match arg_name(ident).as_str() {
+        "value" => quote_spanned! { bind.span =>
+            value = {::std::clone::Clone::clone(&*#handle)}
+            oninput = {::defy::__private::bind_value(&#handle)}
+        },
+        "checked" => quote_spanned! { bind.span =>
+            checked = {*#handle}
+            onchange = {::defy::__private::bind_checked(&#handle)}
+        },
+        name => {
+            return Err(Error::new_spanned(
+                ident,
+                format_args!(
+                    "DEFY0017: `bind:{name}` is not supported; only `bind:value` and \
+                     `bind:checked` are"
+                ),
+            ))
+        }
+    })
+}
+
+/// Reads a file referenced from the macro input, relative to `CARGO_MANIFEST_DIR`.
+///
+/// Returns the resolved absolute path, which should be passed to `include_str!`
+/// in the generated code so that the compiler tracks the file for rebuilds.
+fn read_source_file(lit: &syn::LitStr) -> Result<(PathBuf, String)> {
+    let mut path = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default());
+    path.push(lit.value());
+
+    let contents = fs::read_to_string(&path).map_err(|err| {
+        Error::new_spanned(lit, format_args!("DEFY0020: cannot read {}: {err}", path.display()))
+    })?;
+    Ok((path, contents))
+}
+
+/// Resolves an attribute value in the form `route!(route)`
+/// to the path of a `yew_router::Routable` value.
+fn resolve_route(value: &syn::Expr) -> Result<Option<TokenStream>> {
+    let syn::Expr::Macro(mac) = value else { return Ok(None) };
+    if !mac.mac.path.is_ident("route") {
+        return Ok(None);
+    }
+    let route: syn::Expr = mac.mac.parse_body()?;
+    Ok(Some(quote_spanned! { mac.span() =>
+        ::defy::__private::route_href(::yew_router::Routable::to_path(&(#route)))
+    }))
+}
+
+/// Resolves an attribute value in the form `asset("path")`.
+fn resolve_asset(config: &Config, value: &syn::Expr) -> Option<TokenStream> {
+    let syn::Expr::Call(call) = value else { return None };
+    let syn::Expr::Path(func) = &*call.func else { return None };
+    if !func.path.is_ident("asset") || call.args.len() != 1 {
+        return None;
+    }
+    let Some(syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. })) = call.args.first()
+    else {
+        return None;
+    };
+
+    Some(asset_url(config, lit, call.span()))
+}
+
+/// The URL expression of an asset path.
+fn asset_url(config: &Config, lit: &syn::LitStr, span: Span) -> TokenStream {
+    match &config.asset_resolver {
+        Some(resolver) => quote_spanned! { span => #resolver(#lit) },
+        None => match env::var("DEFY_ASSET_BASE") {
+            Ok(base) if !base.is_empty() => {
+                let url = format!(
+                    "{}/{}",
+                    base.trim_end_matches('/'),
+                    lit.value().trim_start_matches('/')
+                );
+                quote_spanned! { lit.span() => #url }
+            }
+            _ => quote!(#lit),
+        },
+    }
+}
+
+/// Replaces `token!(dotted.key)` invocations in an expression with values from the `@theme` file.
+fn substitute_theme_tokens(config: &Config, expr: syn::Expr) -> Result<syn::Expr> {
+    let Some(theme) = &config.theme else { return Ok(expr) };
+    syn::parse2(theme::substitute(theme, expr.into_token_stream())?)
+}
+
+/// Rewrites a literal `class` attribute value under `@scope`.
+fn scope_classes(
+    config: &Config,
+    ident: &Punctuated<syn::Ident, syn::Token![-]>,
+    value: &syn::Expr,
+) -> Option<TokenStream> {
+    let scope = config.scope.as_ref()?;
+    let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) = value else { return None };
+    if arg_name(ident) != "class" {
+        return None;
+    }
+
+    let mut scoped_classes = config.scoped_classes.borrow_mut();
+    let classes: Vec<_> = lit
+        .value()
+        .split_whitespace()
+        .map(|class| {
+            scoped_classes.insert(class.to_string());
+            format!("{scope}__{class}")
+        })
+        .collect();
+    let classes = classes.join(" ");
+    Some(quote_spanned! { lit.span() => #classes })
+}
+
+/// Parses a `{ "class": condition, ... }` value, which the parser keeps as verbatim tokens.
+fn class_map(value: &syn::Expr) -> Option<ast::ClassMap> {
+    match value {
+        syn::Expr::Verbatim(tokens) => syn::parse2(tokens.clone()).ok(),
+        _ => None,
+    }
+}
+
+/// Expands a class map to `classes!` with an optional class for each entry.
+///
+/// The classes of a `class` argument are scoped like literal classes.
+fn class_map_to_html(
+    config: &Config,
+    ident: &Punctuated<syn::Ident, syn::Token![-]>,
+    map: ast::ClassMap,
+) -> TokenStream {
+    let classes = map.entries.into_iter().map(|ast::ClassEntry { class, colon: _, condition }| {
+        let value = syn::Expr::Lit(syn::ExprLit { attrs: Vec::new(), lit: class.clone().into() });
+        let class = scope_classes(config, ident, &value).unwrap_or_else(|| quote!(#class));
+        quote_spanned! { condition.span() =>
+            ::std::primitive::bool::then_some(#condition, #class)
+        }
+    });
+    quote_spanned! { map.braces.span.join() =>
+        ::yew::classes!(#(#classes),*)
+    }
+}
+
+/// Merges the classes scoped by this invocation into the scope map file, if requested.
+fn write_scope_map(scope: &str, classes: &BTreeSet<String>) -> Result<()> {
+    let Some(dir) = env::var_os("DEFY_SCOPE_MAP_DIR") else { return Ok(()) };
+    let path = Path::new(&dir).join(format!("{scope}.classes"));
+    let io_err = |err| {
+        Error::new(
+            Span::call_site(),
+            format_args!("DEFY0022: cannot write {}: {err}", path.display()),
+        )
+    };
+
+    let mut lines: BTreeSet<String> = match fs::read_to_string(&path) {
+        Ok(contents) => contents.lines().map(String::from).collect(),
+        Err(_) => BTreeSet::new(),
+    };
+    let old_len = lines.len();
+    lines.extend(classes.iter().map(|class| format!("{class} {scope}__{class}")));
+    if lines.len() == old_len && path.exists() {
+        return Ok(());
+    }
+
+    fs::create_dir_all(&dir).map_err(io_err)?;
+    let contents: String = lines.into_iter().map(|line| line + "\n").collect();
+    fs::write(&path, contents).map_err(io_err)
+}
+
+/// Collects the message IDs defined in a Fluent resource file or directory of resource files.
+fn read_fluent_messages(lit: &syn::LitStr, tracked: &mut Vec<PathBuf>) -> Result<HashSet<String>> {
+    let mut path = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default());
+    path.push(lit.value());
+
+    let io_err = |path: &Path, err| {
+        Error::new_spanned(lit, format_args!("DEFY0020: cannot read {}: {err}", path.display()))
+    };
+
+    let files = if path.is_dir() {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(&path).map_err(|err| io_err(&path, err))? {
+            let entry_path = entry.map_err(|err| io_err(&path, err))?.path();
+            if entry_path.extension().is_some_and(|ext| ext == "ftl") {
+                files.push(entry_path);
+            }
+        }
+        files
+    } else {
+        vec![path]
+    };
+
+    let mut messages = HashSet::new();
+    for file in files {
+        let contents = fs::read_to_string(&file).map_err(|err| io_err(&file, err))?;
+        tracked.push(file);
+        // Messages are the only entries starting with an identifier at the beginning of a line,
+        // other lines are comments, terms (`-term = ...`), attributes or indented continuations.
+        for line in contents.lines() {
+            if let Some((id, _)) = line.split_once('=') {
+                let id = id.trim_end();
+                if id.starts_with(|ch: char| ch.is_ascii_alphabetic())
+                    && id.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+                {
+                    messages.insert(id.to_string());
+                }
+            }
+        }
+    }
+    Ok(messages)
+}
@@ -0,0 +1,463 @@
+//! Lints on the macro input, reported as compiler warnings or errors.
+
+use std::collections::HashMap;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote_spanned};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Error, Result};
+
+use crate::{ast, html};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lint {
+    /// A lowercase element name that is not a known HTML or SVG tag.
+    UnknownTag,
+    /// An attribute that is not valid for the HTML element.
+    UnknownAttr,
+    /// An attribute specified more than once.
+    DuplicateAttr,
+    /// An obsolete element or a deprecated presentational attribute.
+    Obsolete,
+    /// An `img` element without `alt`.
+    ImgAlt,
+    /// A click handler on a non-interactive element without `role` or `tabindex`.
+    ClickRole,
+    /// An `a` element without `href`.
+    AnchorHref,
+    /// A URL literal with a scheme that runs scripts, e.g. `javascript:`.
+    UnsafeUrl,
+    /// A `srcdoc` attribute set from an expression in an unsandboxed `iframe`.
+    DynamicSrcdoc,
+}
+
+impl Lint {
+    pub const ALL: &'static [Lint] = &[
+        Lint::UnknownTag,
+        Lint::UnknownAttr,
+        Lint::DuplicateAttr,
+        Lint::Obsolete,
+        Lint::ImgAlt,
+        Lint::ClickRole,
+        Lint::AnchorHref,
+        Lint::UnsafeUrl,
+        Lint::DynamicSrcdoc,
+    ];
+
+    /// Lints that can be configured together by the name `a11y`.
+    pub const A11Y: &'static [Lint] = &[Lint::ImgAlt, Lint::ClickRole, Lint::AnchorHref];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::UnknownTag => "unknown_tag",
+            Lint::UnknownAttr => "unknown_attr",
+            Lint::DuplicateAttr => "duplicate_attr",
+            Lint::Obsolete => "obsolete",
+            Lint::ImgAlt => "img_alt",
+            Lint::ClickRole => "click_role",
+            Lint::AnchorHref => "anchor_href",
+            Lint::UnsafeUrl => "unsafe_url",
+            Lint::DynamicSrcdoc => "dynamic_srcdoc",
+        }
+    }
+
+    /// The stable diagnostic code of the lint.
+    pub fn code(self) -> &'static str {
+        match self {
+            Lint::UnknownTag => "DEFY1001",
+            Lint::UnknownAttr => "DEFY1002",
+            Lint::DuplicateAttr => "DEFY1003",
+            Lint::Obsolete => "DEFY1004",
+            Lint::ImgAlt => "DEFY1005",
+            Lint::ClickRole => "DEFY1006",
+            Lint::AnchorHref => "DEFY1007",
+            Lint::UnsafeUrl => "DEFY1008",
+            Lint::DynamicSrcdoc => "DEFY1009",
+        }
+    }
+
+    pub fn default_level(self) -> Level {
+        match self {
+            Lint::UnknownTag | Lint::UnknownAttr | Lint::Obsolete | Lint::DynamicSrcdoc => {
+                Level::Warn
+            }
+            Lint::DuplicateAttr | Lint::UnsafeUrl => Level::Deny,
+            Lint::ImgAlt | Lint::ClickRole | Lint::AnchorHref => Level::Allow,
+        }
+    }
+
+    /// Resolves a lint or lint group name.
+    pub fn from_str(name: &str) -> Option<Vec<Lint>> {
+        if name == "a11y" {
+            return Some(Lint::A11Y.to_vec());
+        }
+        Lint::ALL.iter().find(|lint| name == lint.name()).map(|&lint| vec![lint])
+    }
+
+    /// Resolves a lint or lint group name in the macro input.
+    pub fn from_name(name: &syn::Ident) -> Result<Vec<Lint>> {
+        Lint::from_str(&name.to_string()).ok_or_else(|| {
+            Error::new(name.span(), format_args!("DEFY0014: unknown defy lint `{name}`"))
+        })
+    }
+}
+
+/// Parses an `allow`, `warn` or `deny` attribute that only names defy lints.
+fn lint_attr(attr: &syn::Attribute) -> Option<(Level, Vec<Lint>)> {
+    let level = match attr.path().get_ident() {
+        Some(ident) if ident == "allow" => Level::Allow,
+        Some(ident) if ident == "warn" => Level::Warn,
+        Some(ident) if ident == "deny" => Level::Deny,
+        _ => return None,
+    };
+    let names =
+        attr.parse_args_with(Punctuated::<syn::Ident, syn::Token![,]>::parse_terminated).ok()?;
+    let mut lints = Vec::new();
+    for name in &names {
+        lints.extend(Lint::from_str(&name.to_string())?);
+    }
+    Some((level, lints))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
+}
+
+/// Where the level of a lint was configured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// The default level of the lint.
+    Default,
+    /// An `#[allow(...)]`, `#[warn(...)]` or `#[deny(...)]` attribute of an enclosing node.
+    Attribute,
+    /// An `@allow(...)`, `@warn(...)` or `@deny(...)` config.
+    Config,
+    /// The `@strict` config.
+    Strict,
+    /// The `lints` or `strict` keys of `defy.toml`.
+    Defaults,
+}
+
+impl Source {
+    /// Describes the source in the message of a denied lint.
+    fn describe(self) -> &'static str {
+        match self {
+            Source::Default => "denied by default",
+            Source::Attribute => "denied by `#[deny]`",
+            Source::Config => "denied by `@deny`",
+            Source::Strict => "denied by `@strict`",
+            Source::Defaults => "denied by `defy.toml`",
+        }
+    }
+}
+
+pub struct Warning {
+    pub lint:    Lint,
+    pub span:    Span,
+    pub message: String,
+    /// A secondary message at a related span, only shown when the lint is denied.
+    pub note:    Option<(Span, String)>,
+}
+
+/// Collects lint results according to the configured lint levels.
+#[derive(Default)]
+pub struct Lints {
+    pub levels:   HashMap<Lint, (Level, Source)>,
+    /// Where lints without an explicit level were denied, if they are.
+    pub strict:   Option<Source>,
+    /// Levels configured by the attributes of the enclosing nodes, innermost last.
+    scopes:       Vec<HashMap<Lint, Level>>,
+    /// Warnings reported at the `warn` level.
+    pub warnings: Vec<Warning>,
+    errors:       Vec<Error>,
+}
+
+impl Lints {
+    /// Resolves the level of a lint and where it was configured.
+    pub fn level(&self, lint: Lint) -> (Level, Source) {
+        let scoped = self.scopes.iter().rev().find_map(|scope| scope.get(&lint));
+        if let Some(&level) = scoped {
+            return (level, Source::Attribute);
+        }
+        match (self.levels.get(&lint), self.strict) {
+            (Some(&configured), _) => configured,
+            (None, Some(source)) => (Level::Deny, source),
+            (None, None) => (lint.default_level(), Source::Default),
+        }
+    }
+
+    /// Applies the lint levels configured by the attributes of a node
+    /// until the matching call to [`pop_scope`](Self::pop_scope).
+    ///
+    /// Returns the attributes that do not configure defy lints,
+    /// which are forwarded to the generated code.
+    pub fn push_scope(&mut self, attrs: Vec<syn::Attribute>) -> Vec<syn::Attribute> {
+        let mut scope = HashMap::new();
+        let mut forwarded = Vec::new();
+        for attr in attrs {
+            match lint_attr(&attr) {
+                Some((level, lints)) => scope.extend(lints.into_iter().map(|lint| (lint, level))),
+                None => forwarded.push(attr),
+            }
+        }
+        self.scopes.push(scope);
+        forwarded
+    }
+
+    pub fn pop_scope(&mut self) { self.scopes.pop(); }
+
+    pub fn report(&mut self, warnings: Vec<Warning>) {
+        for warning in warnings {
+            match self.level(warning.lint) {
+                (Level::Allow, _) => {}
+                (Level::Warn, _) => self.warnings.push(warning),
+                (Level::Deny, source) => {
+                    let mut err = Error::new(
+                        warning.span,
+                        format_args!(
+                            "{}: {} ({})",
+                            warning.lint.code(),
+                            warning.message,
+                            source.describe(),
+                        ),
+                    );
+                    if let Some((span, note)) = warning.note {
+                        err.combine(Error::new(span, note));
+                    }
+                    self.errors.push(err);
+                }
+            }
+        }
+    }
+
+    /// Returns the tokens emitting the collected warnings, or the collected errors.
+    pub fn finish(&mut self) -> Result<TokenStream> {
+        if let Some(err) = self.errors.drain(..).reduce(|mut err, other| {
+            err.combine(other);
+            err
+        }) {
+            return Err(err);
+        }
+        Ok(emit_warnings(&self.warnings))
+    }
+}
+
+/// Checks the tag name and attributes of an element node.
+pub fn check_node(element: &syn::Path, args: &ast::NodeArgs, warnings: &mut Vec<Warning>) {
+    if let ast::NodeArgs::Named { paren: _, args } = args {
+        check_duplicate_args(args, warnings);
+    }
+
+    let Some(ident) = element.get_ident() else { return };
+    let name = ident.to_string();
+    if name.starts_with(|ch: char| ch.is_ascii_lowercase()) && !html::is_known_element(&name) {
+        warnings.push(Warning {
+            lint:    Lint::UnknownTag,
+            span:    ident.span(),
+            message: format!("`{name}` is not a known HTML or SVG tag"),
+            note:    None,
+        });
+    }
+    if let Some(hint) = html::obsolete_element(&name) {
+        warnings.push(Warning {
+            lint:    Lint::Obsolete,
+            span:    ident.span(),
+            message: format!("`<{name}>` is obsolete; {hint}"),
+            note:    None,
+        });
+    }
+
+    let attrs: Vec<_> = match args {
+        ast::NodeArgs::None => Vec::new(),
+        ast::NodeArgs::Named { paren: _, args } => {
+            args.iter().map(|arg| (crate::arg_name(&arg.ident), arg.ident.span())).collect()
+        }
+        // the attributes are unknown
+        ast::NodeArgs::Rest { .. } => return,
+    };
+
+    for (attr, span) in &attrs {
+        if html::is_valid_attribute(&name, attr) == Some(false) {
+            warnings.push(match html::deprecated_attribute(attr) {
+                Some(hint) => Warning {
+                    lint:    Lint::Obsolete,
+                    span:    *span,
+                    message: format!("`{attr}` is deprecated on `<{name}>`; {hint}"),
+                    note:    None,
+                },
+                None => Warning {
+                    lint:    Lint::UnknownAttr,
+                    span:    *span,
+                    message: format!("`{attr}` is not a valid attribute of `<{name}>`"),
+                    note:    None,
+                },
+            });
+        }
+    }
+
+    let has_attr = |attr: &str| attrs.iter().any(|(name, _)| name == attr);
+    match name.as_str() {
+        "img" if !has_attr("alt") => warnings.push(Warning {
+            lint:    Lint::ImgAlt,
+            span:    ident.span(),
+            message: "`<img>` should have an `alt` attribute, which may be empty for decorative \
+                      images"
+                .into(),
+            note:    None,
+        }),
+        "a" if !has_attr("href") => warnings.push(Warning {
+            lint:    Lint::AnchorHref,
+            span:    ident.span(),
+            message: "`<a>` should have an `href` attribute; use `button` for actions".into(),
+            note:    None,
+        }),
+        _ => {}
+    }
+
+    if let ast::NodeArgs::Named { paren: _, args } = args {
+        check_urls(&name, args, has_attr("sandbox"), warnings);
+    }
+
+    if has_attr("onclick")
+        && html::HTML_ELEMENTS.contains(&name.as_str())
+        && !html::INTERACTIVE_ELEMENTS.contains(&name.as_str())
+        && !has_attr("role")
+        && !has_attr("tabindex")
+    {
+        warnings.push(Warning {
+            lint:    Lint::ClickRole,
+            span:    ident.span(),
+            message: format!(
+                "`<{name}>` with an `onclick` handler should have `role` and `tabindex` \
+                 attributes; use `button` for actions"
+            ),
+            note:    None,
+        });
+    }
+}
+
+/// Checks URL literals for schemes that run scripts, and `srcdoc` attributes set from expressions.
+fn check_urls(
+    element: &str,
+    args: &Punctuated<ast::NodeArg, syn::Token![,]>,
+    sandboxed: bool,
+    warnings: &mut Vec<Warning>,
+) {
+    for arg in args {
+        let Some((_, value)) = &arg.value else { continue };
+        let attr = crate::arg_name(&arg.ident);
+        let lit = match &**value {
+            syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) => Some(lit),
+            _ => None,
+        };
+        match (attr.as_str(), lit) {
+            ("srcdoc", None) if element == "iframe" && !sandboxed => warnings.push(Warning {
+                lint:    Lint::DynamicSrcdoc,
+                span:    value.span(),
+                message: "`srcdoc` is rendered as an HTML document with the origin of the page; \
+                          escape untrusted content or add a `sandbox` attribute"
+                    .into(),
+                note:    None,
+            }),
+            (attr, Some(lit)) if html::URL_ATTRIBUTES.contains(&attr) => {
+                if let Some(scheme) = html::unsafe_url_scheme(attr, &lit.value()) {
+                    warnings.push(Warning {
+                        lint:    Lint::UnsafeUrl,
+                        span:    lit.span(),
+                        message: format!(
+                            "`{scheme}` URLs run scripts in the origin of the page; use an event \
+                             handler instead"
+                        ),
+                        note:    None,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Checks for attributes specified more than once, since yew would silently keep only one of them.
+fn check_duplicate_args(
+    args: &Punctuated<ast::NodeArg, syn::Token![,]>,
+    warnings: &mut Vec<Warning>,
+) {
+    let mut seen = HashMap::new();
+    for arg in args {
+        let name = crate::arg_name(&arg.ident);
+        if let Some(&first) = seen.get(&name) {
+            warnings.push(Warning {
+                lint:    Lint::DuplicateAttr,
+                span:    arg.ident.span(),
+                message: format!("duplicate attribute `{name}`"),
+                note:    Some((first, format!("`{name}` is first specified here"))),
+            });
+        } else {
+            seen.insert(name, arg.ident.span());
+        }
+    }
+}
+
+/// Emits the warnings as statements producing compiler warnings at their spans.
+///
+/// Stable Rust does not allow procedural macros to emit warnings directly,
+/// so each warning is reported as the use of a deprecated item named after the lint.
+/// With the `nightly` feature, the warnings are emitted as diagnostics instead
+/// when running inside a procedural macro.
+pub fn emit_warnings(warnings: &[Warning]) -> TokenStream {
+    #[cfg(feature = "nightly")]
+    if proc_macro::is_available() {
+        for warning in warnings {
+            proc_macro::Diagnostic::spanned(
+                warning.span.unwrap(),
+                proc_macro::Level::Warning,
+                format!(
+                    "{}: {} (`#[warn({})]`)",
+                    warning.lint.code(),
+                    warning.message,
+                    warning.lint.name()
+                ),
+            )
+            .emit();
+        }
+        return TokenStream::new();
+    }
+
+    warnings
+        .iter()
+        .map(|warning| {
+            let item = format_ident!("{}", warning.lint.name(), span = warning.span);
+            let note = format!("defy: {}: {}", warning.lint.code(), warning.message);
+            quote_spanned! { warning.span =>
+                {
+                    #[deprecated(note = #note)]
+                    #[allow(non_camel_case_types)]
+                    struct #item;
+                    let _ = #item;
+                }
+            }
+        })
+        .collect()
+}
+
+/// Emits a note that is not associated with any lint, e.g. for debugging.
+pub fn emit_note(span: Span, message: &str) -> TokenStream {
+    #[cfg(feature = "nightly")]
+    if proc_macro::is_available() {
+        proc_macro::Diagnostic::spanned(span.unwrap(), proc_macro::Level::Note, message).emit();
+        return TokenStream::new();
+    }
+
+    quote_spanned! { span =>
+        {
+            #[deprecated(note = #message)]
+            #[allow(non_camel_case_types)]
+            struct debug_print;
+            let _ = debug_print;
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Minification of static HTML, e.g. converted from markdown files.
+
+/// Elements around which whitespace is not rendered.
+#[rustfmt::skip]
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address", "article", "aside", "blockquote", "body", "br", "dd", "details", "div", "dl", "dt",
+    "figcaption", "figure", "footer", "form", "h1", "h2", "h3", "h4", "h5", "h6", "head", "header",
+    "hr", "html", "li", "link", "main", "meta", "nav", "ol", "p", "pre", "section", "summary",
+    "table", "tbody", "td", "tfoot", "th", "thead", "title", "tr", "ul",
+];
+
+/// Elements whose contents are copied unchanged.
+const RAW_TEXT_ELEMENTS: &[&str] = &["pre", "textarea", "script", "style"];
+
+/// Removes comments and whitespace that is not rendered,
+/// and collapses other runs of whitespace to a single space.
+pub fn minify(html: &str) -> String {
+    let mut output = String::new();
+    let mut rest = html;
+    // whether the previous tag is a block element, including at the start of the document
+    let mut after_block = true;
+    let mut pending_space = false;
+    loop {
+        let text_end = rest.find('<').unwrap_or(rest.len());
+        let text = &rest[..text_end];
+        rest = &rest[text_end..];
+        if !text.trim().is_empty() {
+            if (pending_space || text.starts_with(char::is_whitespace)) && !after_block {
+                output.push(' ');
+            }
+            output.push_str(&text.split_whitespace().collect::<Vec<_>>().join(" "));
+            after_block = false;
+            pending_space = text.ends_with(char::is_whitespace);
+        } else if !text.is_empty() {
+            pending_space = true;
+        }
+        if rest.is_empty() {
+            break;
+        }
+
+        if rest.starts_with("<!--") {
+            rest = rest.find("-->").map_or("", |end| &rest[end + 3..]);
+            continue;
+        }
+        let tag = &rest[..tag_end(rest)];
+        rest = &rest[tag.len()..];
+        let name = tag.trim_start_matches(['<', '/']);
+        let name = name[..name.find(|ch: char| !ch.is_ascii_alphanumeric()).unwrap_or(name.len())]
+            .to_ascii_lowercase();
+        let block = BLOCK_ELEMENTS.contains(&name.as_str());
+        if pending_space && !after_block && !block {
+            output.push(' ');
+        }
+        pending_space = false;
+        after_block = block;
+        output.push_str(tag);
+
+        if !tag.starts_with("</") && RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+            let close = rest.to_ascii_lowercase().find(&format!("</{name}")).unwrap_or(rest.len());
+            output.push_str(&rest[..close]);
+            rest = &rest[close..];
+        }
+    }
+    output
+}
+
+/// The length of the tag at the start of `html`, ignoring `>` in quoted attribute values.
+fn tag_end(html: &str) -> usize {
+    let mut quote = None;
+    for (index, ch) in html.char_indices() {
+        match (quote, ch) {
+            (None, '"' | '\'') => quote = Some(ch),
+            (Some(open), _) if open == ch => quote = None,
+            (None, '>') => return index + 1,
+            _ => {}
+        }
+    }
+    html.len()
+}
@@ -0,0 +1,90 @@
+//! OpenGraph and Twitter card meta tags for `og` blocks.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote_spanned};
+use syn::{Error, Result};
+
+use crate::{ast, Config};
+
+/// The properties of an `og` block
+/// with the `og:` property and the Twitter card name of each.
+const PROPERTIES: &[(&str, Option<&str>, Option<&str>)] = &[
+    ("title", Some("og:title"), Some("twitter:title")),
+    ("description", Some("og:description"), Some("twitter:description")),
+    ("image", Some("og:image"), Some("twitter:image")),
+    ("image_alt", Some("og:image:alt"), Some("twitter:image:alt")),
+    ("image_width", Some("og:image:width"), None),
+    ("image_height", Some("og:image:height"), None),
+    ("url", Some("og:url"), None),
+    ("type", Some("og:type"), None),
+    ("site_name", Some("og:site_name"), None),
+    ("locale", Some("og:locale"), None),
+    ("card", None, Some("twitter:card")),
+    ("site", None, Some("twitter:site")),
+    ("creator", None, Some("twitter:creator")),
+];
+
+/// Converts an `og { ... }` block into yew HTML tokens.
+///
+/// `twitter:card` defaults to `summary_large_image` if an image is given, or `summary` otherwise.
+pub fn to_html(config: &Config, og: ast::Og) -> Result<TokenStream> {
+    let ast::Og { og_, braces: _, properties } = og;
+    let macro_path = &config.macro_path;
+
+    let mut names = Vec::new();
+    let mut locals = Vec::new();
+    let mut tags = Vec::new();
+    for ast::OgProperty { name, value, semi: _ } in properties {
+        let key = name.to_string();
+        let Some(&(_, og_property, twitter_name)) =
+            PROPERTIES.iter().find(|(property, ..)| *property == key)
+        else {
+            let known: Vec<_> = PROPERTIES.iter().map(|(property, ..)| *property).collect();
+            return Err(Error::new(
+                name.span(),
+                format_args!(
+                    "DEFY0027: unknown `og` property `{key}`; expected one of {}",
+                    known.join(", ")
+                ),
+            ));
+        };
+        if names.contains(&key) {
+            return Err(Error::new(
+                name.span(),
+                format_args!("DEFY0027: duplicate `og` property `{key}`"),
+            ));
+        }
+        names.push(key);
+
+        let local = format_ident!("__defy_og_{}", name, span = name.span());
+        locals.push(quote_spanned! { name.span() =>
+            let #local: ::yew::AttrValue =
+                ::std::convert::From::from(::std::string::ToString::to_string(&(#value)));
+        });
+        if let Some(property) = og_property {
+            tags.push(quote_spanned! { name.span() =>
+                <meta property={#property} content={::std::clone::Clone::clone(&#local)} />
+            });
+        }
+        if let Some(twitter_name) = twitter_name {
+            tags.push(quote_spanned! { name.span() =>
+                <meta name={#twitter_name} content={::std::clone::Clone::clone(&#local)} />
+            });
+        }
+    }
+    if !names.iter().any(|name| name == "card") {
+        let card = if names.iter().any(|name| name == "image") {
+            "summary_large_image"
+        } else {
+            "summary"
+        };
+        tags.push(quote_spanned! { og_.span => <meta name="twitter:card" content={#card} /> });
+    }
+
+    Ok(quote_spanned! { og_.span =>
+        { {
+            #(#locals)*
+            #macro_path! { <> #(#tags)* </> }
+        } }
+    })
+}
@@ -0,0 +1,121 @@
+//! Responsive images for `picture` statements.
+
+use proc_macro2::TokenStream;
+use quote::quote_spanned;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Error, Result};
+
+use crate::{arg_name, args_to_html, asset_url, ast, Config};
+
+/// Converts a `picture "path"(widths = [...], ...);` statement into yew HTML tokens.
+///
+/// Each width refers to a variant of the image named like `hero-480w.jpg` for `hero.jpg`.
+pub fn to_html(config: &Config, picture: ast::Picture) -> Result<TokenStream> {
+    let ast::Picture { picture_, path, args, semi: _ } = picture;
+    let macro_path = &config.macro_path;
+
+    let mut widths = None;
+    let mut formats = Vec::new();
+    let mut sizes = None;
+    let mut img_args = Punctuated::new();
+    match args {
+        ast::NodeArgs::None => {}
+        ast::NodeArgs::Named { paren: _, args } => {
+            for arg in args {
+                match (arg_name(&arg.ident).as_str(), arg.value) {
+                    ("widths", Some((_, value))) => widths = Some(int_list(&value)?),
+                    ("formats", Some((_, value))) => formats = str_list(&value)?,
+                    ("sizes", Some((_, value))) => sizes = Some(value),
+                    (_, value) => img_args.push(ast::NodeArg { value, ..arg }),
+                }
+            }
+        }
+        ast::NodeArgs::Rest { eq, arg: _ } => {
+            return Err(Error::new(eq.span, "DEFY0025: `picture` does not accept rest arguments"))
+        }
+    }
+    let Some(widths) = widths.filter(|widths| !widths.is_empty()) else {
+        return Err(Error::new_spanned(
+            &path,
+            "DEFY0025: `picture` requires the widths of the image variants, e.g. `widths = [480, \
+             960]`",
+        ));
+    };
+
+    let value = path.value();
+    let (stem, ext) =
+        value.rsplit_once('.').filter(|(_, ext)| !ext.contains('/')).ok_or_else(|| {
+            Error::new_spanned(&path, "DEFY0025: the path of a `picture` must have an extension")
+        })?;
+    let srcset = |ext: &str| {
+        let urls = widths.iter().map(|width| {
+            let variant = syn::LitStr::new(&format!("{stem}-{width}w.{ext}"), path.span());
+            asset_url(config, &variant, path.span())
+        });
+        let format = widths.iter().map(|width| format!("{{}} {width}w")).collect::<Vec<_>>();
+        let format = format.join(", ");
+        quote_spanned! { path.span() => ::std::format!(#format, #(#urls),*) }
+    };
+    let sizes = sizes.map(|sizes| quote_spanned! { sizes.span() => sizes={#sizes} });
+
+    let sources = formats.iter().map(|format| {
+        let mime = mime_type(format);
+        let srcset = srcset(format);
+        quote_spanned! { picture_.span =>
+            <source type={#mime} srcset={#srcset} #sizes />
+        }
+    });
+    let src = asset_url(config, &path, path.span());
+    let img_srcset = srcset(ext);
+    let img_args = args_to_html(
+        config,
+        ast::NodeArgs::Named { paren: Default::default(), args: img_args },
+        true,
+    )?;
+
+    Ok(quote_spanned! { picture_.span =>
+        { #macro_path! {
+            <picture>
+                #(#sources)*
+                <img src={#src} srcset={#img_srcset} #sizes #img_args />
+            </picture>
+        } }
+    })
+}
+
+fn int_list(expr: &syn::Expr) -> Result<Vec<u32>> {
+    list(expr, |lit| match lit {
+        syn::Lit::Int(int) => int.base10_parse().ok(),
+        _ => None,
+    })
+}
+
+fn str_list(expr: &syn::Expr) -> Result<Vec<String>> {
+    list(expr, |lit| match lit {
+        syn::Lit::Str(lit) => Some(lit.value()),
+        _ => None,
+    })
+}
+
+/// Parses an array of literals.
+fn list<T>(expr: &syn::Expr, parse: impl Fn(&syn::Lit) -> Option<T>) -> Result<Vec<T>> {
+    let error = || Error::new_spanned(expr, "DEFY0025: expected an array of literals");
+    let syn::Expr::Array(array) = expr else { return Err(error()) };
+    array
+        .elems
+        .iter()
+        .map(|elem| match elem {
+            syn::Expr::Lit(lit) => parse(&lit.lit).ok_or_else(error),
+            _ => Err(error()),
+        })
+        .collect()
+}
+
+fn mime_type(format: &str) -> String {
+    match format {
+        "jpg" | "jpeg" => "image/jpeg".to_string(),
+        "svg" => "image/svg+xml".to_string(),
+        format => format!("image/{format}"),
+    }
+}
@@ -0,0 +1,125 @@
+//! `raw "..."` statements and the validation of their HTML.
+
+use proc_macro2::TokenStream;
+use quote::quote_spanned;
+use syn::{Error, Result};
+
+use crate::{ast, html};
+
+/// HTML elements that implicitly close an open `p` element,
+/// so that the contents after them are no longer in the paragraph.
+#[rustfmt::skip]
+const CLOSES_P: &[&str] = &[
+    "address", "article", "aside", "blockquote", "details", "div", "dl", "fieldset", "figcaption",
+    "figure", "footer", "form", "h1", "h2", "h3", "h4", "h5", "h6", "header", "hr", "main", "menu",
+    "nav", "ol", "p", "pre", "section", "table", "ul",
+];
+
+/// HTML elements that cannot contain another element of the same name.
+const NOT_NESTED: &[&str] = &["a", "button", "form"];
+
+/// HTML elements whose contents are text instead of tags.
+const RAW_TEXT: &[&str] = &["script", "style", "textarea", "title"];
+
+/// Converts `raw "..."` into HTML rendered without escaping,
+/// after checking that its tags are balanced and properly nested.
+pub fn to_html(raw: ast::Raw) -> Result<TokenStream> {
+    let ast::Raw { raw_: _, html, semi: _ } = raw;
+    if let Err(err) = check(&html.value()) {
+        return Err(Error::new(
+            html.span(),
+            format_args!("DEFY0037: invalid HTML in `raw`: {err}"),
+        ));
+    }
+    Ok(quote_spanned! { html.span() =>
+        { ::yew::Html::from_html_unchecked(::yew::AttrValue::Static(#html)) }
+    })
+}
+
+/// Checks that the tags of an HTML fragment are balanced and properly nested.
+///
+/// This checks the tag structure only, not the names or attributes of elements.
+/// Void elements and self-closing tags have no end tag,
+/// and comments and the contents of `script`, `style`, `textarea` and `title` are skipped.
+/// Elements with optional end tags such as `li` must still be closed explicitly.
+pub fn check(fragment: &str) -> std::result::Result<(), String> {
+    let mut open: Vec<String> = Vec::new();
+    let mut rest = fragment;
+    while let Some(pos) = rest.find('<') {
+        rest = &rest[pos + 1..];
+        if let Some(comment) = rest.strip_prefix("!--") {
+            let end = comment.find("-->").ok_or("unterminated comment")?;
+            rest = &comment[end + 3..];
+            continue;
+        }
+        if rest.starts_with(['!', '?']) {
+            let end = rest.find('>').ok_or("unterminated declaration")?;
+            rest = &rest[end + 1..];
+            continue;
+        }
+
+        let (closing, tag) = match rest.strip_prefix('/') {
+            Some(tag) => (true, tag),
+            None => (false, rest),
+        };
+        let name_len = tag
+            .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '-' || ch == ':'))
+            .unwrap_or(tag.len());
+        if name_len == 0 || !tag.starts_with(|ch: char| ch.is_ascii_alphabetic()) {
+            // not a tag, e.g. the `<` in `a < b`
+            continue;
+        }
+        let name = tag[..name_len].to_ascii_lowercase();
+        let end = tag_end(&tag[name_len..]).ok_or_else(|| {
+            format!("unterminated tag `<{}{name}`", if closing { "/" } else { "" })
+        })? + name_len;
+        let self_closing = tag[..end].ends_with('/');
+        rest = &tag[end + 1..];
+
+        if closing {
+            match open.pop() {
+                Some(top) if top == name => {}
+                Some(top) => return Err(format!("`</{name}>` does not match the open `<{top}>`")),
+                None => return Err(format!("`</{name}>` has no matching start tag")),
+            }
+            continue;
+        }
+        if html::VOID_ELEMENTS.contains(&name.as_str()) || self_closing {
+            continue;
+        }
+        if open.last().is_some_and(|top| top == "p") && CLOSES_P.contains(&name.as_str()) {
+            return Err(format!("`<{name}>` cannot be nested in `<p>`, which it would close"));
+        }
+        if NOT_NESTED.contains(&name.as_str()) && open.contains(&name) {
+            return Err(format!("`<{name}>` cannot be nested in another `<{name}>`"));
+        }
+        if RAW_TEXT.contains(&name.as_str()) {
+            // the contents end at the first end tag, which is checked in the next iteration
+            let contents_end = rest
+                .to_ascii_lowercase()
+                .find(&format!("</{name}"))
+                .ok_or_else(|| format!("`<{name}>` is not closed"))?;
+            rest = &rest[contents_end..];
+        }
+        open.push(name);
+    }
+    match open.pop() {
+        Some(name) => Err(format!("`<{name}>` is not closed")),
+        None => Ok(()),
+    }
+}
+
+/// The position of the `>` that ends a tag, skipping quoted attribute values.
+fn tag_end(attrs: &str) -> Option<usize> {
+    let mut quote = None;
+    for (pos, ch) in attrs.char_indices() {
+        match quote {
+            Some(open) if ch == open => quote = None,
+            Some(_) => {}
+            None if ch == '"' || ch == '\'' => quote = Some(ch),
+            None if ch == '>' => return Some(pos),
+            None => {}
+        }
+    }
+    None
+}
@@ -0,0 +1,76 @@
+//! `#[defy::stories]` modules and their `#[story]` functions.
+
+use proc_macro2::TokenStream;
+use quote::quote_spanned;
+use syn::spanned::Spanned;
+use syn::{Error, Result};
+
+/// Expands `#[stories]` on an inline module,
+/// adding a `STORIES` list of its `#[story]` functions and a `Stories` gallery component.
+pub fn stories(attr: TokenStream, item: TokenStream) -> Result<TokenStream> {
+    if !attr.is_empty() {
+        return Err(Error::new_spanned(attr, "DEFY0030: `#[stories]` does not accept arguments"));
+    }
+    let mut module: syn::ItemMod = syn::parse2(item)?;
+    let span = module.ident.span();
+    let Some((_, items)) = &mut module.content else {
+        return Err(Error::new(
+            module.semi.span(),
+            "DEFY0030: `#[stories]` requires an inline module, e.g. `mod stories { ... }`",
+        ));
+    };
+
+    let mut stories = Vec::new();
+    for item in items.iter_mut() {
+        let syn::Item::Fn(func) = item else { continue };
+        let Some(index) = func.attrs.iter().position(is_story_attr) else { continue };
+        let attr = func.attrs.remove(index);
+        if !func.sig.inputs.is_empty() || !func.sig.generics.params.is_empty() {
+            return Err(Error::new_spanned(
+                &func.sig,
+                "DEFY0030: a story must be a function without parameters returning `Html`",
+            ));
+        }
+        let name = match &attr.meta {
+            syn::Meta::Path(_) => crate::form::humanize(&func.sig.ident.to_string()),
+            syn::Meta::List(list) => list.parse_args::<syn::LitStr>()?.value(),
+            syn::Meta::NameValue(_) => {
+                return Err(Error::new_spanned(
+                    attr,
+                    "DEFY0030: expected `#[story]` or `#[story(\"name\")]`",
+                ))
+            }
+        };
+        let ident = &func.sig.ident;
+        stories.push(quote_spanned! { ident.span() =>
+            ::defy::story::Story { name: #name, render: #ident }
+        });
+    }
+
+    items.push(syn::parse2(quote_spanned! { span =>
+        /// The stories of this module in source order.
+        pub const STORIES: &[::defy::story::Story] = &[#(#stories),*];
+    })?);
+    items.push(syn::parse2(quote_spanned! { span =>
+        /// A gallery of the stories of this module.
+        #[::yew::function_component]
+        pub fn Stories() -> ::yew::Html {
+            ::yew::html! { <::defy::story::Gallery stories={STORIES} /> }
+        }
+    })?);
+    Ok(quote_spanned! { span => #module })
+}
+
+/// Expands `#[story]` outside a `#[stories]` module,
+/// which removes the attribute before it is expanded.
+pub fn story(item: TokenStream) -> Result<TokenStream> {
+    Err(Error::new_spanned(
+        item,
+        "DEFY0030: `#[story]` can only be used on functions in a `#[defy::stories]` module",
+    ))
+}
+
+/// Whether `attr` is `#[story]` or a path to it, e.g. `#[defy::story]`.
+fn is_story_attr(attr: &syn::Attribute) -> bool {
+    attr.path().segments.last().is_some_and(|segment| segment.ident == "story")
+}
@@ -0,0 +1,701 @@
+#![cfg(test)]
+
+use quote::quote;
+
+use crate::ast;
+
+#[test]
+fn test_if_else() {
+    let _: ast::Input = syn::parse2(quote! {
+        if foo {
+            + "";
+        } else {
+            + "";
+        }
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_match() {
+    let _: ast::Input = syn::parse2(quote! {
+        match route {
+            Route::List | Route::Post => {
+                pages::comp;
+            }
+        }
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_keyword() {
+    let _: ast::Input = syn::parse2(quote! {
+        input(type = "checkbox");
+    })
+    .unwrap();
+}
+
+#[test]
+fn test_include() {
+    let input: ast::Input = syn::parse2(quote! {
+        include "templates/footer.defy";
+        include;
+    })
+    .unwrap();
+    assert!(matches!(input.nodes.stmts[0], ast::Stmt::Include(_)));
+    assert!(matches!(input.nodes.stmts[1], ast::Stmt::Node(_)));
+
+    let err = crate::run(quote! { include templates::footer; }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0111: "), "{err}");
+}
+
+#[test]
+fn test_markdown() {
+    let input: ast::Input = syn::parse2(quote! {
+        markdown "docs/about.md";
+        markdown post.body;
+        markdown { + ""; }
+    })
+    .unwrap();
+    assert!(matches!(
+        input.nodes.stmts[0],
+        ast::Stmt::Markdown(ast::Markdown { source: ast::MarkdownSource::File(_), .. })
+    ));
+    assert!(matches!(
+        input.nodes.stmts[1],
+        ast::Stmt::Markdown(ast::Markdown { source: ast::MarkdownSource::Expr(_), .. })
+    ));
+    assert!(matches!(input.nodes.stmts[2], ast::Stmt::Node(_)));
+}
+
+#[test]
+#[cfg(feature = "markdown")]
+fn test_minify() {
+    let html = "<h1>Title</h1>\n<!-- draft -->\n<p>Some  <em>text</em>\n<b>bold</b> \
+                </p>\n<pre>a\n  b</pre>\n";
+    assert_eq!(
+        crate::minify::minify(html),
+        "<h1>Title</h1><p>Some <em>text</em> <b>bold</b></p><pre>a\n  b</pre>",
+    );
+}
+
+#[test]
+fn test_translate() {
+    let input: ast::Input = syn::parse2(quote! {
+        @i18n crate::i18n::lookup
+        t "greeting" { name = user.name, count = 3 };
+        t "farewell";
+    })
+    .unwrap();
+    match &input.nodes.stmts[0] {
+        ast::Stmt::Translate(ast::Translate { id, args: Some((_, args)), .. }) => {
+            assert_eq!(id.value(), "greeting");
+            assert_eq!(args.len(), 2);
+        }
+        _ => panic!("expected translate statement with arguments"),
+    }
+    assert!(matches!(
+        input.nodes.stmts[1],
+        ast::Stmt::Translate(ast::Translate { args: None, .. })
+    ));
+}
+
+#[test]
+fn test_icon_override() {
+    let lit: syn::LitStr = syn::parse2(quote!("icon.svg")).unwrap();
+    let svg = crate::icon::to_html(
+        &lit,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" class="old" view-box="0 0 1 1"><path d="M0"/></svg>"#,
+        quote!(class = "new"),
+        &["class".to_string()].into_iter().collect(),
+    )
+    .unwrap()
+    .to_string();
+    assert!(!svg.contains("\"old\""));
+    assert!(svg.contains("\"new\""));
+    assert!(svg.contains("view - box"));
+}
+
+#[test]
+fn test_icon_invalid_name() {
+    let lit: syn::LitStr = syn::parse2(quote!("icon.svg")).unwrap();
+    let svg = r#"<svg xmlns="http://www.w3.org/2000/svg"><path data-2x="1" d="M0"/></svg>"#;
+    let err = crate::icon::to_html(&lit, svg, quote!(), &Default::default()).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0040: "));
+}
+
+#[test]
+fn test_style() {
+    let input: ast::Input = syn::parse2(quote! {
+        div {
+            style { "padding: 1rem;" }
+            style { + "p { margin: 0; }"; }
+        }
+    })
+    .unwrap();
+    let ast::Stmt::Node(ast::Node { body: ast::NodeBody::Braced { children, .. }, .. }) =
+        &input.nodes.stmts[0]
+    else {
+        panic!("expected node with children")
+    };
+    assert!(matches!(children.stmts[0], ast::Stmt::Style(_)));
+    assert!(matches!(children.stmts[1], ast::Stmt::Node(_)));
+}
+
+#[test]
+fn test_theme_tokens() {
+    let theme: toml::Table = "[color]\nprimary = \"#f00\"\n".parse().unwrap();
+    let output =
+        crate::theme::substitute(&theme, quote!(format!("{}", token!(color.primary)))).unwrap();
+    assert_eq!(output.to_string(), quote!(format!("{}", "#f00")).to_string());
+
+    assert!(crate::theme::substitute(&theme, quote!(token!(color.secondary))).is_err());
+
+    let theme: toml::Table = "[size]\nmax = inf\n".parse().unwrap();
+    let err = crate::theme::substitute(&theme, quote!(token!(size.max))).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0011: "));
+}
+
+#[test]
+fn test_unknown_tag_lint() {
+    let mut warnings = Vec::new();
+    for element in [quote!(div), quote!(linearGradient), quote!(Comp), quote!(pages::comp)] {
+        crate::lint::check_node(
+            &syn::parse2(element).unwrap(),
+            &ast::NodeArgs::None,
+            &mut warnings,
+        );
+    }
+    assert!(warnings.is_empty());
+
+    crate::lint::check_node(
+        &syn::parse2(quote!(dvi)).unwrap(),
+        &ast::NodeArgs::None,
+        &mut warnings,
+    );
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].lint, crate::lint::Lint::UnknownTag);
+}
+
+#[test]
+fn test_unknown_attr_lint() {
+    let node: ast::Node = syn::parse2(quote! {
+        div(id = "a", href = "b", data-x = "c", aria-label = "d", onclick = e);
+    })
+    .unwrap();
+    let mut warnings = Vec::new();
+    crate::lint::check_node(&node.element, &node.args, &mut warnings);
+    warnings.retain(|warning| warning.lint == crate::lint::Lint::UnknownAttr);
+    assert_eq!(warnings.len(), 1);
+}
+
+#[test]
+fn test_a11y_lints() {
+    let node: ast::Node = syn::parse2(quote! {
+        div(onclick = f) { img(src = "a.png"); }
+    })
+    .unwrap();
+    let mut warnings = Vec::new();
+    crate::lint::check_node(&node.element, &node.args, &mut warnings);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].lint, crate::lint::Lint::ClickRole);
+    assert_eq!(
+        warnings[0].message,
+        "`<div>` with an `onclick` handler should have `role` and `tabindex` attributes; use \
+         `button` for actions",
+    );
+
+    assert!(crate::run(quote! { img(src = "a.png"); }).is_ok());
+    assert!(crate::run(quote! { @deny(a11y) img(src = "a.png"); }).is_err());
+    assert!(crate::run(quote! { @deny(a11y) img(src = "a.png", alt = ""); }).is_ok());
+    assert!(crate::run(quote! { @deny(no_such_lint) div; }).is_err());
+}
+
+#[test]
+fn test_duplicate_attr() {
+    assert!(crate::run(quote! { div(class, id = "a", class = "b"); }).is_err());
+    assert!(crate::run(quote! { div(class = "a", id = "b"); }).is_ok());
+    assert!(crate::run(quote! { @warn(duplicate_attr) div(class, class); }).is_ok());
+}
+
+#[test]
+fn test_node_lint_attrs() {
+    assert!(crate::run(quote! { @deny(unknown_tag) #[allow(unknown_tag)] dvi { dvi; } }).is_ok());
+    assert!(crate::run(quote! { @deny(unknown_tag) dvi { #[allow(unknown_tag)] dvi; } }).is_err());
+    let documented = quote! { @deny(unknown_tag) #[allow(unknown_tag)] widget { + "Custom"; } };
+    assert!(crate::run(documented).is_ok());
+    assert!(crate::run(quote! { #[deny(a11y)] div { img; } }).is_err());
+
+    let denied_by = |input| crate::run(input).err().unwrap().to_string();
+    assert!(denied_by(quote! { #[deny(a11y)] div { img; } }).ends_with("(denied by `#[deny]`)"));
+    assert!(denied_by(quote! { @deny(unknown_tag) dvi; }).ends_with("(denied by `@deny`)"));
+    assert!(denied_by(quote! { @strict img; }).ends_with("(denied by `@strict`)"));
+    assert!(denied_by(quote! { a(href = "javascript:x"); }).ends_with("(denied by default)"));
+    // attributes naming other lints are forwarded to the compiler instead
+    assert!(crate::run(quote! { @deny(unknown_tag) #[allow(unused, unknown_tag)] dvi; }).is_err());
+    assert!(crate::run(quote! { #[allow(unused)] div; }).is_ok());
+}
+
+#[test]
+fn test_void_element_children() {
+    assert!(crate::run(quote! { br { + "text"; } }).is_err());
+    assert!(crate::run(quote! { img {} }).is_err());
+    assert!(crate::run(quote! { img(src = "a.png"); }).is_ok());
+}
+
+#[test]
+fn test_obsolete_lint() {
+    let mut warnings = Vec::new();
+    for node in [quote! { center; }, quote! { div(align = "left", id = "a"); }] {
+        let node: ast::Node = syn::parse2(node).unwrap();
+        crate::lint::check_node(&node.element, &node.args, &mut warnings);
+    }
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings.iter().all(|warning| warning.lint == crate::lint::Lint::Obsolete));
+}
+
+#[test]
+fn test_route_href() {
+    let output = crate::run(quote! { a(href = route!(Route::Post { id })) { + "x"; } }).unwrap();
+    let output = output.to_string();
+    assert!(output
+        .contains("route_href (:: yew_router :: Routable :: to_path (& (Route :: Post { id })))"));
+    assert!(!output.contains("route !"));
+}
+
+#[test]
+fn test_url_lints() {
+    assert!(crate::run(quote! { a(href = "javascript:alert(1)") { + "x"; } }).is_err());
+    assert!(crate::run(quote! { a(href = " JavaScript\t:alert(1)") { + "x"; } }).is_err());
+    assert!(crate::run(quote! { form(action = "data:text/html,<p>") {} }).is_err());
+    assert!(crate::run(quote! { img(src = "data:image/png;base64,AA==", alt = ""); }).is_ok());
+    assert!(crate::run(quote! { a(href = "/javascript:") { + "x"; } }).is_ok());
+    assert!(crate::run(quote! { @allow(unsafe_url) a(href = "javascript:;") { + "x"; } }).is_ok());
+
+    let mut warnings = Vec::new();
+    for node in [
+        quote! { iframe(srcdoc = html); },
+        quote! { iframe(srcdoc = "<p>static</p>"); },
+        quote! { iframe(srcdoc = html, sandbox = ""); },
+    ] {
+        let node: ast::Node = syn::parse2(node).unwrap();
+        crate::lint::check_node(&node.element, &node.args, &mut warnings);
+    }
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].lint, crate::lint::Lint::DynamicSrcdoc);
+}
+
+#[test]
+fn test_strict() {
+    assert!(crate::run(quote! { @strict img(src = "a.png"); }).is_err());
+    assert!(crate::run(quote! { @strict @allow(img_alt) img(src = "a.png"); }).is_ok());
+    assert!(crate::run(quote! { @strict img(src = "a.png", alt = ""); }).is_ok());
+}
+
+#[test]
+fn test_recovery() {
+    let err = crate::run(quote! {
+        div { 1; }
+        if a { span; } else { "b"; }
+        let x = S { a };
+        p(id = ) { span; }
+        br;
+    })
+    .err()
+    .unwrap();
+    assert_eq!(err.into_iter().count(), 3);
+}
+
+#[test]
+fn test_missing_plus() {
+    for input in [quote! { "hello"; }, quote! { format!("{}", 1); }] {
+        let err = crate::run(input).err().unwrap();
+        assert!(err.to_string().contains("prefixed with `+`"));
+    }
+}
+
+#[test]
+fn test_missing_node_body() {
+    for input in [quote! { div span; }, quote! { div(id = "a") }] {
+        let err = crate::run(input).err().unwrap();
+        assert!(err.to_string().contains("expected `;` for a childless element"), "{err}");
+    }
+}
+
+#[test]
+fn test_braced_head() {
+    let err = crate::run(quote! { if x == Foo { a: 1 } { br; } }).err().unwrap();
+    assert!(err.to_string().contains("must be parenthesized"));
+    assert!(crate::run(quote! { if (x == Foo { a: 1 }) { br; } }).is_ok());
+    assert!(crate::run(quote! { for x in (Foo { a: 1 }).iter() { br; } }).is_ok());
+}
+
+#[test]
+fn test_defaults() {
+    let table: toml::Table = r#"
+        macro_path = "::yew::html_nested"
+        lints = { a11y = "deny" }
+        minify = true
+        sort_attrs = true
+    "#
+    .parse()
+    .unwrap();
+    let mut config = crate::Config::new();
+    crate::defaults::apply(&mut config, &table).unwrap();
+    assert!(config.macro_path == syn::parse_quote!(::yew::html_nested));
+    assert!(config.minify);
+    assert!(config.sort_attrs);
+    assert_eq!(
+        config.lints.borrow().level(crate::lint::Lint::ImgAlt),
+        (crate::lint::Level::Deny, crate::lint::Source::Defaults),
+    );
+    let err = crate::run_with_config(&mut config, quote! { img; }).err().unwrap();
+    assert!(err.to_string().ends_with("(denied by `defy.toml`)"));
+
+    let table: toml::Table = "lints = { a11y = true }".parse().unwrap();
+    assert!(crate::defaults::apply(&mut config, &table).is_err());
+}
+
+#[test]
+fn test_export() {
+    let output = crate::run(quote! { @export my_defy @scope "card" }).unwrap();
+    let expected = quote! {
+        #[allow(unused_macros)]
+        macro_rules! my_defy {
+            ($($tt:tt)*) => { ::defy::defy! { @macro_path ::yew::html @scope "card" $($tt)* } };
+        }
+    };
+    assert_eq!(output.to_string(), expected.to_string());
+
+    let output = crate::run(quote! {
+        @export my_defy
+        @debug_print
+        @debug_loc
+        @emit_notes
+        @macro_path ::yew::html_nested
+        @minify
+    })
+    .unwrap();
+    let expected = quote! {
+        #[allow(unused_macros)]
+        macro_rules! my_defy {
+            ($($tt:tt)*) => { ::defy::defy! { @macro_path ::yew::html_nested @minify $($tt)* } };
+        }
+    };
+    assert_eq!(output.to_string(), expected.to_string());
+}
+
+#[test]
+fn test_diagnostic_codes() {
+    let err = crate::run(quote! { br; let x = 1; }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0001: "));
+    let err = crate::run(quote! { div(class, class); }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY1003: "));
+    let err = crate::run(quote! { if x { break; } }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0016: "));
+    let err = crate::run(quote! { for x in xs { memo(x) { continue; } } }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0016: "));
+    let err = crate::run(quote! { for x in xs { if media "print" { break; } } }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0016: "));
+    for closure in [
+        quote!(memo(x) { continue; }),
+        quote!(once { continue; }),
+        quote!(defer { continue; }),
+        quote!(await load() as y { continue; }),
+        quote!(try {
+            continue;
+        }),
+        quote!(virtual for y in ys (height = 20, viewport = 400) { continue; }),
+    ] {
+        let err = crate::run(quote! { for x in xs { if x { break; } #closure } }).err().unwrap();
+        assert!(err.to_string().starts_with("DEFY0016: "), "{closure}");
+    }
+    let err = crate::run(quote! { await load() { br; } }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0104: "));
+    let err = crate::run(quote! { input(bind:title = title); }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0017: "));
+    let err = crate::run(quote! { input(bind:value); }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0041: "));
+    let err = crate::form::derive(quote! { struct Point(i32, i32); }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0024: "));
+    let err = crate::run(quote! { options from xs { |x| { + x; } } }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0107: "));
+    let err = crate::run(quote! { picture "hero.jpg"(alt = "Hero"); }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0025: "));
+    let err = crate::run(quote! { entity nbps; }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0028: "));
+    let err = crate::run(quote! { button(testid); }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0029: "));
+    let err = crate::story::stories(quote!(), quote! { mod stories; }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0030: "));
+    let err = crate::story::story(quote! { fn story() {} }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0030: "));
+    let err = crate::run(quote! { button(@click); }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0108: "));
+    let err = crate::run(quote! { button(@click(Enter) = f); }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0031: "));
+    let err = crate::run(quote! { input(@keydown("k" with control) = f); }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0031: "));
+    let err = crate::run(quote! { form(@submit.preventt = f); }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0032: "));
+    let err = crate::run(quote! { div(@wheel.passive.prevent = f); }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0032: "));
+    let err = crate::run(quote! { og { name "x"; } }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0027: "));
+    if !cfg!(feature = "json") {
+        let err = crate::run(quote! { json_script = data; }).err().unwrap();
+        assert!(err.to_string().starts_with("DEFY0026: "));
+    }
+    let err = crate::run(quote! { transition(fade = "in") if x { br; } }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0033: "));
+    let err =
+        crate::run(quote! { transition(enter = "a", enter = "b") if x { br; } }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0033: "));
+    let err = crate::run(quote! { virtual for x in xs (height = 1) { br; } }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0033: "));
+    let err = crate::run(quote! { virtual for x in xs { br; } }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0109: "));
+    let err = crate::run(quote! { island div(id = "x"); }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0034: "));
+    let err = crate::run(quote! { svg(xmlns:xlink = "x"); }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0035: "));
+    let err = crate::run(quote! { @xml feed(@click = f); }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0035: "));
+    let err = crate::run(quote! { xml_decl(version = v); }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0035: "));
+    let err = crate::run(quote! { block content { br; } }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0036: "));
+    let err = crate::run(quote! { extend base { block a {} block a {} } }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0036: "));
+    let err = crate::run(quote! { extend base { br; } }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0036: "));
+    let err = crate::run(quote! { dyn widget(label = x); }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0038: "));
+    let err = crate::run(quote! { dyn widget(props = x, y); }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0038: "));
+    let err = crate::run(quote! { lazy div; }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0039: "));
+    let err = crate::run(quote! { lazy Panel { br; } }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0039: "));
+    let err = crate::run(quote! { doctype xhtml; }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0110: "));
+    let err = crate::run(quote! { wrap_if x a(href = "/"); }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0106: "));
+
+    assert_eq!(
+        crate::diagnostics::split_code("in footer.defy: DEFY0101: text nodes"),
+        (Some("DEFY0101"), "in footer.defy: text nodes".to_string())
+    );
+    assert_eq!(crate::diagnostics::split_code("expected `;`"), (None, "expected `;`".to_string()));
+}
+
+#[test]
+fn test_diagnostics_sidecar() {
+    let dir = std::env::temp_dir().join(format!("defy-diagnostics-{}", std::process::id()));
+    let call_site = proc_macro2::Span::call_site();
+    let path = dir.join(crate::diagnostics::file_name(call_site));
+    let warning = || crate::lint::Warning {
+        lint:    crate::lint::Lint::UnknownTag,
+        span:    call_site,
+        message: "`dvi` is not a \"known\" tag".to_string(),
+        note:    None,
+    };
+    let err = syn::Error::new(proc_macro2::Span::call_site(), "DEFY0001: let statements");
+    // expanding the invocation again replaces the diagnostics of the previous expansion
+    crate::diagnostics::write(&dir, call_site, Some(&err), &[warning()]).unwrap();
+    crate::diagnostics::write(&dir, call_site, Some(&err), &[warning()]).unwrap();
+
+    let output = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<_> = output.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with(
+        r#"{"level":"error","code":"DEFY0001","lint":null,"message":"let statements""#
+    ));
+    assert!(lines[1].starts_with(
+        r#"{"level":"warning","code":"DEFY1001","lint":"unknown_tag","message":"`dvi` is not a \"known\" tag""#
+    ));
+
+    crate::diagnostics::write(&dir, call_site, None, &[]).unwrap();
+    assert!(!path.exists());
+    std::fs::remove_dir(&dir).unwrap();
+}
+
+#[test]
+fn test_debug_loc() {
+    let output = crate::run(quote! { @debug_loc div { span(class = "a"); Comp; } }).unwrap();
+    let output = output.to_string();
+    assert_eq!(output.matches("data - defy - loc").count(), 2);
+    assert!(output.contains("debug_assertions"));
+
+    let output = crate::run(quote! { div; }).unwrap().to_string();
+    assert!(!output.contains("data - defy - loc"));
+}
+
+#[test]
+fn test_testids() {
+    let output = crate::run(quote! {
+        @testids
+        div { UserCard { span; } button(testid = "save") { b; } }
+    })
+    .unwrap()
+    .to_string();
+    for testid in ["\"div\"", "\"div-user-card-span\"", "\"save\"", "\"save-b\""] {
+        assert!(output.contains(testid), "{testid} not in {output}");
+    }
+    assert_eq!(output.matches("data - testid").count(), 4);
+    assert_eq!(output.matches("testid =").count(), 4);
+
+    let output = crate::run(quote! { div { button(testid = id); } }).unwrap().to_string();
+    assert_eq!(output.matches("data - testid").count(), 1);
+}
+
+#[test]
+fn test_event_handlers() {
+    let output = crate::run(quote! { button(@click = on_click); }).unwrap().to_string();
+    assert!(output.contains("onclick = { on_click }"), "{output}");
+
+    let output = crate::run(quote! {
+        @link ctx
+        button(@click = Msg::Increment);
+        input(@input = |e: InputEvent| Msg::Input(e));
+    })
+    .unwrap()
+    .to_string();
+    assert!(
+        output.contains("(ctx) . link () . callback (move | _ | Msg :: Increment)"),
+        "{output}"
+    );
+    assert!(output.contains("(ctx) . link () . callback (| e : InputEvent | Msg :: Input (e))"));
+
+    let output = crate::run(quote! { input(@keydown(Escape | "k" with ctrl + shift) = close); })
+        .unwrap()
+        .to_string();
+    assert!(output.contains("onkeydown ="));
+    assert!(output.contains(
+        r#"key_matches (& __defy_event , "Escape" , & []) || :: defy :: __private :: key_matches (& __defy_event , "k" , & ["ctrl" , "shift"])"#
+    ), "{output}");
+
+    let output = crate::run(quote! { form(@submit.prevent.stop = submit); }).unwrap().to_string();
+    assert!(
+        output.contains(
+            "prevent_default (& __defy_event) ; :: defy :: __private :: stop_propagation"
+        ),
+        "{output}"
+    );
+    let output = crate::run(quote! { div(@wheel.passive = scroll); }).unwrap().to_string();
+    assert!(output.contains("add_passive_listener (& mut __defy_node , \"wheel\""), "{output}");
+    assert!(!output.contains("onwheel ="));
+}
+
+#[test]
+fn test_attr_values() {
+    let output = crate::run(quote! {
+        img(src = src, width = "10", class = class, disabled = d, onclick = f);
+        Image(src = src);
+    })
+    .unwrap()
+    .to_string();
+    assert_eq!(output.matches(":: defy :: __private :: attr").count(), 1, "{output}");
+    assert!(output.contains("src = { :: defy :: __private :: attr (src) }"));
+}
+
+#[test]
+fn test_class_map() {
+    let output = crate::run(quote! {
+        @scope "card"
+        div(class = { "open": x, "a::b": y }, data-x = { a }) {}
+    })
+    .unwrap()
+    .to_string();
+    assert!(output.contains(r#"then_some (x , "card__open")"#), "{output}");
+    assert!(output.contains(r#"then_some (y , "card__a::b")"#));
+    assert!(output.contains("data - x = { :: defy :: __private :: attr ({ a }) }"), "{output}");
+}
+
+#[test]
+fn test_trace() {
+    let output = crate::run(quote! { @trace div { span; } br; }).unwrap().to_string();
+    assert_eq!(output.matches("trace_span").count(), if cfg!(feature = "trace") { 2 } else { 0 });
+    if !cfg!(feature = "trace") {
+        let untraced = crate::run(quote! { div { span; } br; }).unwrap().to_string();
+        assert_eq!(output, untraced);
+    }
+}
+
+#[test]
+fn test_unkeyed_list_warning() {
+    let warns = |input| crate::run(input).unwrap().to_string().contains("warn_unkeyed_list");
+    assert!(warns(quote! { for x in xs { li { + x; } } }));
+    assert!(warns(quote! { for x in xs { let y = x; Item(x = y); } }));
+    assert!(!warns(quote! { for x in xs { li(key = x) { + x; } } }));
+    assert!(!warns(quote! { for x in xs { li; li; } }));
+    assert!(!warns(quote! { for x in xs { + x; } }));
+}
+
+#[test]
+fn test_memo() {
+    let expansion = crate::run(quote! { memo(items, n = items.len()) { + n; } }).unwrap();
+    assert!(expansion.to_string().contains(":: defy :: __private :: memo"));
+
+    let input: ast::Input = syn::parse2(quote! { memo(x = 1) { br; } memo(x = 1); }).unwrap();
+    assert!(matches!(input.nodes.stmts[0], ast::Stmt::Memo(_)));
+    assert!(matches!(input.nodes.stmts[1], ast::Stmt::Node(_)));
+}
+
+#[test]
+fn test_once() {
+    let expansion = crate::run(quote! { once { br; } }).unwrap();
+    assert!(expansion.to_string().contains(":: defy :: __private :: memo (()"));
+
+    let input: ast::Input = syn::parse2(quote! { once { br; } once; }).unwrap();
+    assert!(matches!(input.nodes.stmts[0], ast::Stmt::Once(_)));
+    assert!(matches!(input.nodes.stmts[1], ast::Stmt::Node(_)));
+}
+
+#[test]
+fn test_repeat() {
+    let expansion = crate::run(quote! { repeat 3 { li; } }).unwrap().to_string();
+    assert!(!expansion.contains("warn_unkeyed_list"));
+
+    let input: ast::Input = syn::parse2(quote! { repeat count as i { br; } repeat; }).unwrap();
+    let ast::Stmt::Repeat(repeat) = &input.nodes.stmts[0] else { panic!() };
+    assert!(repeat.as_.is_some());
+    assert!(matches!(input.nodes.stmts[1], ast::Stmt::Node(_)));
+}
+
+#[test]
+fn test_raw_html() {
+    use crate::raw::check;
+
+    assert!(check(r#"<p class="a>b">Hi<br>there<img src="x"/></p><!-- <div> -->"#).is_ok());
+    assert!(check("<ul><li>a</li></ul><script>if (a < b) { x = '</p>' }</script>").is_ok());
+    assert!(check("1 < 2 <!DOCTYPE html>").is_ok());
+    assert_eq!(
+        check("<div><span></div>").unwrap_err(),
+        "`</div>` does not match the open `<span>`"
+    );
+    assert_eq!(check("<b>bold").unwrap_err(), "`<b>` is not closed");
+    assert_eq!(check("</b>").unwrap_err(), "`</b>` has no matching start tag");
+    assert!(check("<p><div></div></p>").is_err());
+    assert!(check("<a href=\"/\"><a></a></a>").is_err());
+    assert!(check("<div class=\"x>").is_err());
+
+    let err = crate::run(quote! { raw "<b>"; }).err().unwrap();
+    assert!(err.to_string().starts_with("DEFY0037: "));
+}
+
+#[test]
+fn test_iter_macro_path() {
+    let output = crate::expand_iter(quote! { @macro_path my_html br; });
+    let output = output.to_string();
+    assert!(output.contains("my_html ! { }"), "{output}");
+    assert!(!output.contains("yew :: Html"), "{output}");
+}
+
+#[test]
+fn test_await_deps() {
+    let output = crate::run(quote! { await(id) fetch(id) as user { + user; } }).unwrap();
+    let args = quote!((::std::clone::Clone::clone(&(id)),), move |(id,)| fetch(id),);
+    assert!(output.to_string().contains(&format!("suspend ({args}")), "{output}");
+}
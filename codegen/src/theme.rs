@@ -0,0 +1,67 @@
+//! Design token substitution for `@theme`.
+
+use proc_macro2::{Group, Literal, TokenStream, TokenTree};
+use quote::{quote_spanned, TokenStreamExt};
+use syn::{Error, Result};
+
+/// Replaces each `token!(dotted.key)` in `ts` with the literal value of the key in `theme`.
+pub fn substitute(theme: &toml::Table, ts: TokenStream) -> Result<TokenStream> {
+    let mut output = TokenStream::new();
+    let mut iter = ts.into_iter().peekable();
+    while let Some(tt) = iter.next() {
+        match tt {
+            TokenTree::Ident(ident)
+                if ident == "token"
+                    && matches!(iter.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '!') =>
+            {
+                let bang = iter.next().expect("peeked");
+                let Some(TokenTree::Group(group)) = iter.next() else {
+                    return Err(Error::new(bang.span(), "DEFY0012: expected `token!(key)`"));
+                };
+
+                let key = group.stream().to_string().replace(' ', "");
+                let mut parts = key.split('.');
+                let value = parts
+                    .next()
+                    .and_then(|first| theme.get(first))
+                    .and_then(|value| parts.try_fold(value, |value, part| value.get(part)));
+                let span = ident.span().join(group.span()).unwrap_or(group.span());
+                output.extend(match value {
+                    Some(toml::Value::String(value)) => quote_spanned!(span => #value),
+                    Some(toml::Value::Integer(value)) => {
+                        let value = Literal::i64_unsuffixed(*value);
+                        quote_spanned!(span => #value)
+                    }
+                    Some(toml::Value::Float(value)) if value.is_finite() => {
+                        let value = Literal::f64_unsuffixed(*value);
+                        quote_spanned!(span => #value)
+                    }
+                    Some(toml::Value::Boolean(value)) => quote_spanned!(span => #value),
+                    Some(_) => {
+                        return Err(Error::new(
+                            span,
+                            format_args!(
+                                "DEFY0011: theme token `{key}` is not a string, finite number or \
+                                 boolean"
+                            ),
+                        ))
+                    }
+                    None => {
+                        return Err(Error::new(
+                            span,
+                            format_args!("DEFY0010: theme token `{key}` is not defined"),
+                        ))
+                    }
+                });
+            }
+            TokenTree::Group(group) => {
+                let mut new_group =
+                    Group::new(group.delimiter(), substitute(theme, group.stream())?);
+                new_group.set_span(group.span());
+                output.append(new_group);
+            }
+            tt => output.append(tt),
+        }
+    }
+    Ok(output)
+}
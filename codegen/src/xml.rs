@@ -0,0 +1,167 @@
+//! Elements and declarations of XML documents rendered with `@xml`.
+//!
+//! yew treats HTML void elements such as `link` specially and cannot render namespaced names,
+//! so XML elements are emitted as raw tags with their attribute values escaped at runtime.
+
+use proc_macro2::TokenStream;
+use quote::quote_spanned;
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{Error, Result};
+
+use crate::{ast, Config};
+
+/// Converts a node into raw XML tags.
+///
+/// Path segments are joined with `:`, e.g. `image::loc` is rendered as `<image:loc>`.
+/// Elements without children are rendered as empty-element tags, e.g. `<br/>`.
+pub fn node_to_html(
+    config: &Config,
+    element: syn::Path,
+    args: ast::NodeArgs,
+    body: ast::NodeBody,
+) -> Result<TokenStream> {
+    let macro_path = &config.macro_path;
+    let span = element.span();
+    let name = element
+        .segments
+        .iter()
+        .map(|segment| {
+            if !segment.arguments.is_none() {
+                return Err(Error::new_spanned(
+                    &segment.arguments,
+                    "DEFY0035: XML element names cannot have generic arguments",
+                ));
+            }
+            Ok(segment.ident.unraw().to_string())
+        })
+        .collect::<Result<Vec<_>>>()?
+        .join(":");
+
+    let attrs = match args {
+        ast::NodeArgs::None => Vec::new(),
+        ast::NodeArgs::Rest { eq, arg: _ } => {
+            return Err(Error::new(
+                eq.span,
+                "DEFY0035: XML elements cannot take props with `= expr`; use `(name = value)`",
+            ))
+        }
+        ast::NodeArgs::Named { paren: _, args } => {
+            args.into_iter().map(attr).collect::<Result<Vec<_>>>()?
+        }
+    };
+
+    Ok(match body {
+        ast::NodeBody::Semi(semi) => quote_spanned! { semi.span =>
+            { ::defy::__private::xml_tag(#name, &[#(#attrs),*], true) }
+        },
+        ast::NodeBody::Braced { braces, children } => {
+            let children = crate::emit(config, braces.span.join(), children)?;
+            quote_spanned! { span =>
+                { #macro_path! {
+                    <>
+                        { ::defy::__private::xml_tag(#name, &[#(#attrs),*], false) }
+                        { #children }
+                        { ::defy::__private::xml_close(#name) }
+                    </>
+                } }
+            }
+        }
+    })
+}
+
+/// Converts an attribute into a `(name, value)` tuple.
+fn attr(arg: ast::NodeArg) -> Result<TokenStream> {
+    let ast::NodeArg { bind, at, prefix, ident, keys, modifiers, value } = arg;
+    if let Some((bind, _)) = bind {
+        return Err(Error::new(bind.span, "DEFY0035: XML elements cannot have `bind:` attributes"));
+    }
+    if let Some(at) = at {
+        return Err(Error::new(at.span, "DEFY0035: XML elements cannot have event handlers"));
+    }
+    if let Some((paren, _)) = keys {
+        return Err(Error::new(
+            paren.span.join(),
+            "DEFY0035: XML attributes cannot have key filters",
+        ));
+    }
+    if let Some((dot, _)) = modifiers.first() {
+        return Err(Error::new(dot.span, "DEFY0035: XML attributes cannot have modifiers"));
+    }
+    let mut name: String = match &prefix {
+        Some((prefix, _)) => format!("{}:", prefix.unraw()),
+        None => String::new(),
+    };
+    name.push_str(
+        &ident.iter().map(|ident| ident.unraw().to_string()).collect::<Vec<_>>().join("-"),
+    );
+    let Some((_, value)) = value else {
+        return Err(Error::new(
+            ident.span(),
+            format_args!("DEFY0035: XML attribute `{name}` requires a value"),
+        ));
+    };
+    Ok(quote_spanned! { value.span() =>
+        (#name, ::std::string::ToString::to_string(&#value))
+    })
+}
+
+/// Converts `xml_decl(version = "1.0", ...);` into an XML declaration.
+///
+/// The arguments must be string literals, since the declaration is built at compile time.
+pub fn decl_to_html(decl: ast::XmlDecl) -> Result<TokenStream> {
+    let ast::XmlDecl { xml_decl_, args, semi: _ } = decl;
+    let mut out = String::from("<?xml");
+    match args {
+        ast::NodeArgs::None => {}
+        ast::NodeArgs::Rest { eq, arg: _ } => {
+            return Err(Error::new(
+                eq.span,
+                "DEFY0035: expected `xml_decl(name = \"value\", ...);`",
+            ))
+        }
+        ast::NodeArgs::Named { paren: _, args } => {
+            for arg in args {
+                let value = match &arg.value {
+                    Some((_, value)) => match &**value {
+                        syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) => lit.value(),
+                        value => {
+                            return Err(Error::new_spanned(
+                                value,
+                                "DEFY0035: the arguments of `xml_decl` must be string literals",
+                            ))
+                        }
+                    },
+                    None => {
+                        return Err(Error::new(
+                            arg.ident.span(),
+                            "DEFY0035: the arguments of `xml_decl` must be string literals",
+                        ))
+                    }
+                };
+                if arg.bind.is_some() || arg.at.is_some() || arg.prefix.is_some() {
+                    return Err(Error::new(
+                        arg.ident.span(),
+                        "DEFY0035: expected `xml_decl(name = \"value\", ...);`",
+                    ));
+                }
+                let name = crate::arg_name(&arg.ident);
+                out.push_str(&format!(" {name}=\"{}\"", escape(&value)));
+            }
+        }
+    }
+    out.push_str("?>");
+    Ok(quote_spanned! { xml_decl_.span =>
+        { ::yew::Html::from_html_unchecked(::yew::AttrValue::Static(#out)) }
+    })
+}
+
+/// Escapes a string for an XML attribute value.
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
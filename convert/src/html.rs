@@ -0,0 +1,299 @@
+//! Converts HTML snippets.
+//!
+//! The parser is lenient like browsers are:
+//! unclosed elements are closed at the end of their parent,
+//! and stray closing tags are ignored.
+
+use crate::Stmt;
+
+/// Elements that never have children.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements whose contents are not parsed as HTML.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title"];
+
+/// Elements that are implicitly closed by an opening tag of the same name.
+const SELF_CLOSING_SIBLINGS: &[&str] = &["dd", "dt", "li", "option", "p", "td", "th", "tr"];
+
+/// Converts an HTML snippet to defy syntax with `indent` leading spaces.
+pub fn html2defy(html: &str, indent: usize) -> String { crate::print(&convert(html), indent) }
+
+/// Converts an HTML snippet to defy statements.
+pub fn convert(html: &str) -> Vec<Stmt> {
+    let mut parser = Parser { html, pos: 0 };
+    let (stmts, _) = parser.children(&[], false);
+    stmts
+}
+
+struct Parser<'a> {
+    html: &'a str,
+    pos:  usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str { &self.html[self.pos..] }
+
+    /// Parses children until the closing tag of any element in `open`, which is left unconsumed.
+    ///
+    /// Returns the name of the closed element, or `None` at the end of input.
+    fn children(&mut self, open: &[String], preformatted: bool) -> (Vec<Stmt>, Option<String>) {
+        let mut stmts = Vec::new();
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                return (stmts, None);
+            }
+
+            if let Some(comment) = rest.strip_prefix("<!--") {
+                let len = comment.find("-->").unwrap_or(comment.len());
+                let text = comment[..len].trim();
+                if !text.is_empty() {
+                    stmts.push(Stmt::Comment(text.to_string()));
+                }
+                self.pos += 4 + (len + 3).min(comment.len());
+            } else if rest.starts_with("<!") || rest.starts_with("<?") {
+                // doctypes and processing instructions have no defy equivalent
+                self.pos += rest.find('>').map_or(rest.len(), |end| end + 1);
+            } else if let Some(close) = rest.strip_prefix("</") {
+                let len = close.find('>').unwrap_or(close.len());
+                let name = close[..len].trim().to_string();
+                if open.iter().any(|open| open.eq_ignore_ascii_case(&name)) {
+                    return (stmts, Some(name));
+                }
+                self.pos += 2 + (len + 1).min(close.len());
+            } else if rest.starts_with('<')
+                && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic())
+            {
+                if let Some(current) = open.last().filter(|current| closes_sibling(current, rest)) {
+                    return (stmts, Some(current.clone()));
+                }
+                stmts.extend(self.element(open, preformatted));
+            } else {
+                let first = rest.chars().next().map_or(0, char::len_utf8);
+                let len = rest[first..].find('<').map_or(rest.len(), |len| len + first);
+                let text = decode_entities(&rest[..len]);
+                let next = &rest[len..];
+                let has_next = !next.is_empty()
+                    && !next.starts_with("</")
+                    && !open.last().is_some_and(|current| closes_sibling(current, next));
+                self.pos += len;
+                push_text(&mut stmts, &text, preformatted, has_next);
+            }
+        }
+    }
+
+    /// Parses an element starting at `<`.
+    fn element(&mut self, open: &[String], preformatted: bool) -> Vec<Stmt> {
+        self.pos += 1;
+        let name = tag_name(self.rest()).to_string();
+        self.pos += name.len();
+
+        let mut stmts = Vec::new();
+        let mut args = Vec::new();
+        let self_closing = loop {
+            self.pos += self.rest().len() - self.rest().trim_start().len();
+            let rest = self.rest();
+            if rest.is_empty() {
+                break true;
+            }
+            if let Some(after) = rest.strip_prefix("/>") {
+                self.pos = self.html.len() - after.len();
+                break true;
+            }
+            if let Some(after) = rest.strip_prefix('>') {
+                self.pos = self.html.len() - after.len();
+                break false;
+            }
+            if let Some(after) = rest.strip_prefix('/') {
+                self.pos = self.html.len() - after.len();
+                continue;
+            }
+
+            let len = rest
+                .find(|c: char| c.is_whitespace() || matches!(c, '=' | '>' | '/'))
+                .unwrap_or(rest.len())
+                .max(1);
+            let attr = rest[..len].to_string();
+            self.pos += len;
+            let value = self.attr_value();
+
+            if !is_kebab_ident(&attr) {
+                stmts.push(Stmt::Todo(format!(
+                    "the attribute `{attr}` on `<{name}>` cannot be written in defy syntax"
+                )));
+            } else if attr.to_ascii_lowercase().starts_with("on") {
+                stmts.push(Stmt::Todo(format!(
+                    "inline event handler `{attr}` on `<{name}>` must be rewritten as a callback: \
+                     {}",
+                    value.as_deref().unwrap_or_default()
+                )));
+            } else {
+                let value = match value {
+                    Some(value) => format!("{value:?}"),
+                    None => "true".to_string(),
+                };
+                args.push((attr, Some(value)));
+            }
+        };
+
+        let lower = name.to_ascii_lowercase();
+        let children = if self_closing || VOID_ELEMENTS.contains(&&*lower) {
+            None
+        } else if RAW_TEXT_ELEMENTS.contains(&&*lower) {
+            let rest = self.rest();
+            let end = find_ignore_case(rest, &format!("</{lower}")).unwrap_or(rest.len());
+            let mut children = Vec::new();
+            push_text(&mut children, rest[..end].trim(), true, false);
+            self.pos += end;
+            self.close_tag();
+            Some(children)
+        } else {
+            let open: Vec<_> = open.iter().cloned().chain([name.clone()]).collect();
+            let (children, closed) = self.children(&open, preformatted || lower == "pre");
+            if closed.is_some_and(|closed| closed.eq_ignore_ascii_case(&name)) {
+                self.close_tag();
+            }
+            Some(children)
+        };
+        let children = children.filter(|children| !children.is_empty());
+
+        if name.contains('-') {
+            stmts.push(Stmt::Todo(format!(
+                "custom element `<{name}>` cannot be written in defy syntax; its children follow"
+            )));
+            stmts.extend(children.into_iter().flatten());
+            return stmts;
+        }
+
+        stmts.push(Stmt::Node { name, args, rest: None, children });
+        stmts
+    }
+
+    /// Parses an optional `= value` after an attribute name.
+    fn attr_value(&mut self) -> Option<String> {
+        let rest = self.rest();
+        let after_eq = rest.trim_start().strip_prefix('=')?.trim_start();
+        let (value, after) = match after_eq.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let value = &after_eq[1..];
+                let len = value.find(quote).unwrap_or(value.len());
+                (&value[..len], &value[(len + 1).min(value.len())..])
+            }
+            _ => {
+                let len = after_eq
+                    .find(|c: char| c.is_whitespace() || c == '>')
+                    .unwrap_or(after_eq.len());
+                after_eq.split_at(len)
+            }
+        };
+        self.pos = self.html.len() - after.len();
+        Some(decode_entities(value))
+    }
+
+    /// Consumes a closing tag, if any.
+    fn close_tag(&mut self) {
+        let rest = self.rest();
+        if rest.starts_with("</") {
+            self.pos += rest.find('>').map_or(rest.len(), |end| end + 1);
+        }
+    }
+}
+
+/// Whether the opening tag at the start of `rest` implicitly closes the `current` element.
+fn closes_sibling(current: &str, rest: &str) -> bool {
+    let name = tag_name(&rest[1..]);
+    current.eq_ignore_ascii_case(name)
+        && SELF_CLOSING_SIBLINGS.contains(&&*name.to_ascii_lowercase())
+}
+
+fn tag_name(tag: &str) -> &str {
+    let len = tag.find(|c: char| c.is_whitespace() || matches!(c, '>' | '/')).unwrap_or(tag.len());
+    &tag[..len]
+}
+
+/// Whether `name` consists of Rust identifiers separated by `-`.
+fn is_kebab_ident(name: &str) -> bool {
+    name.split('-').all(|part| {
+        part.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack.to_ascii_lowercase().find(needle)
+}
+
+/// Pushes a text node, collapsing whitespace unless `preformatted`.
+///
+/// Surrounding whitespace is kept as a single space only between the text and a sibling.
+fn push_text(stmts: &mut Vec<Stmt>, text: &str, preformatted: bool, has_next: bool) {
+    let text = if preformatted {
+        text.to_string()
+    } else {
+        let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if collapsed.is_empty() {
+            return;
+        }
+        let start =
+            if text.starts_with(char::is_whitespace) && !stmts.is_empty() { " " } else { "" };
+        let end = if text.ends_with(char::is_whitespace) && has_next { " " } else { "" };
+        format!("{start}{collapsed}{end}")
+    };
+    if !text.is_empty() {
+        stmts.push(Stmt::Text(format!("{text:?}")));
+    }
+}
+
+/// Decodes character references.
+///
+/// Unknown named references are kept as written.
+pub(crate) fn decode_entities(text: &str) -> String {
+    let mut output = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest[1..].find(';').and_then(|len| {
+            let entity = &rest[1..=len];
+            let ch = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                "copy" => Some('©'),
+                "mdash" => Some('—'),
+                "ndash" => Some('–'),
+                "hellip" => Some('…'),
+                _ => {
+                    let code = match entity.strip_prefix('#') {
+                        Some(hex) if hex.starts_with(['x', 'X']) => {
+                            u32::from_str_radix(&hex[1..], 16).ok()
+                        }
+                        Some(dec) => dec.parse().ok(),
+                        None => None,
+                    };
+                    code.and_then(char::from_u32)
+                }
+            };
+            ch.map(|ch| (ch, len + 2))
+        });
+        match decoded {
+            Some((ch, len)) => {
+                output.push(ch);
+                rest = &rest[len..];
+            }
+            None => {
+                output.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
@@ -0,0 +1,479 @@
+//! Converts JSX/TSX templates.
+//!
+//! JavaScript expressions are copied as written, so they usually need to be rewritten in Rust.
+//! The common idioms are recognized:
+//! `{cond && <X/>}` and `{cond ? <X/> : <Y/>}` become `if`,
+//! and `{items.map(item => <X/>)}` becomes `for`.
+
+use crate::html::decode_entities;
+use crate::Stmt;
+
+/// React property names of HTML elements that differ from the HTML attribute.
+const RENAMED_ATTRS: &[(&str, &str)] = &[
+    ("autoComplete", "autocomplete"),
+    ("autoFocus", "autofocus"),
+    ("className", "class"),
+    ("colSpan", "colspan"),
+    ("contentEditable", "contenteditable"),
+    ("htmlFor", "for"),
+    ("maxLength", "maxlength"),
+    ("minLength", "minlength"),
+    ("readOnly", "readonly"),
+    ("rowSpan", "rowspan"),
+    ("spellCheck", "spellcheck"),
+    ("srcSet", "srcset"),
+    ("tabIndex", "tabindex"),
+];
+
+/// Converts the JSX elements in `source` to defy syntax with `indent` leading spaces.
+///
+/// `source` may be a JSX snippet or a whole module,
+/// in which case each top-level JSX expression is converted separately.
+pub fn jsx2defy(source: &str, indent: usize) -> String {
+    let outputs: Vec<_> = convert(source).iter().map(|stmts| crate::print(stmts, indent)).collect();
+    outputs.join("\n")
+}
+
+/// Converts each top-level JSX expression in `source` to defy statements.
+pub fn convert(source: &str) -> Vec<Vec<Stmt>> {
+    let mut parser = Parser { source, pos: 0 };
+    let mut outputs = Vec::new();
+    while let Some(c) = parser.rest().chars().next() {
+        if parser.skip_literal() {
+            continue;
+        }
+        if parser.at_jsx() {
+            outputs.push(parser.element());
+        } else {
+            parser.pos += c.len_utf8();
+        }
+    }
+    outputs
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    pos:    usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str { &self.source[self.pos..] }
+
+    fn skip_whitespace(&mut self) {
+        self.pos += self.rest().len() - self.rest().trim_start().len();
+    }
+
+    /// Whether a JSX element starts at the current position.
+    fn at_jsx(&self) -> bool {
+        let rest = self.rest();
+        let before = self.source[..self.pos].trim_end();
+        rest.starts_with('<')
+            && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '>')
+            && (before.is_empty()
+                || before.ends_with(['(', '[', '{', ',', '=', ':', '?', '&', '|', '!', ';', '>'])
+                || before.ends_with("return"))
+    }
+
+    /// Skips a string, template literal or comment at the current position.
+    fn skip_literal(&mut self) -> bool {
+        let rest = self.rest();
+        if rest.starts_with("//") {
+            self.pos += rest.find('\n').unwrap_or(rest.len());
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            self.pos += 2 + comment.find("*/").map_or(comment.len(), |len| len + 2);
+        } else if let Some(quote @ ('"' | '\'')) = rest.chars().next() {
+            let mut chars = rest.char_indices().skip(1);
+            let mut end = rest.len();
+            while let Some((index, c)) = chars.next() {
+                if c == '\\' {
+                    chars.next();
+                } else if c == quote || c == '\n' {
+                    end = index + 1;
+                    break;
+                }
+            }
+            self.pos += end;
+        } else if rest.starts_with('`') {
+            self.pos += 1;
+            while let Some(c) = self.rest().chars().next() {
+                if c == '`' {
+                    self.pos += 1;
+                    break;
+                } else if c == '\\' {
+                    self.pos += 1;
+                    self.pos += self.rest().chars().next().map_or(0, char::len_utf8);
+                } else if self.rest().starts_with("${") {
+                    self.pos += 2;
+                    self.scan_js(&|_| false);
+                    self.pos += usize::from(self.rest().starts_with('}'));
+                } else {
+                    self.pos += c.len_utf8();
+                }
+            }
+        } else {
+            return false;
+        }
+        true
+    }
+
+    /// Skips JavaScript until `stop` matches outside brackets,
+    /// or until an unbalanced closing bracket, which is left unconsumed.
+    fn scan_js(&mut self, stop: &dyn Fn(&str) -> bool) {
+        let mut depth = 0usize;
+        while let Some(c) = self.rest().chars().next() {
+            let rest = self.rest();
+            if depth == 0 && stop(rest) {
+                return;
+            }
+            if self.skip_literal() {
+                continue;
+            }
+            if self.at_jsx() {
+                self.element();
+                continue;
+            }
+            match c {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' if depth == 0 => return,
+                ')' | ']' | '}' => depth -= 1,
+                // not the conditional operator
+                '?' if rest.starts_with("?.") || rest.starts_with("??") => self.pos += 1,
+                _ => {}
+            }
+            self.pos += c.len_utf8();
+        }
+    }
+
+    /// Parses a braced JavaScript expression, returning its contents.
+    fn braced(&mut self) -> &'a str {
+        self.pos += 1;
+        let start = self.pos;
+        self.scan_js(&|_| false);
+        let expr = &self.source[start..self.pos];
+        self.pos += usize::from(self.rest().starts_with('}'));
+        expr
+    }
+
+    /// Parses an element or fragment starting at `<`.
+    ///
+    /// Fragments are flattened into their children since defy does not need them.
+    fn element(&mut self) -> Vec<Stmt> {
+        self.pos += 1;
+        if self.rest().starts_with('>') {
+            self.pos += 1;
+            return self.children();
+        }
+
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| {
+                !(c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '.' | ':' | '-'))
+            })
+            .unwrap_or(rest.len());
+        let name = &rest[..len];
+        self.pos += len;
+        let is_component = name.starts_with(|c: char| c.is_ascii_uppercase()) || name.contains('.');
+
+        let mut stmts = Vec::new();
+        let mut args = Vec::new();
+        let has_children = loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.is_empty() || rest.starts_with("/>") {
+                self.pos += rest.len().min(2);
+                break false;
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                break true;
+            }
+            if rest.starts_with('{') {
+                let spread = self.braced();
+                stmts.push(Stmt::Todo(format!(
+                    "cannot convert the spread attribute `{{{spread}}}` on `<{name}>`"
+                )));
+                continue;
+            }
+
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | ':' | '-')))
+                .unwrap_or(rest.len());
+            if len == 0 {
+                // skip unexpected characters
+                self.pos += rest.chars().next().map_or(0, char::len_utf8);
+                continue;
+            }
+            let attr = &rest[..len];
+            self.pos += len;
+
+            self.skip_whitespace();
+            let value = if self.rest().starts_with('=') {
+                self.pos += 1;
+                self.skip_whitespace();
+                let rest = self.rest();
+                match rest.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let Some(len) = rest[1..].find(quote) else {
+                            self.pos += rest.len();
+                            stmts.push(Stmt::Todo(format!(
+                                "the value of `{attr}` on `<{name}>` is an unterminated string"
+                            )));
+                            continue;
+                        };
+                        self.pos += len + 2;
+                        format!("{:?}", decode_entities(&rest[1..=len]))
+                    }
+                    Some('{') => self.braced().trim().to_string(),
+                    _ => {
+                        stmts.push(Stmt::Todo(format!(
+                            "the attribute `{attr}` on `<{name}>` is missing its value"
+                        )));
+                        continue;
+                    }
+                }
+            } else {
+                "true".to_string()
+            };
+
+            if attr.contains(['$', ':']) {
+                stmts.push(Stmt::Todo(format!(
+                    "the attribute `{attr}` on `<{name}>` cannot be written in defy syntax"
+                )));
+            } else if value.starts_with("function")
+                || (value.contains("=>") && !value.starts_with('"'))
+            {
+                stmts.push(Stmt::Todo(format!(
+                    "the closure for `{attr}` on `<{name}>` must be rewritten as a callback: \
+                     {value}"
+                )));
+            } else if value.starts_with('{') {
+                stmts.push(Stmt::Todo(format!(
+                    "the object for `{attr}` on `<{name}>` must be rewritten: {value}"
+                )));
+            } else if is_component {
+                args.push((snake_case(attr), Some(value)));
+            } else {
+                args.push((html_attr(attr), Some(value)));
+            }
+        };
+
+        let children = if has_children { self.children() } else { Vec::new() };
+        let children = if children.is_empty() { None } else { Some(children) };
+
+        if name.contains(['-', ':']) {
+            stmts.push(Stmt::Todo(format!(
+                "custom element `<{name}>` cannot be written in defy syntax; its children follow"
+            )));
+            stmts.extend(children.into_iter().flatten());
+            return stmts;
+        }
+
+        stmts.push(Stmt::Node { name: name.replace('.', "::"), args, rest: None, children });
+        stmts
+    }
+
+    /// Parses children until a closing tag, which is consumed.
+    fn children(&mut self) -> Vec<Stmt> {
+        let mut stmts = Vec::new();
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                return stmts;
+            }
+            if rest.starts_with("</") {
+                self.pos += rest.find('>').map_or(rest.len(), |end| end + 1);
+                return stmts;
+            }
+
+            if rest.starts_with('<') {
+                stmts.extend(self.element());
+            } else if rest.starts_with('{') {
+                let expr = self.braced();
+                stmts.extend(convert_expr(expr));
+            } else {
+                let len = rest.find(['<', '{']).unwrap_or(rest.len());
+                self.pos += len;
+                if let Some(text) = jsx_text(&rest[..len]) {
+                    stmts.push(Stmt::Text(format!("{:?}", decode_entities(&text))));
+                }
+            }
+        }
+    }
+}
+
+/// Converts an embedded `{expr}` child.
+fn convert_expr(expr: &str) -> Vec<Stmt> {
+    let expr = expr.trim();
+    if expr.is_empty() {
+        return Vec::new();
+    }
+    if let Some(comment) = expr.strip_prefix("/*").and_then(|expr| expr.strip_suffix("*/")) {
+        return vec![Stmt::Comment(comment.trim().to_string())];
+    }
+
+    let is_conditional =
+        |rest: &str| rest.starts_with('?') && !rest.starts_with("?.") && !rest.starts_with("??");
+    if let Some(question) = top_level(expr, &is_conditional).first().map(|&index| index + 1) {
+        let branches = &expr[question..];
+        if let Some(&colon) = top_level(branches, &|rest| rest.starts_with(':')).first() {
+            let (then, else_) = (&branches[..colon], &branches[colon + 1..]);
+            if is_jsx(then) || is_jsx(else_) {
+                let else_ = branch(else_);
+                return vec![Stmt::If {
+                    cond:  strip_parens(&expr[..question - 1]).to_string(),
+                    then:  branch(then),
+                    else_: if else_.is_empty() { None } else { Some(else_) },
+                }];
+            }
+        }
+    }
+
+    if let Some(&and) = top_level(expr, &|rest| rest.starts_with("&&")).last() {
+        let then = &expr[and + 2..];
+        if is_jsx(then) {
+            return vec![Stmt::If {
+                cond:  strip_parens(&expr[..and]).to_string(),
+                then:  branch(then),
+                else_: None,
+            }];
+        }
+    }
+
+    if let Some(stmt) = convert_map(expr) {
+        return vec![stmt];
+    }
+
+    vec![Stmt::Text(expr.to_string())]
+}
+
+/// Converts `iter.map(item => <X/>)` to a `for` loop.
+fn convert_map(expr: &str) -> Option<Stmt> {
+    let &map = top_level(expr, &|rest| rest.starts_with(".map(")).last()?;
+    let iter = expr[..map].trim();
+    let callback = expr[map + 5..].trim_end().strip_suffix(')')?;
+
+    let &arrow = top_level(callback, &|rest| rest.starts_with("=>")).first()?;
+    let params = strip_parens(&callback[..arrow]);
+    let body = callback[arrow + 2..].trim();
+    if !is_jsx(body) {
+        return Some(Stmt::Todo(format!("cannot convert the `map` callback: {expr}")));
+    }
+
+    let mut params = params.split(',').map(|param| {
+        // remove TypeScript type annotations
+        param.split(':').next().unwrap_or_default().trim()
+    });
+    let pat = params.next().filter(|pat| !pat.is_empty()).unwrap_or("_");
+    let mut body = branch(body);
+    if let Some(index) = params.next() {
+        body.insert(
+            0,
+            Stmt::Todo(format!("the index parameter `{index}` should come from `.enumerate()`")),
+        );
+    }
+
+    Some(Stmt::For { pat: pat.to_string(), iter: format!("{iter}.iter()"), body })
+}
+
+/// Converts a branch of a conditional expression.
+fn branch(expr: &str) -> Vec<Stmt> {
+    let expr = strip_parens(expr);
+    if matches!(expr, "null" | "undefined" | "false" | "\"\"" | "''") {
+        Vec::new()
+    } else if is_jsx(expr) {
+        convert(expr).into_iter().flatten().collect()
+    } else {
+        vec![Stmt::Text(expr.to_string())]
+    }
+}
+
+/// The positions in `expr` where `stop` matches outside brackets, strings and JSX.
+fn top_level(expr: &str, stop: &dyn Fn(&str) -> bool) -> Vec<usize> {
+    let mut parser = Parser { source: expr, pos: 0 };
+    let mut matches = Vec::new();
+    while !parser.rest().is_empty() {
+        parser.scan_js(stop);
+        if parser.rest().is_empty() {
+            break;
+        }
+        if stop(parser.rest()) {
+            matches.push(parser.pos);
+        }
+        // step over the match or an unbalanced closing bracket
+        parser.pos += parser.rest().chars().next().map_or(0, char::len_utf8);
+    }
+    matches
+}
+
+fn is_jsx(expr: &str) -> bool { strip_parens(expr).starts_with('<') }
+
+/// Removes surrounding whitespace and parentheses.
+fn strip_parens(mut expr: &str) -> &str {
+    loop {
+        expr = expr.trim();
+        match expr.strip_prefix('(').and_then(|inner| inner.strip_suffix(')')) {
+            Some(inner) if balanced(inner) => expr = inner,
+            _ => return expr,
+        }
+    }
+}
+
+/// Whether `expr` has no unbalanced closing brackets, e.g. unlike `a) + (b`.
+fn balanced(expr: &str) -> bool {
+    let mut parser = Parser { source: expr, pos: 0 };
+    parser.scan_js(&|_| false);
+    parser.rest().is_empty()
+}
+
+/// Applies the JSX whitespace rules to text:
+/// lines are trimmed and joined with spaces, and blank lines are removed.
+fn jsx_text(text: &str) -> Option<String> {
+    let lines: Vec<_> = text.split('\n').collect();
+    let last = lines.len() - 1;
+    let lines: Vec<_> = lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let line = if index > 0 { line.trim_start() } else { line };
+            if index < last {
+                line.trim_end()
+            } else {
+                line
+            }
+        })
+        .filter(|line| !line.is_empty())
+        .collect();
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join(" "))
+    }
+}
+
+/// Converts a React property name of an HTML element to the HTML attribute name.
+fn html_attr(attr: &str) -> String {
+    if let Some(&(_, renamed)) = RENAMED_ATTRS.iter().find(|&&(react, _)| react == attr) {
+        return renamed.to_string();
+    }
+    if attr.starts_with("on") {
+        // event handlers are lowercase in yew
+        return attr.to_ascii_lowercase();
+    }
+    attr.to_string()
+}
+
+/// Converts a camelCase component property name to a Rust field name.
+fn snake_case(attr: &str) -> String {
+    let mut output = String::new();
+    for c in attr.chars() {
+        if c.is_ascii_uppercase() {
+            if !output.is_empty() {
+                output.push('_');
+            }
+            output.push(c.to_ascii_lowercase());
+        } else {
+            output.push(c);
+        }
+    }
+    output
+}
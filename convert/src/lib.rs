@@ -0,0 +1,131 @@
+//! Converts templates in other syntaxes to [`defy!`](https://docs.rs/defy) syntax.
+//!
+//! Constructs without a defy equivalent are preserved as `// TODO(defy-convert)` comments
+//! so that they can be migrated by hand.
+
+pub mod html;
+pub mod jsx;
+pub mod yew;
+
+mod tests;
+
+/// A statement in the converted defy syntax.
+///
+/// Expressions, patterns and names are stored as source text.
+pub enum Stmt {
+    /// An element or component.
+    Node {
+        name:     String,
+        /// Attribute names with their values, or `None` for shorthand attributes.
+        args:     Vec<(String, Option<String>)>,
+        /// The base props expression, written as `Comp = props`.
+        rest:     Option<String>,
+        /// `None` for a node without children.
+        children: Option<Vec<Stmt>>,
+    },
+    /// A text or embedded expression child.
+    Text(String),
+    If {
+        cond:  String,
+        then:  Vec<Stmt>,
+        else_: Option<Vec<Stmt>>,
+    },
+    For {
+        pat:  String,
+        iter: String,
+        body: Vec<Stmt>,
+    },
+    /// A comment from the source.
+    Comment(String),
+    /// A construct that could not be converted, kept as a comment.
+    Todo(String),
+}
+
+/// The maximum line width for placing a single child on the same line as its parent.
+const MAX_WIDTH: usize = 100;
+
+/// Prints statements in defy syntax, one per line with `indent` leading spaces.
+pub fn print(stmts: &[Stmt], indent: usize) -> String {
+    let mut output = String::new();
+    for stmt in stmts {
+        print_stmt(stmt, indent, &mut output);
+    }
+    output
+}
+
+fn print_stmt(stmt: &Stmt, indent: usize, output: &mut String) {
+    let pad = " ".repeat(indent);
+    match stmt {
+        Stmt::Node { name, args, rest, children } => {
+            let mut head = name.clone();
+            if !args.is_empty() {
+                let args: Vec<_> = args
+                    .iter()
+                    .map(|(name, value)| match value {
+                        Some(value) => format!("{name} = {value}"),
+                        None => name.clone(),
+                    })
+                    .collect();
+                head = format!("{head}({})", args.join(", "));
+            }
+            if let Some(rest) = rest {
+                head = format!("{head} = {rest}");
+            }
+            match children {
+                None => output.push_str(&format!("{pad}{head};\n")),
+                Some(children) => print_block(&head, children, indent, output),
+            }
+        }
+        Stmt::Text(expr) => output.push_str(&format!("{pad}+ {expr};\n")),
+        Stmt::If { cond, then, else_ } => {
+            let mut block = String::new();
+            print_block(&format!("if {cond}"), then, indent, &mut block);
+            if let Some(else_) = else_ {
+                block.truncate(block.trim_end().len());
+                let mut else_block = String::new();
+                print_block("else", else_, indent, &mut else_block);
+                block.push(' ');
+                block.push_str(else_block.trim_start());
+            }
+            output.push_str(&block);
+        }
+        Stmt::For { pat, iter, body } => {
+            print_block(&format!("for {pat} in {iter}"), body, indent, output);
+        }
+        Stmt::Comment(comment) => {
+            for line in comment.lines() {
+                output.push_str(&format!("{pad}// {}\n", line.trim()));
+            }
+        }
+        Stmt::Todo(message) => {
+            for line in message.lines() {
+                output.push_str(&format!("{pad}// TODO(defy-convert): {line}\n"));
+            }
+        }
+    }
+}
+
+fn print_block(head: &str, children: &[Stmt], indent: usize, output: &mut String) {
+    let pad = " ".repeat(indent);
+    match children {
+        [] => output.push_str(&format!("{pad}{head} {{}}\n")),
+        [child @ (Stmt::Text(_) | Stmt::Node { children: None, .. })] => {
+            let inner = print(std::slice::from_ref(child), 0);
+            let line = format!("{pad}{head} {{ {} }}", inner.trim_end());
+            if !line.contains('\n') && line.chars().count() <= MAX_WIDTH {
+                output.push_str(&line);
+                output.push('\n');
+            } else {
+                print_block_lines(head, children, indent, output);
+            }
+        }
+        _ => print_block_lines(head, children, indent, output),
+    }
+}
+
+fn print_block_lines(head: &str, children: &[Stmt], indent: usize, output: &mut String) {
+    let pad = " ".repeat(indent);
+    output.push_str(&format!("{pad}{head} {{\n"));
+    output.push_str(&print(children, indent + 4));
+    output.push_str(&format!("{pad}}}\n"));
+}
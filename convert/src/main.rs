@@ -0,0 +1,78 @@
+//! Converts templates to defy syntax.
+//!
+//! ```text
+//! defy-convert yew [FILE]...
+//! defy-convert html [FILE]...
+//! defy-convert jsx [FILE]...
+//! ```
+//!
+//! `yew` replaces the `html!` invocations in Rust source files in place.
+//! `html` and `jsx` print the defy syntax for HTML and JSX/TSX files to stdout.
+//! Without files, stdin is converted to stdout.
+
+use std::io::{self, Read};
+use std::process::ExitCode;
+use std::{env, fs};
+
+const USAGE: &str = "usage: defy-convert (yew | html | jsx) [FILE]...";
+
+type Convert = fn(&str) -> Result<String, String>;
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+    let (convert, in_place): (Convert, _) = match args.next().as_deref() {
+        Some("yew") => (
+            |source| defy_convert::yew::convert_source(source).map_err(|err| err.to_string()),
+            true,
+        ),
+        Some("html") => (|source| Ok(defy_convert::html::html2defy(source, 0)), false),
+        Some("jsx") => (|source| Ok(defy_convert::jsx::jsx2defy(source, 0)), false),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let files: Vec<_> = args.collect();
+
+    if files.is_empty() {
+        let mut source = String::new();
+        if let Err(err) = io::stdin().read_to_string(&mut source) {
+            eprintln!("cannot read stdin: {err}");
+            return ExitCode::FAILURE;
+        }
+        return match convert(&source) {
+            Ok(output) => {
+                print!("{output}");
+                ExitCode::SUCCESS
+            }
+            Err(err) => {
+                eprintln!("<stdin>: {err}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    let mut success = true;
+    for file in files {
+        let result = fs::read_to_string(&file)
+            .map_err(|err| err.to_string())
+            .and_then(|source| convert(&source))
+            .and_then(|output| {
+                if in_place {
+                    fs::write(&file, output).map_err(|err| err.to_string())
+                } else {
+                    print!("{output}");
+                    Ok(())
+                }
+            });
+        if let Err(err) = result {
+            eprintln!("{file}: {err}");
+            success = false;
+        }
+    }
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
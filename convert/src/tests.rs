@@ -0,0 +1,142 @@
+#![cfg(test)]
+
+#[test]
+fn test_yew() {
+    let source = r#"
+fn view() -> Html {
+    html! {
+        <>
+            <h1 class="title">{ "Hello" }</h1>
+            <ul>
+                { for items.iter().map(render) }
+            </ul>
+            if logged_in {
+                <Profile user={user.clone()} {onclick} />
+            } else if guest {
+                <p>{"guest"}</p>
+            } else {
+                <a href="/login">{ "Log in" }</a>
+            }
+            <my-element />
+            <br />
+        </>
+    }
+}
+"#;
+    let expected = r#"
+fn view() -> Html {
+    defy! {
+        h1(class = "title") { + "Hello"; }
+        ul {
+            for item in items.iter().map(render) { + item; }
+        }
+        if logged_in { Profile(user = user.clone(), onclick); } else {
+            if guest {
+                p { + "guest"; }
+            } else {
+                a(href = "/login") { + "Log in"; }
+            }
+        }
+        // TODO(defy-convert): custom element `<my-element>` cannot be written in defy syntax; its children follow
+        br;
+    }
+}
+"#;
+    assert_eq!(crate::yew::convert_source(source).unwrap(), expected);
+}
+
+#[test]
+fn test_html() {
+    let html = r#"<!DOCTYPE html>
+<div class="card" data-card-id=3 hidden>
+    <!-- header -->
+    <h1>Hello &amp; <b>welcome</b>!</h1>
+    <ul>
+        <li>One
+        <li>Two
+    </ul>
+    <img src="a.png" alt="">
+    <button onclick="go()" @click="go">Go</button>
+    <my-widget><span>inner</span></my-widget>
+</div>
+"#;
+    let expected = r#"div(class = "card", data-card-id = "3", hidden = true) {
+    // header
+    h1 {
+        + "Hello & ";
+        b { + "welcome"; }
+        + "!";
+    }
+    ul {
+        li { + "One"; }
+        li { + "Two"; }
+    }
+    img(src = "a.png", alt = "");
+    // TODO(defy-convert): inline event handler `onclick` on `<button>` must be rewritten as a callback: go()
+    // TODO(defy-convert): the attribute `@click` on `<button>` cannot be written in defy syntax
+    button { + "Go"; }
+    // TODO(defy-convert): custom element `<my-widget>` cannot be written in defy syntax; its children follow
+    span { + "inner"; }
+}
+"#;
+    assert_eq!(crate::html::html2defy(html, 0), expected);
+}
+
+#[test]
+fn test_jsx() {
+    let source = r#"
+import React from "react";
+
+export function List({ items, user }: Props) {
+    const title = "It's a <list>";
+    return (
+        <>
+            {/* items */}
+            <h1 className="title" tabIndex={0}>Hello, {user.name}!</h1>
+            {user.admin && <AdminPanel userId={user.id} />}
+            {items.length > 0 ? (
+                <ul>
+                    {items.map((item) => <li key={item.id}>{item.label}</li>)}
+                </ul>
+            ) : <p>Don't panic</p>}
+            <button onClick={() => setCount(count + 1)} style={{ color: "red" }}>
+                Add
+            </button>
+        </>
+    );
+}
+"#;
+    let expected = r#"// items
+h1(class = "title", tabindex = 0) {
+    + "Hello, ";
+    + user.name;
+    + "!";
+}
+if user.admin { AdminPanel(user_id = user.id); }
+if items.length > 0 {
+    ul {
+        for item in items.iter() {
+            li(key = item.id) { + item.label; }
+        }
+    }
+} else {
+    p { + "Don't panic"; }
+}
+// TODO(defy-convert): the closure for `onClick` on `<button>` must be rewritten as a callback: () => setCount(count + 1)
+// TODO(defy-convert): the object for `style` on `<button>` must be rewritten: { color: "red" }
+button { + "Add"; }
+"#;
+    assert_eq!(crate::jsx::jsx2defy(source, 0), expected);
+}
+
+#[test]
+fn test_jsx_missing_value() {
+    assert_eq!(
+        crate::jsx::jsx2defy(r#"<a href="foo"#, 0),
+        "// TODO(defy-convert): the value of `href` on `<a>` is an unterminated string\na;\n",
+    );
+    assert_eq!(
+        crate::jsx::jsx2defy("<a x= />", 0),
+        "// TODO(defy-convert): the attribute `x` on `<a>` is missing its value\na;\n",
+    );
+}
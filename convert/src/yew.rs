@@ -0,0 +1,263 @@
+//! Converts `yew::html!` invocations.
+
+use defy_fmt::source::{find_macros, has_comments, line_indent, source_text, LineIndex};
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::ext::IdentExt;
+use syn::parse::{ParseStream, Parser};
+use syn::spanned::Spanned;
+use syn::{Error, Result, Token};
+
+use crate::Stmt;
+
+/// Replaces the `html!` invocations in a Rust source file with equivalent `defy!` invocations.
+///
+/// Invocations containing comments are left unchanged, since the comments would be lost.
+/// Nested invocations, e.g. in closures, are also left unchanged.
+pub fn convert_source(source: &str) -> Result<String> {
+    let file = syn::parse_file(source)?;
+    let lines = LineIndex::new(source);
+    let mut output = String::new();
+    let mut copied = 0;
+    for mac in find_macros(&file, &["html"]) {
+        let (open, close) = match &mac.delimiter {
+            syn::MacroDelimiter::Paren(delim) => (delim.span.open(), delim.span.close()),
+            syn::MacroDelimiter::Brace(delim) => (delim.span.open(), delim.span.close()),
+            syn::MacroDelimiter::Bracket(delim) => (delim.span.open(), delim.span.close()),
+        };
+        let (open, close) = (lines.range(open), lines.range(close));
+        if has_comments(source, &lines, open.end, mac.tokens.clone(), close.start) {
+            continue;
+        }
+
+        let stmts = convert_tokens(mac.tokens.clone())?;
+        let start = lines.offset(mac.path.span().start());
+        let base = line_indent(source, start);
+        output.push_str(&source[copied..start]);
+        if stmts.is_empty() {
+            output.push_str("defy! {}");
+        } else {
+            output.push_str(&format!(
+                "defy! {{\n{}{}}}",
+                crate::print(&stmts, base + 4),
+                " ".repeat(base)
+            ));
+        }
+        copied = close.end;
+    }
+    output.push_str(&source[copied..]);
+    Ok(output)
+}
+
+/// Converts the body of an `html!` invocation.
+pub fn convert_tokens(tokens: TokenStream) -> Result<Vec<Stmt>> {
+    (|input: ParseStream| parse_children(input, None)).parse2(tokens)
+}
+
+/// Parses children until the closing tag `</closing>`, or until the end if `closing` is `None`.
+fn parse_children(input: ParseStream, closing: Option<&str>) -> Result<Vec<Stmt>> {
+    let mut stmts = Vec::new();
+    loop {
+        if input.is_empty() {
+            return match closing {
+                Some(name) => Err(input.error(format_args!("unclosed element `<{name}>`"))),
+                None => Ok(stmts),
+            };
+        }
+
+        if input.peek(Token![<]) && input.peek2(Token![/]) {
+            let lt: Token![<] = input.parse()?;
+            input.parse::<Token![/]>()?;
+            let name = parse_until_gt(input)?;
+            let Some(expected) = closing else {
+                return Err(Error::new(lt.span, "unexpected closing tag"));
+            };
+            if name.to_string().replace(' ', "") != expected.replace(' ', "") {
+                return Err(Error::new(
+                    lt.span,
+                    format_args!("closing tag does not match `<{expected}>`"),
+                ));
+            }
+            return Ok(stmts);
+        } else if input.peek(Token![<]) {
+            stmts.extend(parse_element(input)?);
+        } else if input.peek(Token![if]) {
+            stmts.push(parse_if(input)?);
+        } else if input.peek(syn::token::Brace) {
+            let block: proc_macro2::Group = input.parse()?;
+            stmts.extend(convert_block(block.stream()));
+        } else if input.peek(syn::Lit) {
+            let lit: syn::Lit = input.parse()?;
+            stmts.push(Stmt::Text(source_text(&lit)));
+        } else {
+            return Err(input.error("expected an element, a block or a literal"));
+        }
+    }
+}
+
+/// Converts a `{ ... }` child.
+fn convert_block(tokens: TokenStream) -> Option<Stmt> {
+    let mut iter = tokens.clone().into_iter();
+    match iter.next() {
+        None => None,
+        Some(TokenTree::Ident(ident)) if ident == "for" => {
+            let iter: TokenStream = iter.collect();
+            Some(Stmt::For {
+                pat:  "item".into(),
+                iter: source_text(&iter),
+                body: vec![Stmt::Text("item".into())],
+            })
+        }
+        Some(_) => Some(Stmt::Text(source_text(&tokens))),
+    }
+}
+
+/// Parses an element or fragment after `<`.
+///
+/// Fragments are flattened into their children since defy does not need them.
+fn parse_element(input: ParseStream) -> Result<Vec<Stmt>> {
+    let lt: Token![<] = input.parse()?;
+
+    if input.peek(Token![>]) {
+        input.parse::<Token![>]>()?;
+        return parse_children(input, Some(""));
+    }
+    if input.peek(Token![@]) {
+        return Err(Error::new(lt.span, "dynamic tags are not supported"));
+    }
+
+    let name = parse_element_name(input)?;
+    let name_text = source_text(&name);
+    let is_custom = name
+        .clone()
+        .into_iter()
+        .any(|tt| matches!(tt, TokenTree::Punct(punct) if punct.as_char() == '-' ));
+
+    let mut args = Vec::new();
+    let mut rest = None;
+    let children = loop {
+        if input.peek(Token![/]) && input.peek2(Token![>]) {
+            input.parse::<Token![/]>()?;
+            input.parse::<Token![>]>()?;
+            break None;
+        }
+        if input.peek(Token![>]) {
+            input.parse::<Token![>]>()?;
+            let children = parse_children(input, Some(&name.to_string()))?;
+            break if children.is_empty() { None } else { Some(children) };
+        }
+
+        if input.peek(Token![..]) {
+            input.parse::<Token![..]>()?;
+            let expr: syn::Expr = input.parse()?;
+            rest = Some(source_text(&expr));
+        } else if input.peek(syn::token::Brace) {
+            let block: proc_macro2::Group = input.parse()?;
+            args.push((block.stream().to_string(), None));
+        } else {
+            let mut attr = input.call(syn::Ident::parse_any)?.to_string();
+            while input.peek(Token![-]) {
+                input.parse::<Token![-]>()?;
+                attr = format!("{attr}-{}", input.call(syn::Ident::parse_any)?);
+            }
+            input.parse::<Token![=]>()?;
+            let value = if input.peek(syn::token::Brace) {
+                let block: proc_macro2::Group = input.parse()?;
+                source_text(&block.stream())
+            } else {
+                let lit: syn::Lit = input.parse()?;
+                source_text(&lit)
+            };
+            args.push((attr, Some(value)));
+        }
+    };
+
+    if is_custom {
+        let mut stmts = vec![Stmt::Todo(format!(
+            "custom element `<{name_text}>` cannot be written in defy syntax; its children follow"
+        ))];
+        stmts.extend(children.into_iter().flatten());
+        return Ok(stmts);
+    }
+
+    let mut todo = None;
+    if rest.is_some() && !args.is_empty() {
+        todo = Some(Stmt::Todo(format!(
+            "`<{name_text}>` has both props and a base props expression, which defy does not \
+             support; the base props were `{}`",
+            rest.take().expect("checked is_some"),
+        )));
+    }
+
+    let node = Stmt::Node { name: name_text, args, rest, children };
+    Ok(todo.into_iter().chain([node]).collect())
+}
+
+/// Parses an element name, e.g. `div`, `my-element`, `a::Comp<T>`.
+fn parse_element_name(input: ParseStream) -> Result<TokenStream> {
+    let mut name = TokenStream::new();
+    name.extend([TokenTree::Ident(input.call(syn::Ident::parse_any)?)]);
+    loop {
+        if input.peek(Token![::]) {
+            let colons: Token![::] = input.parse()?;
+            name.extend(colons.to_token_stream());
+            name.extend([TokenTree::Ident(input.call(syn::Ident::parse_any)?)]);
+        } else if input.peek(Token![-]) && input.peek2(syn::Ident::peek_any) {
+            let dash: Token![-] = input.parse()?;
+            name.extend(dash.to_token_stream());
+            name.extend([TokenTree::Ident(input.call(syn::Ident::parse_any)?)]);
+        } else if input.peek(Token![<]) {
+            let generics: syn::AngleBracketedGenericArguments = input.parse()?;
+            name.extend(generics.to_token_stream());
+        } else {
+            return Ok(name);
+        }
+    }
+}
+
+/// Parses tokens until `>`, consuming the `>`.
+fn parse_until_gt(input: ParseStream) -> Result<TokenStream> {
+    let mut tokens = TokenStream::new();
+    while !input.peek(Token![>]) {
+        if input.is_empty() {
+            return Err(input.error("expected `>`"));
+        }
+        tokens.extend([input.parse::<TokenTree>()?]);
+    }
+    input.parse::<Token![>]>()?;
+    Ok(tokens)
+}
+
+/// Parses `if cond { ... } else ...` after `if`.
+fn parse_if(input: ParseStream) -> Result<Stmt> {
+    input.parse::<Token![if]>()?;
+    let mut cond = TokenStream::new();
+    while !input.peek(syn::token::Brace) {
+        if input.is_empty() {
+            return Err(input.error("expected the body of `if`"));
+        }
+        cond.extend([input.parse::<TokenTree>()?]);
+    }
+    let then = parse_braced_children(input)?;
+
+    let else_ = if input.peek(Token![else]) {
+        input.parse::<Token![else]>()?;
+        if input.peek(Token![if]) {
+            Some(vec![parse_if(input)?])
+        } else {
+            Some(parse_braced_children(input)?)
+        }
+    } else {
+        None
+    };
+
+    Ok(Stmt::If { cond: source_text(&cond), then, else_ })
+}
+
+fn parse_braced_children(input: ParseStream) -> Result<Vec<Stmt>> {
+    let group: proc_macro2::Group = input.parse()?;
+    if group.delimiter() != Delimiter::Brace {
+        return Err(Error::new(group.span(), "expected braces"));
+    }
+    (|input: ParseStream| parse_children(input, None)).parse2(group.stream())
+}
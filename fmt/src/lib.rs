@@ -0,0 +1,629 @@
+//! Formatter for the contents of [`defy!`](https://docs.rs/defy) invocations,
+//! which rustfmt leaves untouched.
+//!
+//! Statements are laid out one per line with normalized indentation and brace placement.
+//! Attribute lists are wrapped one per line when they do not fit in the line width.
+//! Rust expressions, patterns and paths are kept as written.
+//!
+//! Invocations containing comments are left unchanged,
+//! since comments are not retained by the parser.
+
+use defy_syntax as ast;
+use proc_macro2::{Span, TokenStream};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+
+pub mod source;
+mod tests;
+
+use source::{find_macros, has_comments, line_indent, source_text as source, LineIndex};
+
+pub struct Options {
+    /// The number of spaces per indentation level.
+    pub indent:    usize,
+    /// The line width to wrap attribute lists at.
+    pub max_width: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self { Self { indent: 4, max_width: 100 } }
+}
+
+/// Formats all `defy!` and `defy_iter!` invocations in a Rust source file.
+pub fn format_source(source: &str, options: &Options) -> syn::Result<String> {
+    let file = syn::parse_file(source)?;
+    let lines = LineIndex::new(source);
+    let mut output = String::new();
+    let mut copied = 0;
+    for mac in find_macros(&file, &["defy", "defy_iter"]) {
+        let syn::MacroDelimiter::Brace(braces) = &mac.delimiter else { continue };
+        let open = lines.range(braces.span.open());
+        let close = lines.range(braces.span.close());
+        if has_comments(source, &lines, open.end, mac.tokens.clone(), close.start) {
+            continue;
+        }
+
+        let input: ast::Input = mac.parse_body()?;
+        let base = line_indent(source, lines.offset(mac.path.span().start()));
+
+        let mut printer = Printer { options, output: String::new() };
+        printer.input(&input, base + options.indent);
+        let body = if printer.output.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{\n{}{}}}", printer.output, " ".repeat(base))
+        };
+
+        output.push_str(&source[copied..open.start]);
+        output.push_str(&body);
+        copied = close.end;
+    }
+    output.push_str(&source[copied..]);
+    Ok(output)
+}
+
+/// Formats the contents of a `defy!` invocation, without the surrounding braces.
+pub fn format_tokens(tokens: TokenStream, options: &Options) -> syn::Result<String> {
+    let input: ast::Input = syn::parse2(tokens)?;
+    let mut printer = Printer { options, output: String::new() };
+    printer.input(&input, 0);
+    Ok(printer.output)
+}
+
+struct Printer<'a> {
+    options: &'a Options,
+    output:  String,
+}
+
+impl Printer<'_> {
+    fn line(&mut self, indent: usize, line: &str) {
+        self.output.push_str(&" ".repeat(indent));
+        self.output.push_str(line);
+        self.output.push('\n');
+    }
+
+    fn input(&mut self, input: &ast::Input, indent: usize) {
+        for (_, tokens) in &input.configs {
+            let line = source(tokens);
+            self.line(indent, &line);
+        }
+        if !input.configs.is_empty() && !input.nodes.stmts.is_empty() {
+            self.output.push('\n');
+        }
+        self.nodes(&input.nodes, indent);
+    }
+
+    fn nodes(&mut self, nodes: &ast::Nodes, indent: usize) {
+        let mut prev_end = None;
+        for stmt in &nodes.stmts {
+            let (start, end) = stmt_lines(stmt);
+            // preserve a single blank line between statements
+            if prev_end.is_some_and(|prev_end: usize| start > prev_end + 1) {
+                self.output.push('\n');
+            }
+            prev_end = Some(end);
+            self.stmt(stmt, indent);
+        }
+    }
+
+    fn column(&self) -> usize {
+        let line_start = self.output.rfind('\n').map_or(0, |pos| pos + 1);
+        self.output[line_start..].chars().count()
+    }
+
+    /// Prints `head` followed by a braced block of statements, without the final newline.
+    ///
+    /// The current line must already be indented to `indent`.
+    fn block(&mut self, head: &str, nodes: &ast::Nodes, indent: usize) {
+        if nodes.stmts.is_empty() {
+            self.output.push_str(&format!("{head} {{}}"));
+            return;
+        }
+        if let [stmt @ (ast::Stmt::Text(_)
+        | ast::Stmt::Node(ast::Node { body: ast::NodeBody::Semi(_), .. }))] = &nodes.stmts[..]
+        {
+            let mut inner = Printer { options: self.options, output: String::new() };
+            inner.stmt(stmt, 0);
+            let inline = format!("{head} {{ {} }}", inner.output.trim_end());
+            if !inline.contains('\n')
+                && self.column() + inline.chars().count() <= self.options.max_width
+            {
+                self.output.push_str(&inline);
+                return;
+            }
+        }
+
+        self.output.push_str(&format!("{head} {{\n"));
+        self.nodes(nodes, indent + self.options.indent);
+        self.output.push_str(&" ".repeat(indent));
+        self.output.push('}');
+    }
+
+    /// Prints a line with `head` followed by a braced block of statements.
+    fn block_line(&mut self, head: &str, nodes: &ast::Nodes, indent: usize) {
+        self.output.push_str(&" ".repeat(indent));
+        self.block(head, nodes, indent);
+        self.output.push('\n');
+    }
+
+    fn stmt(&mut self, stmt: &ast::Stmt, indent: usize) {
+        match stmt {
+            ast::Stmt::If(if_) => {
+                self.output.push_str(&" ".repeat(indent));
+                let media = if if_.media.is_some() { "media " } else { "" };
+                self.block(&format!("if {media}{}", source(&if_.expr)), &if_.body, indent);
+                if let Some(else_) = &if_.else_ {
+                    self.block(" else", &else_.body, indent);
+                }
+                self.output.push('\n');
+            }
+            ast::Stmt::Match(match_) => {
+                self.line(indent, &format!("match {} {{", source(&match_.expr)));
+                for arm in &match_.arms {
+                    let mut head = source(&arm.pat);
+                    if let Some((_, guard)) = &arm.guard {
+                        head = format!("{head} if {}", source(guard));
+                    }
+                    self.block_line(&format!("{head} =>"), &arm.body, indent + self.options.indent);
+                }
+                self.line(indent, "}");
+            }
+            ast::Stmt::For(for_) => {
+                let head = format!("for {} in {}", source(&for_.pat), source(&for_.iter));
+                self.output.push_str(&" ".repeat(indent));
+                self.block(&head, &for_.body, indent);
+                if let Some(else_) = &for_.else_ {
+                    self.block(" else", &else_.body, indent);
+                }
+                self.output.push('\n');
+            }
+            ast::Stmt::VirtualFor(for_) => {
+                let head = format!(
+                    "virtual for {} in {} ({})",
+                    source(&for_.pat),
+                    source(&for_.iter),
+                    block_args(&for_.args),
+                );
+                self.block_line(&head, &for_.body, indent);
+            }
+            ast::Stmt::Let(let_) => {
+                let mut pat = source(&let_.pat);
+                if let Some((_, ty)) = &let_.ty {
+                    pat = format!("{pat}: {}", source(ty));
+                }
+                self.line(indent, &format!("let {pat} = {};", source(&let_.expr)));
+            }
+            ast::Stmt::Item(item) => self.line(indent, &source(item)),
+            ast::Stmt::Text(text) => self.line(indent, &format!("+ {};", source(&text.expr))),
+            ast::Stmt::Dyn(dyn_) => self.line(indent, &format!("dyn {};", source(&dyn_.expr))),
+            ast::Stmt::Embed(embed) => {
+                self.line(indent, &format!("embed {};", source(&embed.expr)))
+            }
+            ast::Stmt::Include(include) => {
+                self.line(indent, &format!("include {};", source(&include.path)));
+            }
+            ast::Stmt::Markdown(markdown) => {
+                let source = match &markdown.source {
+                    ast::MarkdownSource::File(lit) => source(lit),
+                    ast::MarkdownSource::Expr(expr) => source(expr),
+                };
+                self.line(indent, &format!("markdown {source};"));
+            }
+            ast::Stmt::Translate(translate) => {
+                let mut line = format!("t {}", source(&translate.id));
+                if let Some((_, args)) = &translate.args {
+                    let args: Vec<_> = args
+                        .iter()
+                        .map(|arg| format!("{} = {}", arg.name, source(&arg.value)))
+                        .collect();
+                    line = format!("{line} {{ {} }}", args.join(", "));
+                }
+                self.line(indent, &format!("{line};"));
+            }
+            ast::Stmt::Icon(icon) => {
+                let head = format!("icon {}", source(&icon.path));
+                self.with_args(head, &icon.args, indent, ";");
+            }
+            ast::Stmt::Picture(picture) => {
+                let head = format!("picture {}", source(&picture.path));
+                self.with_args(head, &picture.args, indent, ";");
+            }
+            ast::Stmt::JsonScript(json_script) => {
+                let tail = format!(" = {};", source(&json_script.expr));
+                self.with_args("json_script".to_string(), &json_script.args, indent, &tail);
+            }
+            ast::Stmt::DocumentHead(head) => self.block_line("document_head", &head.body, indent),
+            ast::Stmt::Space(_) => self.line(indent, "space;"),
+            ast::Stmt::Verbatim(verbatim) => {
+                self.line(indent, &format!("verbatim {};", source(&verbatim.text)));
+            }
+            ast::Stmt::Passthrough(block) if block.tokens.is_empty() => {
+                self.line(indent, "passthrough {}")
+            }
+            ast::Stmt::Passthrough(block) => {
+                self.line(indent, &format!("passthrough {{ {} }}", source(&block.tokens)))
+            }
+            ast::Stmt::Raw(raw) => self.line(indent, &format!("raw {};", source(&raw.html))),
+            ast::Stmt::Entity(ast::Entity::Named { name, .. }) => {
+                self.line(indent, &format!("entity {name};"));
+            }
+            ast::Stmt::Entity(ast::Entity::Inline { name, .. }) => {
+                self.line(indent, &format!("&{{ {} }}", source(name)));
+            }
+            ast::Stmt::Og(og) => {
+                self.line(indent, "og {");
+                for property in &og.properties {
+                    let line = format!("{} {};", property.name, source(&property.value));
+                    self.line(indent + self.options.indent, &line);
+                }
+                self.line(indent, "}");
+            }
+            ast::Stmt::Style(style) => {
+                self.line(indent, &format!("style {{ {} }}", source(&style.css)));
+            }
+            ast::Stmt::Memo(memo) => {
+                self.block_line(&format!("memo({})", memo_deps(&memo.deps)), &memo.body, indent);
+            }
+            ast::Stmt::Once(once) => self.block_line("once", &once.body, indent),
+            ast::Stmt::Defer(defer) => {
+                self.output.push_str(&" ".repeat(indent));
+                self.block("defer", &defer.body, indent);
+                if let Some(placeholder) = &defer.placeholder {
+                    self.block(" placeholder", &placeholder.body, indent);
+                }
+                self.output.push('\n');
+            }
+            ast::Stmt::Await(await_) => {
+                self.output.push_str(&" ".repeat(indent));
+                let deps = match &await_.deps {
+                    Some((_, deps)) => format!("({})", memo_deps(deps)),
+                    None => String::new(),
+                };
+                let head = format!("await{deps} {} as {}", source(&await_.expr), await_.ident);
+                self.block(&head, &await_.body, indent);
+                if let Some(pending) = &await_.pending {
+                    self.block(" pending", &pending.body, indent);
+                }
+                self.output.push('\n');
+            }
+            ast::Stmt::Try(try_) => {
+                self.output.push_str(&" ".repeat(indent));
+                self.block("try", &try_.body, indent);
+                if let Some(catch) = &try_.catch {
+                    let mut head = format!(" catch {}", catch.ident);
+                    if let Some((_, ty)) = &catch.ty {
+                        head = format!("{head}: {}", source(ty));
+                    }
+                    self.block(&head, &catch.body, indent);
+                }
+                self.output.push('\n');
+            }
+            ast::Stmt::Repeat(repeat) => {
+                let mut head = format!("repeat {}", source(&repeat.count));
+                if let Some((_, ident)) = &repeat.as_ {
+                    head = format!("{head} as {ident}");
+                }
+                self.block_line(&head, &repeat.body, indent);
+            }
+            ast::Stmt::With(with) => {
+                let head = format!("with {} as {}", source(&with.expr), with.ident);
+                self.block_line(&head, &with.body, indent);
+            }
+            ast::Stmt::Maybe(maybe) => {
+                self.output.push_str(&" ".repeat(indent));
+                let head = format!("maybe {} as {}", source(&maybe.expr), maybe.ident);
+                self.block(&head, &maybe.body, indent);
+                if let Some(else_) = &maybe.else_ {
+                    self.block(" else", &else_.body, indent);
+                }
+                self.output.push('\n');
+            }
+            ast::Stmt::Form(form) => {
+                let mut head = format!("form for {}", source(&form.expr));
+                if let Some(errors) = &form.errors {
+                    head = format!("{head}, errors = {}", source(&errors.expr));
+                }
+                self.line(indent, &format!("{head} {{"));
+                for field in &form.fields {
+                    let head = format!("field {}", field.ident);
+                    self.with_args(head, &field.args, indent + self.options.indent, ";");
+                }
+                self.line(indent, "}");
+            }
+            ast::Stmt::Table(table) => {
+                let head = format!(
+                    "table{} for {} in {} {{",
+                    self.args_single_line(&table.args),
+                    source(&table.pat),
+                    source(&table.iter)
+                );
+                self.line(indent, &head);
+                for column in &table.columns {
+                    let head = format!("column {}", source(&column.header));
+                    self.block_line(&head, &column.body, indent + self.options.indent);
+                }
+                self.line(indent, "}");
+            }
+            ast::Stmt::Options(options) => {
+                self.line(indent, &format!("options from {} {{", source(&options.iter)));
+                let head = format!("|{}| ", source(&options.pat));
+                let inner = indent + self.options.indent;
+                match &options.body {
+                    ast::NodeBody::Semi(_) => self.with_args(head, &options.args, inner, ";"),
+                    ast::NodeBody::Braced { braces: _, children } => {
+                        let head = format!("{head}{}", self.args_single_line(&options.args));
+                        self.block_line(&head, children, inner);
+                    }
+                }
+                self.line(indent, "}");
+            }
+            ast::Stmt::WrapIf(wrap_if) => {
+                let node = &wrap_if.node;
+                let args = self.args_single_line(&node.args);
+                let head =
+                    format!("wrap_if {} {}{args}", source(&wrap_if.expr), source(&node.element));
+                match &node.body {
+                    ast::NodeBody::Braced { braces: _, children } => {
+                        self.block_line(&head, children, indent)
+                    }
+                    ast::NodeBody::Semi(_) => self.line(indent, &format!("{head};")),
+                }
+            }
+            ast::Stmt::XmlDecl(decl) => {
+                self.with_args("xml_decl".to_string(), &decl.args, indent, ";")
+            }
+            ast::Stmt::Doctype(_) => self.line(indent, "doctype html;"),
+            ast::Stmt::Block(block) => {
+                self.block_line(&format!("block {}", block.name), &block.body, indent)
+            }
+            ast::Stmt::Extend(extend) => self.block_line(
+                &format!("extend {}", source(&extend.layout)),
+                &extend.blocks,
+                indent,
+            ),
+            ast::Stmt::Island(island) => self.keyword_node("island", &island.node, indent),
+            ast::Stmt::Lazy(lazy) => self.keyword_node("lazy", &lazy.node, indent),
+            ast::Stmt::Transition(transition) => {
+                let args = block_args(&transition.args);
+                let head = format!("transition({args}) if {}", source(&transition.cond));
+                self.block_line(&head, &transition.body, indent);
+            }
+            ast::Stmt::Do(do_) => self.line(indent, &format!("do {};", source(&do_.expr))),
+            ast::Stmt::Note(note) => match note {
+                ast::Note::Stmt { text, .. } => {
+                    self.line(indent, &format!("note {};", source(text)))
+                }
+                ast::Note::Doc(attr) => self.line(indent, &source(attr)),
+            },
+            ast::Stmt::Attributed(attributed) => {
+                for attr in &attributed.attrs {
+                    self.line(indent, &source(attr));
+                }
+                self.stmt(&attributed.stmt, indent);
+            }
+            ast::Stmt::Break(_) => self.line(indent, "break;"),
+            ast::Stmt::Continue(_) => self.line(indent, "continue;"),
+            ast::Stmt::Node(node) => {
+                for attr in &node.attrs {
+                    let attr = source(attr);
+                    self.line(indent, &attr);
+                }
+                let head = source(&node.element);
+                match &node.body {
+                    ast::NodeBody::Semi(_) => self.with_args(head, &node.args, indent, ";"),
+                    ast::NodeBody::Braced { braces: _, children } => {
+                        let args = self.args_single_line(&node.args);
+                        let head_line = format!("{head}{args}");
+                        if indent + head_line.chars().count() + 2 <= self.options.max_width {
+                            self.block_line(&head_line, children, indent);
+                        } else {
+                            self.with_args(head, &node.args, indent, " {");
+                            self.nodes(children, indent + self.options.indent);
+                            self.line(indent, "}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Prints a node preceded by a statement keyword, e.g. `island Counter(start = 1);`.
+    fn keyword_node(&mut self, keyword: &str, node: &ast::Node, indent: usize) {
+        let head = format!("{keyword} {}", source(&node.element));
+        match &node.body {
+            ast::NodeBody::Braced { braces: _, children } => {
+                let head = format!("{head}{}", self.args_single_line(&node.args));
+                self.block_line(&head, children, indent)
+            }
+            ast::NodeBody::Semi(_) => self.with_args(head, &node.args, indent, ";"),
+        }
+    }
+
+    fn args_single_line(&self, args: &ast::NodeArgs) -> String {
+        match args {
+            ast::NodeArgs::None => String::new(),
+            ast::NodeArgs::Named { paren: _, args } => {
+                let args: Vec<_> = args.iter().map(node_arg).collect();
+                format!("({})", args.join(", "))
+            }
+            ast::NodeArgs::Rest { eq: _, arg } => format!(" = {}", source(arg)),
+        }
+    }
+
+    /// Prints `head` with the node arguments, wrapping them one per line if they are too long.
+    fn with_args(&mut self, head: String, args: &ast::NodeArgs, indent: usize, tail: &str) {
+        let single = format!("{head}{}{tail}", self.args_single_line(args));
+        let ast::NodeArgs::Named { paren: _, args } = args else {
+            self.line(indent, &single);
+            return;
+        };
+        if !single.contains('\n') && indent + single.chars().count() <= self.options.max_width {
+            self.line(indent, &single);
+            return;
+        }
+
+        self.line(indent, &format!("{head}("));
+        for arg in args {
+            self.line(indent + self.options.indent, &format!("{},", node_arg(arg)));
+        }
+        self.line(indent, &format!("){tail}"));
+    }
+}
+
+fn node_arg(arg: &ast::NodeArg) -> String {
+    let mut name = arg.ident.iter().map(|ident| ident.to_string()).collect::<Vec<_>>().join("-");
+    if arg.bind.is_some() {
+        name.insert_str(0, "bind:");
+    }
+    if let Some((prefix, _)) = &arg.prefix {
+        name.insert_str(0, &format!("{prefix}:"));
+    }
+    if arg.at.is_some() {
+        name.insert(0, '@');
+    }
+    if let Some((_, keys)) = &arg.keys {
+        let keys = keys.iter().map(|filter| {
+            let key = match &filter.key {
+                ast::KeyName::Ident(ident) => ident.to_string(),
+                ast::KeyName::Str(lit) => source(lit),
+            };
+            match &filter.modifiers {
+                None => key,
+                Some((_, modifiers)) => {
+                    let modifiers = modifiers.iter().map(ToString::to_string);
+                    format!("{key} with {}", modifiers.collect::<Vec<_>>().join(" + "))
+                }
+            }
+        });
+        name.push_str(&format!("({})", keys.collect::<Vec<_>>().join(" | ")));
+    }
+    for (_, modifier) in &arg.modifiers {
+        name.push_str(&format!(".{modifier}"));
+    }
+    match &arg.value {
+        None => name,
+        Some((_, value)) => format!("{name} = {}", source(value)),
+    }
+}
+
+/// The arguments of a `transition` or `virtual for` block, without parentheses.
+fn block_args(args: &Punctuated<ast::BlockArg, syn::Token![,]>) -> String {
+    let args: Vec<_> =
+        args.iter().map(|arg| format!("{} = {}", arg.ident, source(&arg.value))).collect();
+    args.join(", ")
+}
+
+/// The dependencies of a `memo` or `await` block, without parentheses.
+fn memo_deps(deps: &Punctuated<ast::MemoDep, syn::Token![,]>) -> String {
+    let deps: Vec<_> = deps
+        .iter()
+        .map(|dep| match &dep.value {
+            None => dep.ident.to_string(),
+            Some((_, value)) => format!("{} = {}", dep.ident, source(value)),
+        })
+        .collect();
+    deps.join(", ")
+}
+
+/// The first and last lines of a statement in the source.
+fn stmt_lines(stmt: &ast::Stmt) -> (usize, usize) {
+    let (start, end) = match stmt {
+        ast::Stmt::If(if_) => (
+            if_.if_.span,
+            if_.else_.as_ref().map_or(if_.braces.span.close(), |else_| else_.braces.span.close()),
+        ),
+        ast::Stmt::Match(match_) => (match_.match_.span, match_.braces.span.close()),
+        ast::Stmt::For(for_) => (
+            for_.for_.span,
+            for_.else_.as_ref().map_or(for_.braces.span.close(), |else_| else_.braces.span.close()),
+        ),
+        ast::Stmt::Let(let_) => (let_.let_.span, let_.semi.span),
+        ast::Stmt::Item(item) => (item.span(), item.span()),
+        ast::Stmt::Text(text) => (text.add.span, text.semi.span),
+        ast::Stmt::Embed(embed) => (embed.embed_.span, embed.semi.span),
+        ast::Stmt::Dyn(dyn_) => (dyn_.dyn_.span, dyn_.semi.span),
+        ast::Stmt::Include(include) => (include.include_.span, include.semi.span),
+        ast::Stmt::Markdown(markdown) => (markdown.markdown_.span, markdown.semi.span),
+        ast::Stmt::Translate(translate) => (translate.t.span, translate.semi.span),
+        ast::Stmt::Icon(icon) => (icon.icon_.span, icon.semi.span),
+        ast::Stmt::Picture(picture) => (picture.picture_.span, picture.semi.span),
+        ast::Stmt::Og(og) => (og.og_.span, og.braces.span.close()),
+        ast::Stmt::DocumentHead(head) => (head.document_head_.span, head.braces.span.close()),
+        ast::Stmt::Space(space) => (space.space_.span, space.semi.span),
+        ast::Stmt::Verbatim(verbatim) => (verbatim.verbatim_.span, verbatim.semi.span),
+        ast::Stmt::Raw(raw) => (raw.raw_.span, raw.semi.span),
+        ast::Stmt::Passthrough(block) => (block.passthrough_.span, block.braces.span.close()),
+        ast::Stmt::Entity(ast::Entity::Named { entity_, semi, .. }) => (entity_.span, semi.span),
+        ast::Stmt::Entity(ast::Entity::Inline { and, braces, .. }) => {
+            (and.span, braces.span.close())
+        }
+        ast::Stmt::JsonScript(json_script) => {
+            (json_script.json_script_.span, json_script.semi.span)
+        }
+        ast::Stmt::Style(style) => (style.style_.span, style.braces.span.close()),
+        ast::Stmt::Memo(memo) => (memo.memo_.span, memo.braces.span.close()),
+        ast::Stmt::Once(once) => (once.once_.span, once.braces.span.close()),
+        ast::Stmt::Defer(defer) => (
+            defer.defer_.span,
+            defer
+                .placeholder
+                .as_ref()
+                .map_or(defer.braces.span.close(), |placeholder| placeholder.braces.span.close()),
+        ),
+        ast::Stmt::Await(await_) => (
+            await_.await_.span,
+            await_
+                .pending
+                .as_ref()
+                .map_or(await_.braces.span.close(), |pending| pending.braces.span.close()),
+        ),
+        ast::Stmt::Try(try_) => (
+            try_.try_.span,
+            try_.catch.as_ref().map_or(try_.braces.span.close(), |catch| catch.braces.span.close()),
+        ),
+        ast::Stmt::Repeat(repeat) => (repeat.repeat_.span, repeat.braces.span.close()),
+        ast::Stmt::With(with) => (with.with_.span, with.braces.span.close()),
+        ast::Stmt::Maybe(maybe) => (
+            maybe.maybe_.span,
+            maybe
+                .else_
+                .as_ref()
+                .map_or(maybe.braces.span.close(), |else_| else_.braces.span.close()),
+        ),
+        ast::Stmt::Form(form) => (form.form_.span, form.braces.span.close()),
+        ast::Stmt::Table(table) => (table.table_.span, table.braces.span.close()),
+        ast::Stmt::Options(options) => (options.options_.span, options.braces.span.close()),
+        ast::Stmt::WrapIf(wrap_if) => (wrap_if.wrap_if_.span, node_end(&wrap_if.node)),
+        ast::Stmt::Transition(transition) => {
+            (transition.transition_.span, transition.braces.span.close())
+        }
+        ast::Stmt::XmlDecl(decl) => (decl.xml_decl_.span, decl.semi.span),
+        ast::Stmt::Doctype(doctype) => (doctype.doctype_.span, doctype.semi.span),
+        ast::Stmt::Block(block) => (block.block_.span, block.braces.span.close()),
+        ast::Stmt::Extend(extend) => (extend.extend_.span, extend.braces.span.close()),
+        ast::Stmt::Island(island) => (island.island_.span, node_end(&island.node)),
+        ast::Stmt::Lazy(lazy) => (lazy.lazy_.span, node_end(&lazy.node)),
+        ast::Stmt::VirtualFor(for_) => (for_.virtual_.span, for_.braces.span.close()),
+        ast::Stmt::Do(do_) => (do_.do_.span, do_.semi.span),
+        ast::Stmt::Note(note) => (note.span(), note.span()),
+        ast::Stmt::Attributed(attributed) => {
+            let start = attributed.attrs[0].pound_token.span.start().line;
+            return (start, stmt_lines(&attributed.stmt).1);
+        }
+        ast::Stmt::Break(break_) => (break_.break_.span, break_.semi.span),
+        ast::Stmt::Continue(continue_) => (continue_.continue_.span, continue_.semi.span),
+        ast::Stmt::Node(node) => (
+            node.attrs.first().map_or(node.element.span(), |attr| attr.pound_token.span),
+            node_end(node),
+        ),
+    };
+    (start.start().line, end.end().line)
+}
+
+/// The span of the end of a node, which is its `;` or closing brace.
+fn node_end(node: &ast::Node) -> Span {
+    match &node.body {
+        ast::NodeBody::Semi(semi) => semi.span,
+        ast::NodeBody::Braced { braces, .. } => braces.span.close(),
+    }
+}
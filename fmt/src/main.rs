@@ -0,0 +1,80 @@
+//! Formats the `defy!` invocations in Rust source files.
+//!
+//! ```text
+//! defy-fmt [--check] [FILE]...
+//! ```
+//!
+//! Files are formatted in place. Without files, stdin is formatted to stdout.
+//! With `--check`, files are not modified,
+//! and the command fails if any of them is not formatted.
+
+use std::io::{self, Read};
+use std::process::ExitCode;
+use std::{env, fs};
+
+fn main() -> ExitCode {
+    let mut check = false;
+    let mut files = Vec::new();
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--check" => check = true,
+            "-h" | "--help" => {
+                println!("usage: defy-fmt [--check] [FILE]...");
+                return ExitCode::SUCCESS;
+            }
+            _ => files.push(arg),
+        }
+    }
+
+    let options = defy_fmt::Options::default();
+
+    if files.is_empty() {
+        let mut source = String::new();
+        if let Err(err) = io::stdin().read_to_string(&mut source) {
+            eprintln!("cannot read stdin: {err}");
+            return ExitCode::FAILURE;
+        }
+        return match defy_fmt::format_source(&source, &options) {
+            Ok(output) if check && output != source => ExitCode::FAILURE,
+            Ok(_) if check => ExitCode::SUCCESS,
+            Ok(output) => {
+                print!("{output}");
+                ExitCode::SUCCESS
+            }
+            Err(err) => {
+                eprintln!("<stdin>: {err}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    let mut success = true;
+    for file in files {
+        let result = fs::read_to_string(&file).map_err(|err| err.to_string()).and_then(|source| {
+            let output =
+                defy_fmt::format_source(&source, &options).map_err(|err| err.to_string())?;
+            Ok((source, output))
+        });
+        match result {
+            Ok((source, output)) if output != source => {
+                if check {
+                    println!("{file} is not formatted");
+                    success = false;
+                } else if let Err(err) = fs::write(&file, output) {
+                    eprintln!("{file}: cannot write: {err}");
+                    success = false;
+                }
+            }
+            Ok(_) => {}
+            Err(err) => {
+                eprintln!("{file}: {err}");
+                success = false;
+            }
+        }
+    }
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
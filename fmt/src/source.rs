@@ -0,0 +1,111 @@
+//! Utilities for rewriting macro invocations in Rust source files.
+//!
+//! Source locations require the `span-locations` feature of proc-macro2,
+//! which this crate enables.
+
+use std::ops::Range;
+
+use proc_macro2::{LineColumn, Span, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::visit::Visit;
+
+/// Collects the invocations of macros with one of the `names`,
+/// e.g. `defy` for `defy!` and `defy::defy!`.
+pub fn find_macros<'ast>(file: &'ast syn::File, names: &[&str]) -> Vec<&'ast syn::Macro> {
+    let mut finder = Finder { names, macros: Vec::new() };
+    finder.visit_file(file);
+    finder.macros
+}
+
+struct Finder<'a, 'ast> {
+    names:  &'a [&'a str],
+    macros: Vec<&'ast syn::Macro>,
+}
+
+impl<'ast> Visit<'ast> for Finder<'_, 'ast> {
+    fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+        if mac
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| self.names.iter().any(|name| segment.ident == name))
+        {
+            self.macros.push(mac);
+        }
+    }
+}
+
+/// Converts line-column positions to byte offsets.
+pub struct LineIndex<'a> {
+    source: &'a str,
+    starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let starts =
+            std::iter::once(0).chain(source.match_indices('\n').map(|(pos, _)| pos + 1)).collect();
+        Self { source, starts }
+    }
+
+    pub fn offset(&self, lc: LineColumn) -> usize {
+        let start = self.starts[lc.line - 1];
+        let line = &self.source[start..];
+        start + line.char_indices().nth(lc.column).map_or(line.len(), |(pos, _)| pos)
+    }
+
+    pub fn range(&self, span: Span) -> Range<usize> {
+        self.offset(span.start())..self.offset(span.end())
+    }
+}
+
+/// Whether there is anything other than whitespace between the tokens,
+/// i.e. comments that would be lost by reprinting the tokens.
+///
+/// `start` and `end` are the byte offsets enclosing the tokens.
+pub fn has_comments(
+    source: &str,
+    lines: &LineIndex,
+    start: usize,
+    tokens: TokenStream,
+    end: usize,
+) -> bool {
+    fn collect(tokens: TokenStream, lines: &LineIndex, ranges: &mut Vec<Range<usize>>) {
+        for tt in tokens {
+            match tt {
+                TokenTree::Group(group) => {
+                    ranges.push(lines.range(group.span_open()));
+                    collect(group.stream(), lines, ranges);
+                    ranges.push(lines.range(group.span_close()));
+                }
+                tt => ranges.push(lines.range(tt.span())),
+            }
+        }
+    }
+
+    let mut ranges = Vec::new();
+    ranges.push(start..start);
+    collect(tokens, lines, &mut ranges);
+    ranges.push(end..end);
+    ranges.windows(2).any(|pair| !source[pair[0].end..pair[1].start].trim().is_empty())
+}
+
+/// The number of leading spaces on the line containing the byte offset `offset`.
+pub fn line_indent(source: &str, offset: usize) -> usize {
+    let line_start = source[..offset].rfind('\n').map_or(0, |pos| pos + 1);
+    source[line_start..].chars().take_while(|&ch| ch == ' ').count()
+}
+
+/// Returns the source text of the tokens, or their token string if unavailable.
+pub fn source_text(tokens: &dyn ToTokens) -> String {
+    let tokens = tokens.to_token_stream();
+    let mut iter = tokens.clone().into_iter();
+    let text = match (iter.next(), iter.last()) {
+        (Some(first), Some(last)) => {
+            first.span().join(last.span()).and_then(|span| span.source_text())
+        }
+        (Some(first), None) => first.span().source_text(),
+        _ => None,
+    };
+    text.unwrap_or_else(|| tokens.to_string())
+}
@@ -0,0 +1,109 @@
+#![cfg(test)]
+
+use crate::{format_source, Options};
+
+const UNFORMATTED: &str = r#"
+fn view() -> Html {
+    defy! {
+        h1 {    + "Hello world"; }
+        button(@click.stop=save, class={ "active": active }) { + "Save"; }
+        input(@keydown(Enter|"k" with ctrl+shift)=submit);
+        transition(enter="fade-in",leave="fade-out") if open { + "Hi"; }
+        virtual for row in rows(height=32,viewport=600) { + row; }
+        if media   "print" { + "printed"; }
+        island Counter(start=1);
+        xml_decl(version="1.0");
+        doctype   html;
+        raw   "<b>Hi</b>" ;
+        passthrough {<br />}
+        embed   sidebar ;
+        dyn  widget(props = p) ;
+        lazy  Settings { fallback{ spinner ; } }
+        await(id,n=count)   fetch(id) as user { + user; }
+        extend base_layout { block content {+"Hi";} }
+        image::loc(xmlns:image="urn:image");
+      ul(class = "list") {
+            for datum in data { let field = datum.field;
+                if datum.display { li(data-length = field.len().to_string()) { + field; } } else { br; }
+
+                match datum.label {
+                    Label::First(i) if i > 3 => { h2 { +i; } }
+                    _ => {}
+                }
+            }
+        }
+        div(id = "a-very-long-identifier-value", class = "another-very-long-class-list", onclick = callback) { p; }
+    }
+}
+"#;
+
+const FORMATTED: &str = r#"
+fn view() -> Html {
+    defy! {
+        h1 { + "Hello world"; }
+        button(@click.stop = save, class = { "active": active }) { + "Save"; }
+        input(@keydown(Enter | "k" with ctrl + shift) = submit);
+        transition(enter = "fade-in", leave = "fade-out") if open { + "Hi"; }
+        virtual for row in rows (height = 32, viewport = 600) { + row; }
+        if media "print" { + "printed"; }
+        island Counter(start = 1);
+        xml_decl(version = "1.0");
+        doctype html;
+        raw "<b>Hi</b>";
+        passthrough { <br /> }
+        embed sidebar;
+        dyn widget(props = p);
+        lazy Settings {
+            fallback { spinner; }
+        }
+        await(id, n = count) fetch(id) as user { + user; }
+        extend base_layout {
+            block content { + "Hi"; }
+        }
+        image::loc(xmlns:image = "urn:image");
+        ul(class = "list") {
+            for datum in data {
+                let field = datum.field;
+                if datum.display {
+                    li(data-length = field.len().to_string()) { + field; }
+                } else { br; }
+
+                match datum.label {
+                    Label::First(i) if i > 3 => {
+                        h2 { + i; }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        div(
+            id = "a-very-long-identifier-value",
+            class = "another-very-long-class-list",
+            onclick = callback,
+        ) {
+            p;
+        }
+    }
+}
+"#;
+
+#[test]
+fn test_format() {
+    assert_eq!(format_source(UNFORMATTED, &Options::default()).unwrap(), FORMATTED);
+    assert_eq!(format_source(FORMATTED, &Options::default()).unwrap(), FORMATTED);
+}
+
+#[test]
+fn test_comments_unchanged() {
+    let source = "fn f() { defy! { br;  // comment\n } }";
+    assert_eq!(format_source(source, &Options::default()).unwrap(), source);
+}
+
+#[test]
+fn test_format_defy_iter() {
+    let source = "fn f() { defy::defy_iter! { li {+ \"a\" ;}  li; } }";
+    assert_eq!(
+        format_source(source, &Options::default()).unwrap(),
+        "fn f() { defy::defy_iter! {\n    li { + \"a\"; }\n    li;\n} }",
+    );
+}
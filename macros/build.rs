@@ -0,0 +1,6 @@
+// The URLs emitted for `asset("...")` attribute values depend on `DEFY_ASSET_BASE`.
+// Rebuilding this crate when it changes also rebuilds the crates using the macros,
+// since procedural macros cannot track environment variables on stable Rust.
+fn main() {
+    println!("cargo:rerun-if-env-changed=DEFY_ASSET_BASE");
+}
@@ -0,0 +1,39 @@
+//! The `defy!` and `defy_iter!` procedural macros, `#[derive(Form)]` and `#[stories]`, re-exported by the [`defy`](https://docs.rs/defy) crate.
+
+/// See the [`defy`](https://docs.rs/defy) crate documentation.
+#[proc_macro]
+pub fn defy(ts: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    defy_codegen::expand(ts.into()).into()
+}
+
+/// See the [`defy`](https://docs.rs/defy) crate documentation.
+#[proc_macro]
+pub fn defy_iter(ts: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    defy_codegen::expand_iter(ts.into()).into()
+}
+
+/// See the [`defy::form`](https://docs.rs/defy/latest/defy/form) module documentation.
+#[proc_macro_derive(Form, attributes(form, validate))]
+pub fn derive_form(ts: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    defy_codegen::derive_form(ts.into()).into()
+}
+
+/// See the [`defy::story`](https://docs.rs/defy/latest/defy/story) module documentation.
+#[proc_macro_attribute]
+pub fn stories(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    defy_codegen::stories(attr.into(), item.into()).into()
+}
+
+/// Marks a story in a `#[stories]` module.
+///
+/// See the [`defy::story`](https://docs.rs/defy/latest/defy/story) module documentation.
+#[proc_macro_attribute]
+pub fn story(
+    _attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    defy_codegen::story(item.into()).into()
+}
@@ -0,0 +1,108 @@
+//! Class strings built without yew.
+//!
+//! [`classes!`](crate::classes!) joins class names from strings, options, lists
+//! and `{ "class": condition }` maps into a `String`,
+//! for code that renders HTML without yew's `Classes`, such as emails or static pages:
+//!
+//! ```
+//! let active = true;
+//! let size = Some("large");
+//! assert_eq!(
+//!     defy::classes!["button", size, ["rounded", ""], { "active": active, "disabled": !active }],
+//!     "button large rounded active",
+//! );
+//! ```
+//!
+//! Empty names are skipped, and names are not deduplicated.
+
+use std::borrow::Cow;
+
+/// Values that can be passed to [`classes!`](crate::classes!).
+pub trait ClassNames {
+    /// Appends the class names to `out`, separated by spaces.
+    fn push_classes(self, out: &mut String);
+}
+
+impl ClassNames for &str {
+    fn push_classes(self, out: &mut String) {
+        let name = self.trim();
+        if !name.is_empty() {
+            if !out.is_empty() {
+                out.push(' ');
+            }
+            out.push_str(name);
+        }
+    }
+}
+
+impl ClassNames for String {
+    fn push_classes(self, out: &mut String) { self.as_str().push_classes(out) }
+}
+
+impl ClassNames for &String {
+    fn push_classes(self, out: &mut String) { self.as_str().push_classes(out) }
+}
+
+impl ClassNames for Cow<'_, str> {
+    fn push_classes(self, out: &mut String) { (*self).push_classes(out) }
+}
+
+impl<T: ClassNames> ClassNames for Option<T> {
+    fn push_classes(self, out: &mut String) {
+        if let Some(names) = self {
+            names.push_classes(out);
+        }
+    }
+}
+
+impl<T: ClassNames> ClassNames for Vec<T> {
+    fn push_classes(self, out: &mut String) {
+        self.into_iter().for_each(|names| names.push_classes(out));
+    }
+}
+
+impl<T: ClassNames + Clone> ClassNames for &Vec<T> {
+    fn push_classes(self, out: &mut String) { self.as_slice().push_classes(out) }
+}
+
+impl<T: ClassNames, const N: usize> ClassNames for [T; N] {
+    fn push_classes(self, out: &mut String) {
+        self.into_iter().for_each(|names| names.push_classes(out));
+    }
+}
+
+impl<T: ClassNames + Clone> ClassNames for &[T] {
+    fn push_classes(self, out: &mut String) {
+        self.iter().cloned().for_each(|names| names.push_classes(out));
+    }
+}
+
+/// Joins class names into a `String`.
+///
+/// Each item is a [`ClassNames`] value, such as a string, an `Option` or a list,
+/// or a `{ "class": condition, ... }` map of classes included if their condition is true.
+/// See the [`class`](crate::class) module.
+#[macro_export]
+macro_rules! classes {
+    (@push $out:ident $(,)?) => {};
+    (@push $out:ident, { $($class:literal : $condition:expr),* $(,)? } $(, $($rest:tt)*)?) => {
+        $(
+            if $condition {
+                $crate::class::ClassNames::push_classes($class, &mut $out);
+            }
+        )*
+        $($crate::classes!(@push $out, $($rest)*);)?
+    };
+    (@push $out:ident, $item:expr $(, $($rest:tt)*)?) => {
+        $crate::class::ClassNames::push_classes($item, &mut $out);
+        $($crate::classes!(@push $out, $($rest)*);)?
+    };
+    () => {
+        ::std::string::String::new()
+    };
+    ($($items:tt)*) => {{
+        let mut out = ::std::string::String::new();
+        $crate::classes!(@push out, $($items)*);
+        out
+    }};
+}
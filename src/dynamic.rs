@@ -0,0 +1,63 @@
+//! Components selected at runtime.
+//!
+//! `dyn component(props = props);` renders a [`DynComponent`] value,
+//! such as a function pointer or a boxed closure returning `Html`,
+//! which plugin-style UIs can look up from an enum or a registry.
+//! [`component`] turns a component type into such a value:
+//!
+//! ```
+//! use yew::prelude::*;
+//!
+//! #[derive(PartialEq, Properties)]
+//! struct PanelProps {
+//!     title: AttrValue,
+//! }
+//!
+//! #[function_component]
+//! fn Chart(props: &PanelProps) -> Html {
+//!     defy::defy! { h2 { + &props.title; } }
+//! }
+//!
+//! fn table(props: PanelProps) -> Html {
+//!     defy::defy! { table(title = props.title); }
+//! }
+//!
+//! enum Kind {
+//!     Chart,
+//!     Table,
+//! }
+//!
+//! fn panel(kind: Kind) -> fn(PanelProps) -> Html {
+//!     match kind {
+//!         Kind::Chart => defy::dynamic::component::<Chart>(),
+//!         Kind::Table => table,
+//!     }
+//! }
+//!
+//! let kind = Kind::Chart;
+//! let html = defy::defy! {
+//!     dyn panel(kind)(props = PanelProps { title: "Sales".into() });
+//! };
+//! ```
+//!
+//! `dyn component;` without arguments passes `()` as the props.
+
+use yew::html::BaseComponent;
+use yew::virtual_dom::VChild;
+use yew::Html;
+
+/// Values that `dyn` statements can render with props of type `P`.
+pub trait DynComponent<P> {
+    /// Renders the component with the given props.
+    fn render(&self, props: P) -> Html;
+}
+
+impl<P, F: Fn(P) -> Html + ?Sized> DynComponent<P> for F {
+    fn render(&self, props: P) -> Html { self(props) }
+}
+
+/// A function rendering the component `C`,
+/// which can be stored together with functions rendering other components with the same props.
+pub fn component<C: BaseComponent>() -> fn(C::Properties) -> Html {
+    |props| VChild::<C>::new(props, None).into()
+}
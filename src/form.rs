@@ -0,0 +1,220 @@
+//! Forms generated from structs.
+//!
+//! `#[derive(Form)]` on a struct with named fields enables `form` statements,
+//! which render a labeled, controlled input for each listed field of a `UseStateHandle`:
+//!
+//! ```
+//! #[derive(Clone, PartialEq, defy::form::Form)]
+//! struct Signup {
+//!     #[form(label = "E-mail")]
+//!     email:          String,
+//!     age:            u32,
+//!     terms_accepted: bool,
+//! }
+//!
+//! #[yew::function_component]
+//! fn SignupForm() -> yew::Html {
+//!     let signup = yew::use_state(|| Signup {
+//!         email:          String::new(),
+//!         age:            18,
+//!         terms_accepted: false,
+//!     });
+//!     defy::defy! {
+//!         form {
+//!             form for signup {
+//!                 field email(required = true);
+//!                 field age(min = "0");
+//!                 field terms_accepted;
+//!             }
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Each field becomes a `label` followed by an `input`
+//! with the field name as its `id` and `name`,
+//! and the type given by the [`FormValue`] implementation of the field type:
+//! `text` for strings, `number` for numbers and `checkbox` for `bool`.
+//! Extra attributes of the input can be passed in parentheses.
+//!
+//! The label defaults to the field name in sentence case, e.g. `Terms accepted`,
+//! and can be overridden with `#[form(label = "...")]`.
+//!
+//! # Validation
+//! The rules of [`validator`](https://docs.rs/validator) `#[validate(...)]` attributes
+//! are emitted as the equivalent HTML constraints,
+//! so that browsers check the same rules as the server:
+//!
+//! | Rule | Attributes |
+//! | --- | --- |
+//! | `email` | `type="email"` |
+//! | `url` | `type="url"` |
+//! | `required` | `required` |
+//! | `length(min = a, max = b)` | `minlength="a" maxlength="b"` |
+//! | `range(min = a, max = b)` | `min="a" max="b"` |
+//!
+//! Bounds that are not literals and other rules are ignored.
+//! Since `regex` rules refer to a compiled expression,
+//! the `pattern` attribute is set separately with `#[form(pattern = "...")]`.
+//! Attributes passed explicitly to `field` take precedence.
+//!
+//! `form for state, errors = expr { ... }` renders the messages of each field
+//! in `<span class="form-error">` elements after its input.
+//! `expr` implements [`FormErrors`], e.g. `validation_errors.field_errors()`
+//! or an `Option` of it that is `None` before the form is validated.
+
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+
+pub use defy_macros::Form;
+use yew::AttrValue;
+
+/// A struct that can be edited with a `form` statement.
+///
+/// Use `#[derive(Form)]` to implement this trait.
+pub trait Form {
+    /// The label of the input for `field`.
+    fn label(field: &str) -> &'static str;
+
+    /// The HTML constraint attributes of the input for `field`.
+    fn constraints(_field: &str) -> Constraints { Constraints::default() }
+}
+
+/// HTML constraint attributes of an input, derived from `#[validate(...)]` rules.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Constraints {
+    /// Overrides the [`FormValue::INPUT_TYPE`] of the field, e.g. `email`.
+    pub input_type: Option<&'static str>,
+    pub required:   bool,
+    pub minlength:  Option<&'static str>,
+    pub maxlength:  Option<&'static str>,
+    pub min:        Option<&'static str>,
+    pub max:        Option<&'static str>,
+    pub pattern:    Option<&'static str>,
+}
+
+/// Validation errors rendered after the inputs of a `form` statement with `errors = ...`.
+pub trait FormErrors {
+    /// The error messages of `field`.
+    fn field_errors(&self, field: &str) -> Vec<String>;
+}
+
+/// The error messages of a single field.
+pub trait FieldErrors {
+    fn messages(&self) -> Vec<String>;
+}
+
+impl<E: fmt::Display> FieldErrors for [E] {
+    fn messages(&self) -> Vec<String> { self.iter().map(ToString::to_string).collect() }
+}
+
+impl<E: fmt::Display> FieldErrors for Vec<E> {
+    fn messages(&self) -> Vec<String> { self[..].messages() }
+}
+
+impl<T: FieldErrors + ?Sized> FieldErrors for &T {
+    fn messages(&self) -> Vec<String> { (**self).messages() }
+}
+
+/// Errors keyed by field name,
+/// such as the `field_errors()` of `validator::ValidationErrors`.
+impl<K, V, S> FormErrors for HashMap<K, V, S>
+where
+    K: Borrow<str> + Hash + Eq,
+    V: FieldErrors,
+    S: BuildHasher,
+{
+    fn field_errors(&self, field: &str) -> Vec<String> {
+        self.get(field).map_or_else(Vec::new, FieldErrors::messages)
+    }
+}
+
+impl<K: Borrow<str> + Ord, V: FieldErrors> FormErrors for BTreeMap<K, V> {
+    fn field_errors(&self, field: &str) -> Vec<String> {
+        self.get(field).map_or_else(Vec::new, FieldErrors::messages)
+    }
+}
+
+/// No errors if `None`, e.g. before the form is validated.
+impl<T: FormErrors> FormErrors for Option<T> {
+    fn field_errors(&self, field: &str) -> Vec<String> {
+        self.as_ref().map_or_else(Vec::new, |errors| errors.field_errors(field))
+    }
+}
+
+/// A type that can be edited with an `input` element.
+pub trait FormValue: Sized {
+    /// The `type` attribute of the input.
+    const INPUT_TYPE: &'static str;
+
+    /// The `value` attribute of the input.
+    fn to_input(&self) -> String;
+
+    /// The `checked` attribute of the input.
+    fn is_checked(&self) -> bool { false }
+
+    /// Parses the value and checked state of the input,
+    /// returning `None` to keep the current value.
+    fn from_input(value: &str, checked: bool) -> Option<Self>;
+}
+
+impl FormValue for String {
+    const INPUT_TYPE: &'static str = "text";
+
+    fn to_input(&self) -> String { self.clone() }
+
+    fn from_input(value: &str, _: bool) -> Option<Self> { Some(value.to_string()) }
+}
+
+impl FormValue for AttrValue {
+    const INPUT_TYPE: &'static str = "text";
+
+    fn to_input(&self) -> String { self.to_string() }
+
+    fn from_input(value: &str, _: bool) -> Option<Self> { Some(AttrValue::from(value.to_string())) }
+}
+
+impl FormValue for bool {
+    const INPUT_TYPE: &'static str = "checkbox";
+
+    fn to_input(&self) -> String { "true".to_string() }
+
+    fn is_checked(&self) -> bool { *self }
+
+    fn from_input(_: &str, checked: bool) -> Option<Self> { Some(checked) }
+}
+
+macro_rules! impl_number {
+    ($($ty:ty),*) => {
+        $(
+            impl FormValue for $ty {
+                const INPUT_TYPE: &'static str = "number";
+
+                fn to_input(&self) -> String { self.to_string() }
+
+                fn from_input(value: &str, _: bool) -> Option<Self> { value.trim().parse().ok() }
+            }
+        )*
+    };
+}
+
+impl_number!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+/// An optional value, which is `None` when the input is empty.
+impl<T: FormValue> FormValue for Option<T> {
+    const INPUT_TYPE: &'static str = T::INPUT_TYPE;
+
+    fn to_input(&self) -> String { self.as_ref().map_or_else(String::new, T::to_input) }
+
+    fn is_checked(&self) -> bool { self.as_ref().is_some_and(T::is_checked) }
+
+    fn from_input(value: &str, checked: bool) -> Option<Self> {
+        if value.trim().is_empty() {
+            Some(None)
+        } else {
+            T::from_input(value, checked).map(Some)
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Interactive components in otherwise static server-rendered pages.
+//!
+//! `island Counter(start = 1);` renders the component inside a `div`
+//! with a `data-defy-island` attribute naming the component
+//! and a `data-defy-props` attribute containing its props as JSON.
+//! The client hydrates only these components with [`hydrate`],
+//! while the rest of the page stays static HTML:
+//!
+//! ```
+//! # /*
+//! // in the template rendered by the server
+//! island Counter(start = 1);
+//!
+//! // in the entry point of the client, with the `hydration` feature
+//! defy::island::hydrate::<Counter>();
+//! # */
+//! ```
+//!
+//! The props of the component must implement `serde::Serialize` on the server
+//! and `serde::Deserialize` on the client, and the component cannot have children.
+//! Since the component is identified by its type name,
+//! the server and the client must be compiled from the same source with the same compiler,
+//! and the page must be rendered by a hydratable `yew::ServerRenderer`, which is the default.
+
+use yew::html::BaseComponent;
+
+/// The attribute naming the component of an island.
+pub const ISLAND_ATTR: &str = "data-defy-island";
+/// The attribute containing the props of an island as JSON.
+pub const PROPS_ATTR: &str = "data-defy-props";
+
+/// The name of the component `C` in the `data-defy-island` attribute.
+pub fn name<C: BaseComponent>() -> &'static str { std::any::type_name::<C>() }
+
+/// Hydrates every island of the component `C` in the document
+/// with the props in its `data-defy-props` attribute.
+///
+/// # Panics
+/// Panics if the props of an island cannot be deserialized.
+#[cfg(feature = "hydration")]
+pub fn hydrate<C>()
+where
+    C: BaseComponent,
+    C::Properties: serde::de::DeserializeOwned,
+{
+    use web_sys::wasm_bindgen::JsCast;
+
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else { return };
+    let selector = format!("[{ISLAND_ATTR}=\"{}\"]", name::<C>());
+    let Ok(islands) = document.query_selector_all(&selector) else { return };
+    for index in 0..islands.length() {
+        let Some(element) = islands.item(index).and_then(|node| node.dyn_into().ok()) else {
+            continue;
+        };
+        let props = web_sys::Element::get_attribute(&element, PROPS_ATTR).unwrap_or_default();
+        let props = serde_json::from_str(&props).expect("the props of an island are invalid");
+        // the island stays mounted for the lifetime of the page
+        yew::Renderer::<C>::with_root_and_props(element, props).hydrate();
+    }
+}
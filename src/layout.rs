@@ -0,0 +1,55 @@
+//! Layouts with blocks that pages override, like template inheritance in Jinja.
+//!
+//! A layout is a function that takes [`Blocks`] and passes them to `@blocks`,
+//! so that each `block name { ... }` statement renders the override named `name`
+//! or its own children if the block is not overridden.
+//! `extend layout { ... }` calls the layout with the `block` statements in its body as overrides:
+//!
+//! ```
+//! use defy::layout::Blocks;
+//!
+//! fn base_layout(blocks: Blocks) -> yew::Html {
+//!     defy::defy! {
+//!         @blocks blocks
+//!         header { block header { h1 { + "My site"; } } }
+//!         main { block content {} }
+//!     }
+//! }
+//!
+//! fn about_page() -> yew::Html {
+//!     defy::defy! {
+//!         extend base_layout {
+//!             block content { p { + "About us"; } }
+//!         }
+//!     }
+//! }
+//! # let _ = about_page();
+//! ```
+//!
+//! The overrides can contain `block` statements themselves,
+//! so a layout can extend another layout and expose its own blocks.
+//! Overrides of blocks that the layout does not define are ignored.
+
+use yew::Html;
+
+/// The overridden blocks of a layout, passed to the layout by `extend`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Blocks {
+    blocks: Vec<(&'static str, Html)>,
+}
+
+impl Blocks {
+    /// Creates a set of blocks without overrides.
+    pub fn new() -> Self { Self::default() }
+
+    /// Overrides the block `name` with `html`.
+    pub fn with(mut self, name: &'static str, html: Html) -> Self {
+        self.blocks.push((name, html));
+        self
+    }
+
+    /// The override of the block `name`, if any.
+    pub fn get(&self, name: &str) -> Option<Html> {
+        self.blocks.iter().find(|(block, _)| *block == name).map(|(_, html)| html.clone())
+    }
+}
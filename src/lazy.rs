@@ -0,0 +1,61 @@
+//! Components rendered after their code is loaded.
+//!
+//! `lazy Settings(user = user.clone()) { fallback { spinner; } }`
+//! renders the `fallback` block, which is empty if omitted, in a yew `Suspense`
+//! until the future returned by [`load`] completes, and the component afterwards.
+//!
+//! wasm-bindgen builds a single module, so by default the future completes immediately.
+//! Targets that split rarely used components into separately loaded chunks
+//! set a [`Loader`] with [`set_loader`] at startup,
+//! which waits for the chunk of a component identified by [`name`],
+//! e.g. with a JavaScript `import()` through wasm-bindgen:
+//!
+//! ```
+//! # /*
+//! #[wasm_bindgen(inline_js = "export function load(name) { return import(chunkFor(name)); }")]
+//! extern "C" {
+//!     fn load(name: &str) -> js_sys::Promise;
+//! }
+//!
+//! defy::lazy::set_loader(|name| {
+//!     let promise = JsFuture::from(load(name));
+//!     Box::pin(async move {
+//!         promise.await.expect("failed to load a chunk");
+//!     })
+//! });
+//! # */
+//! ```
+//!
+//! Like `await` blocks, the props of the component are moved into a closure,
+//! so they cannot borrow local variables.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::OnceLock;
+
+use yew::html::BaseComponent;
+
+/// Waits for the code of the component with the given [`name`] to be loaded.
+pub type Loader = fn(&'static str) -> Pin<Box<dyn Future<Output = ()>>>;
+
+static LOADER: OnceLock<Loader> = OnceLock::new();
+
+/// Sets the loader used by `lazy` statements.
+///
+/// # Panics
+/// Panics if a loader was already set.
+pub fn set_loader(loader: Loader) {
+    assert!(LOADER.set(loader).is_ok(), "the loader of lazy components is already set");
+}
+
+/// The name of the component `C` passed to the [`Loader`].
+pub fn name<C: BaseComponent>() -> &'static str { std::any::type_name::<C>() }
+
+/// Loads the code of the component `C` with the [`Loader`],
+/// or completes immediately if no loader is set.
+pub fn load<C: BaseComponent>() -> Pin<Box<dyn Future<Output = ()>>> {
+    match LOADER.get() {
+        Some(loader) => loader(name::<C>()),
+        None => Box::pin(std::future::ready(())),
+    }
+}
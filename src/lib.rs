@@ -113,195 +113,1834 @@
 //! <foo a={b} c={d}> ... </foo>
 //! ```
 //!
+//! ## Generic components
+//! ```
+//! # /*
+//! List::<Item<T>, 3>(items = items);
+//! Table<Row, { COLUMNS }>(rows = rows);
+//! # */
+//! ```
+//! become
+//! ```html
+//! <List::<Item<T>, 3> items={items} />
+//! <Table<Row, { COLUMNS }> rows={rows} />
+//! ```
+//! Generic arguments can be written with or without `::`,
+//! and can contain types, lifetimes and const expressions in braces.
+//!
+//! ## Attribute order
+//! Attributes are passed to yew in source order,
+//! so server-rendered HTML only changes when the template does,
+//! except that yew renders `value` and `checked` before other attributes and `class` after them.
+//! With `@sort_attrs`, or `sort_attrs = true` in the [workspace defaults](#workspace-defaults),
+//! attributes are sorted by name instead,
+//! which keeps snapshot tests stable when attributes are reordered:
+//! ```
+//! # /*
+//! @sort_attrs
+//! a(title = caption, href = url);
+//! # */
+//! ```
+//! becomes
+//! ```html
+//! <a href={url} title={caption} />
+//! ```
+//!
+//! ## Conditional classes
+//! ```
+//! # /*
+//! div(class = { "is-open": open, "is-disabled": !enabled }) { ... }
+//! # */
+//! ```
+//! renders each class whose condition is true, becoming
+//! ```html
+//! <div class={classes!(open.then_some("is-open"), (!enabled).then_some("is-disabled"))}> ... </div>
+//! ```
+//! Use `classes!` directly to combine conditional classes with other classes.
+//! [`defy::classes!`](classes!) accepts the same maps and builds a `String` without yew,
+//! e.g. for HTML rendered by other means.
+//!
+//! ## Two-way binding
+//! ```
+//! # /*
+//! input(bind:value = name);
+//! input(type = "checkbox", bind:checked = subscribed);
+//! # */
+//! ```
+//! binds a `UseStateHandle` to an input, becoming
+//! ```html
+//! <input value={(*name).clone()} oninput={/* sets `name` to the input value */} />
+//! <input type="checkbox" checked={*subscribed} onchange={/* sets `subscribed` */} />
+//! ```
+//! `bind:value` also works on `textarea` and `select`,
+//! and accepts a handle to any type that implements `From<String>`
+//! and can be passed as the `value` attribute, such as `String` and `AttrValue`.
+//!
+//! ## Event handlers
+//! `@event = handler` is short for `onevent = handler`:
+//! ```
+//! # /*
+//! button(@click = on_save) { + "Save"; }
+//! # */
+//! ```
+//!
+//! In struct components, `@link ctx` makes the handlers messages sent to the component
+//! through the link of the `Context`:
+//! ```
+//! # /*
+//! @link ctx
+//! button(@click = Msg::Increment) { + "+"; }
+//! input(@input = |event: InputEvent| Msg::Input(event));
+//! # */
+//! ```
+//! becomes
+//! ```html
+//! <button onclick={ctx.link().callback(move |_| Msg::Increment)}>{ "+" }</button>
+//! <input oninput={ctx.link().callback(|event: InputEvent| Msg::Input(event))} />
+//! ```
+//! A closure is passed to `callback` unchanged,
+//! so it must specify the type of the event argument.
+//!
+//! Keyboard event handlers can be limited to some keys
+//! by their [`key`](https://developer.mozilla.org/en-US/docs/Web/API/KeyboardEvent/key) values,
+//! optionally with modifier keys (`ctrl`, `shift`, `alt` and `meta`) that must be pressed:
+//! ```
+//! # /*
+//! input(@keydown(Enter) = on_submit);
+//! input(@keydown(Escape | "k" with ctrl) = on_close);
+//! # */
+//! ```
+//! Single characters are compared case-insensitively.
+//! With `@link ctx`, no message is sent for other keys.
+//!
+//! Modifiers after the event name change how the event is handled:
+//! ```
+//! # /*
+//! form(@submit.prevent = on_submit) { ... }
+//! button(@click.stop = on_close);
+//! input(@keydown(Enter).prevent = on_enter);
+//! div(@wheel.passive = on_wheel) { ... }
+//! a(href = "#", @click.prevent);
+//! # */
+//! ```
+//! - `.prevent` calls `prevent_default()` on the event before the handler,
+//!   and can be used without a handler.
+//! - `.stop` calls `stop_propagation()` on the event before the handler.
+//! - `.passive` adds the listener as
+//!   [passive](https://developer.mozilla.org/en-US/docs/Web/API/EventTarget/addEventListener#passive),
+//!   so that the browser can scroll without waiting for the handler.
+//!   It is only supported on HTML elements and cannot be combined with `.prevent`.
+//!
+//! With key filters, the modifiers only apply to the given keys.
+//!
+//! ## Select options
+//! ```
+//! # /*
+//! select(bind:value = selected) {
+//!     options from &countries { |c| (value = c.code) { + c.name; } }
+//! }
+//! # */
+//! ```
+//! renders an `option` element with the arguments and children for each item.
+//! Each option must have a `value`.
+//! In a `select` with `value = ...` or `bind:value = ...`,
+//! the option whose value has the same string representation is `selected`.
+//!
 //! # Text values
 //! ```
 //! # /*
-//! + expr;
+//! + expr;
+//! # */
+//! ```
+//! renders `expr`, which can be any value implementing `Display` or `Into<Html>`,
+//! such as strings, numbers and `Html`,
+//! or an `Option`, a `Vec` or an iterator of such values.
+//! Other values are rejected with an error naming `+`
+//! instead of the conversion traits used by yew.
+//!
+//! # Embedded HTML
+//! ```
+//! # /*
+//! embed sidebar;
+//! # */
+//! ```
+//! renders an existing `Html` value, e.g. from a helper function or a prop,
+//! or nothing if the value is an `Option<Html>` that is `None`.
+//! Unlike `+`, which is meant for text, other types are rejected with an error naming `embed`.
+//! An `<embed>` element is written as `embed(...);`.
+//!
+//! # Components selected at runtime
+//! ```
+//! # /*
+//! dyn widget(props = props);
+//! # */
+//! ```
+//! renders the component chosen by the value `widget`, e.g. a function pointer returning `Html`
+//! looked up from an enum, with `props` as its props.
+//! See the [`dynamic`] module for how to store components as such values.
+//!
+//! # Iterators of nodes
+//! [`defy_iter!`] takes the same input as `defy!`,
+//! but returns an iterator with an `Html` item for each top-level statement
+//! instead of a single fragment,
+//! e.g. to pass children to APIs that take iterators or to mix them with other nodes:
+//! ```
+//! let items: Vec<yew::Html> = defy::defy_iter! {
+//!     let name = "defy";
+//!     li { + "Hello"; }
+//!     li { + name; }
+//! }
+//! .collect();
+//! assert_eq!(items.len(), 2);
+//! ```
+//! `let` statements and items are not items of the iterator,
+//! and a top-level `for` loop is a single item containing all its iterations.
+//!
+//! # Attribute values
+//! The values of HTML attributes can be strings, `AttrValue`s, or `Option`s of them,
+//! where `None` omits the attribute.
+//! Other values are rejected with an error at the value,
+//! e.g. "`u32` cannot be used as the value of an HTML attribute",
+//! instead of the conversion traits used by yew.
+//! `class`, `value`, `checked`, event listeners, boolean attributes such as `disabled`
+//! and the props of components are converted by yew as usual.
+//!
+//! # Whitespace
+//! Text values are rendered exactly, but whitespace between statements is not rendered,
+//! so `b { + "bold"; } + "text";` renders `<b>bold</b>text`.
+//! `space;` renders a single space between inline elements and text:
+//! ```
+//! # /*
+//! b { + "bold"; }
+//! space;
+//! + "text";
+//! # */
+//! ```
+//! becomes
+//! ```html
+//! <b>bold</b> text
+//! ```
+//!
+//! # Verbatim text
+//! ```
+//! # /*
+//! pre {
+//!     verbatim r#"
+//!         fn main() {
+//!             println!("hello");
+//!         }
+//!     "#;
+//! }
+//! # */
+//! ```
+//! becomes
+//! ```html
+//! <pre>fn main() {
+//!     println!("hello");
+//! }
+//! </pre>
+//! ```
+//! The line break after the opening quote and the indentation common to all lines are removed,
+//! so the literal can be indented with the template,
+//! and the other newlines and indentation are rendered exactly.
+//!
+//! # Passing tokens to yew
+//! ```
+//! # /*
+//! div {
+//!     passthrough { <Suspense fallback={loading}><Profile /></Suspense> }
+//! }
+//! # */
+//! ```
+//! passes the contents of the block unchanged to `yew::html!`, or the macro set by `@macro_path`,
+//! for syntax that `defy!` does not support.
+//! The contents are wrapped in a fragment, so they can contain several nodes.
+//!
+//! # Raw HTML
+//! ```
+//! # /*
+//! raw "<b>Hello</b>";
+//! # */
+//! ```
+//! renders the HTML in a string literal without escaping, e.g. for embed snippets.
+//! The tags are checked when the template is compiled,
+//! so that unbalanced or improperly nested tags, e.g. a `div` inside a `p`,
+//! cannot break the layout of the rest of the page.
+//! Void elements such as `br` and self-closing tags have no end tag,
+//! but elements with optional end tags such as `li` must be closed.
+//!
+//! # Character references
+//! ```
+//! # /*
+//! + "Price";
+//! entity nbsp;
+//! + price;
+//! &{ "mdash" }
+//! # */
+//! ```
+//! becomes
+//! ```text
+//! `{ "Price" }{ "\u{a0}" }{ price }{ "—" }`
+//! ```
+//! Text values are escaped, so `+ "&nbsp;";` renders the literal text `&nbsp;`.
+//! `entity` renders the character of a named reference instead,
+//! and the inline form `&{ "name" }` also accepts numeric references like `&{ "#x2014" }`.
+//! Common references are supported, including whitespace, punctuation, currency,
+//! arrows, mathematical symbols and Greek letters.
+//!
+//! # Local variables
+//! Local variables can be defined in the form of normal `let` statements,
+//! optionally with a type annotation, e.g. `let rows: Vec<_> = iter.collect();`.
+//! However they must precede all non-`let` statements in a `{}` block
+//! in order to preserve evaluation order.
+//!
+//! `use` declarations and `const`, `static` and `fn` items
+//! can be written anywhere in a block and apply to the whole block:
+//! ```
+//! # /*
+//! use crate::components::buttons::*;
+//! const MAX_SHOWN: usize = 10;
+//!
+//! PrimaryButton { + "Save"; }
+//! for item in items.iter().take(MAX_SHOWN) { li { + item; } }
+//! # */
+//! ```
+//!
+//! Local functions help factor out repeated markup;
+//! like other Rust items, they cannot use the local variables of the block.
+//! ```
+//! # /*
+//! fn row(cells: &[String]) -> Html {
+//!     defy! { tr { for cell in cells { td { + cell; } } } }
+//! }
+//!
+//! table {
+//!     + row(&header);
+//!     for cells in &rows { + row(cells); }
+//! }
+//! # */
+//! ```
+//!
+//! To compute a value in the middle of a block, use a `with` block instead,
+//! which evaluates the expression once and binds it for its contents:
+//! ```
+//! # /*
+//! h1 { + title; }
+//! with summarize(&post) as summary {
+//!     p(class = "summary") { + summary.text; }
+//! }
+//! # */
+//! ```
+//!
+//! # Side effects
+//! ```
+//! # /*
+//! do log::debug!("rendering {} items", items.len());
+//! # */
+//! ```
+//! evaluates the expression in order with the surrounding nodes without rendering anything.
+//!
+//! # Notes
+//! ```
+//! # /*
+//! /// The hero image is replaced by a carousel on wide screens.
+//! note "Keep this banner in sync with the marketing site.";
+//! # */
+//! ```
+//! document the template without rendering anything.
+//! With `@emit_notes`, or `emit_notes = true` in the [workspace defaults](#workspace-defaults),
+//! they are rendered as HTML comments instead.
+//!
+//! # Attributes
+//! Any statement can be prefixed with `#[cfg(...)]` attributes,
+//! which exclude it at compile time like in normal Rust code:
+//! ```
+//! # /*
+//! #[cfg(feature = "admin")]
+//! AdminPanel;
+//! # */
+//! ```
+//!
+//! Other outer attributes, such as `#[allow(...)]` or tool attributes,
+//! are forwarded to the generated statement.
+//! `#[allow(...)]`, `#[warn(...)]` and `#[deny(...)]` attributes on an element
+//! that only name [defy lints](#lints) configure the lints instead.
+//!
+//! # If, If-else, For
+//! Same as the normal Rust syntax, except the contents in braces are automatically `defy!`-ed.
+//!
+//! ```
+//! # /*
+//! if media "(max-width: 600px)" { MobileNav; } else { DesktopNav; }
+//! # */
+//! ```
+//! renders the first block while the CSS media query matches, updating when the result changes,
+//! and the `else` block, which is empty if omitted, otherwise.
+//! The `else` block is also rendered by server-side rendering and before the component is mounted,
+//! so that hydration does not depend on the screen of the client.
+//! Both blocks are rendered on every render, so they cannot use `break` or `continue`.
+//!
+//! A `for` loop may be followed by an `else` block, which is rendered when there are no items:
+//! ```
+//! # /*
+//! for item in items { li { + item; } } else { p { + "No results"; } }
+//! # */
+//! ```
+//!
+//! `break;` and `continue;` can be used in the body of a `for` loop, including in nested blocks:
+//! ```
+//! # /*
+//! for (i, row) in rows.iter().enumerate() {
+//!     if i == 10 { break; }
+//!     tr { td { + row; } }
+//! }
+//! # */
+//! ```
+//! Such loops collect their items into a `Vec` instead of mapping over the iterator lazily.
+//! With an `else` block, `else` is rendered if no items were rendered.
+//!
+//! ```
+//! # /*
+//! repeat 5 { span(class = "star"); }
+//! repeat columns as i { Cell(index = i); }
+//! # */
+//! ```
+//! is short for `for _ in 0..5 { ... }` and `for i in 0..columns { ... }`.
+//! Since the items are interchangeable, `repeat` does not warn about missing keys.
+//!
+//! In debug builds, a `for` loop whose body is a single node without `key`
+//! prints a warning to the browser console (or stderr during server-side rendering)
+//! the first time it renders an item, since yew cannot reuse the DOM nodes of unkeyed lists.
+//!
+//! # Virtual lists
+//! ```
+//! # /*
+//! virtual for row in rows.clone() (height = 32, viewport = 600) { Row(row = row.clone()); }
+//! # */
+//! ```
+//! renders a scrollable `div` that is `viewport` pixels high,
+//! containing only the items visible at the current scroll position and two more on each side.
+//! Each item must be exactly `height` pixels high.
+//! The items are passed as an `Rc<[T]>` or a value converted into one, such as a `Vec<T>`,
+//! so pass an `Rc` to avoid copying the items on every render.
+//! The body borrows each item like `for row in &rows`,
+//! but cannot borrow other local variables or use `break` or `continue`.
+//! Server-side rendering emits the items at the top of the list.
+//!
+//! # Maybe
+//! ```
+//! # /*
+//! maybe user.avatar_url as url { img(src = url); } else { Initials(user = user.clone()); }
+//! # */
+//! ```
+//! is short for `if let Some(url) = user.avatar_url { ... } else { ... }`.
+//! The `else` block is optional.
+//!
+//! # Conditional wrappers
+//! ```
+//! # /*
+//! wrap_if props.href.is_some() a(href = props.href.clone()) { + "label"; }
+//! # */
+//! ```
+//! renders the children inside the element if the condition is true, or without it otherwise.
+//! The element must have children.
+//!
+//! # Transitions
+//! ```
+//! # /*
+//! transition(enter = "fade-in", leave = "fade-out") if visible { Dialog; }
+//! # */
+//! ```
+//! renders the children inside a `div` while the condition is true,
+//! like an `if` block with a wrapper element that animates the children in and out.
+//! When the condition becomes true, the `div` has the `enter` class
+//! until its CSS animation or transition ends.
+//! When the condition becomes false, the children stay mounted with the `leave` class
+//! until its animation or transition ends, and are removed afterwards.
+//! Both classes are optional.
+//! In case an animation does not start, e.g. because the class has no styles,
+//! the class is removed after `duration` milliseconds, which defaults to `500`.
+//!
+//! The children are not animated on the first render,
+//! and server-side rendering emits them without classes if the condition is true.
+//!
+//! # Forms
+//! ```
+//! # /*
+//! form for signup { field email(required = true); field age; }
+//! # */
+//! ```
+//! renders a labeled, controlled input for each field of a `UseStateHandle`
+//! of a struct deriving [`form::Form`],
+//! with the HTML constraints of its `#[validate(...)]` rules.
+//! See the [`form`] module for details.
+//!
+//! # Tables
+//! ```
+//! # /*
+//! table(class = "users") for user in &users {
+//!     column "Name" { + &user.name; }
+//!     column "Age" { + user.age; }
+//! }
+//! # */
+//! ```
+//! renders a `table` with a header row of `th(scope = "col")` cells in `thead`,
+//! and a row of `td` cells for each item in `tbody`.
+//!
+//! # Match
+//! Same as the normal Rust syntax, except match arm bodies must be surrounded in braces,
+//! and the contents inside are automatically `defy!`-ed.
+//!
+//! # Memo
+//! ```
+//! # /*
+//! memo(items = props.items.clone(), count) { ... }
+//! # */
+//! ```
+//! renders the block in a separate component that is only re-rendered
+//! when one of the dependencies changes.
+//! Each dependency is written like a node argument, where `count` is short for `count = count`;
+//! the values are cloned, compared with `PartialEq`,
+//! and available in the block as references under their names.
+//! The block cannot borrow other local variables, since it may be re-rendered later.
+//!
+//! ```
+//! # /*
+//! once { ... }
+//! # */
+//! ```
+//! is a memo block without dependencies.
+//! It is rendered on the first render of the component and reused afterwards,
+//! which suits large static markup such as legal text or icon sprites.
+//!
+//! # Defer
+//! ```
+//! # /*
+//! defer { Comments(id = post_id); } placeholder { p { + "Loading comments..."; } }
+//! # */
+//! ```
+//! renders the placeholder, which is empty if omitted,
+//! until the component containing the block is mounted,
+//! and the contents of the block afterwards.
+//! This keeps below-the-fold content out of the first render of long pages.
+//! Server-side rendering only emits the placeholder.
+//! Like memo blocks, the contents cannot borrow local variables.
+//!
+//! # Await
+//! ```
+//! # /*
+//! await fetch_user(id) as user { ProfileCard(user = user.clone()); } pending { Spinner; }
+//! # */
+//! ```
+//! renders the `pending` block, which is empty if omitted, in a yew `Suspense`
+//! until the future returned by `expr` completes,
+//! and the contents of the block afterwards, where `user` is a reference to the output.
+//! The future is created when the block is first rendered,
+//! and recreated when one of the dependencies in parentheses after `await` changes:
+//! ```
+//! # /*
+//! await(id) fetch_user(id) as user { ProfileCard(user = user.clone()); }
+//! # */
+//! ```
+//! Like in memo blocks, the dependencies are cloned into the expression by name,
+//! either from a variable or from a `name = expr` value,
+//! and the expression and the contents cannot borrow local variables.
+//! An expression starting with a parenthesis needs empty dependencies before it,
+//! e.g. `await() (load)() as x`.
+//!
+//! # Lazy components
+//! ```
+//! # /*
+//! lazy settings::Panel(user = user.clone()) { fallback { Spinner; } }
+//! # */
+//! ```
+//! renders the `fallback` block, which is empty if omitted, in a yew `Suspense`
+//! until the code of the component is loaded, and the component afterwards.
+//! The component cannot have children.
+//! See the [`lazy`] module for how targets that split their bundle load components.
+//!
+//! # Try
+//! ```
+//! # /*
+//! try {
+//!     let count = input.parse::<u32>()?;
+//!     + count * 2;
+//! } catch err {
+//!     p(class = "error") { + err.to_string(); }
+//! }
+//! # */
+//! ```
+//! allows `?` in the expressions of the block, including `let` statements and text values.
+//! If an expression fails, the `catch` block is rendered instead with the error bound to `err`.
+//! The error type is `Box<dyn Error>` unless specified, e.g. `catch err: ParseIntError { ... }`.
+//! Without a `catch` block, nothing is rendered on failure.
+//! `?` cannot be used inside nested `for` loops, whose bodies are closures.
+//!
+//! # Include
+//! ```
+//! # /*
+//! include "templates/footer.defy";
+//! # */
+//! ```
+//! reads the file at the given path (relative to `CARGO_MANIFEST_DIR`)
+//! and expands its contents as if they were written in place.
+//! The crate is rebuilt when the included file changes.
+//!
+//! # Markdown
+//! ```
+//! # /*
+//! markdown "docs/about.md";
+//! # */
+//! ```
+//! converts the Markdown file at the given path (relative to `CARGO_MANIFEST_DIR`)
+//! to HTML at compile time. Requires the `markdown` feature, which is enabled by default.
+//!
+//! ```
+//! # /*
+//! markdown expr;
+//! # */
+//! ```
+//! converts the string `expr` at runtime instead.
+//! This requires the `markdown-runtime` feature
+//! and a dependency on [`pulldown_cmark`](https://docs.rs/pulldown-cmark) in the calling crate.
+//!
+//! With `@minify`, or `minify = true` in the [workspace defaults](#workspace-defaults),
+//! comments and whitespace that is not rendered are removed from markdown files
+//! at compile time, and other runs of whitespace are collapsed to a single space.
+//! The contents of `pre`, `textarea`, `script` and `style` elements are unchanged.
+//! Other statements never render whitespace between elements.
+//!
+//! # Localized text
+//! ```
+//! # /*
+//! @i18n crate::i18n::lookup
+//! @i18n_resources "locales/en-US"
+//!
+//! t "greeting" { name = user.name };
+//! # */
+//! ```
+//! becomes
+//! ```text
+//! `{ crate::i18n::lookup("greeting", &[("name", ToString::to_string(&user.name))]) }`
+//! ```
+//! The lookup function is provided by the caller through `@i18n`,
+//! which allows plugging in any localization backend, e.g. `fluent-templates`.
+//! Its return value is rendered like a text value.
+//!
+//! If `@i18n_resources` is specified, the Fluent (`.ftl`) file at the given path,
+//! or all `.ftl` files in the given directory (relative to `CARGO_MANIFEST_DIR`),
+//! are read at compile time, and an error is reported for message IDs not defined in them.
+//!
+//! # Icons
+//! ```
+//! # /*
+//! icon "assets/icons/check.svg" (class = "w-4");
+//! # */
+//! ```
+//! parses the SVG file at the given path (relative to `CARGO_MANIFEST_DIR`) at compile time
+//! and inlines its elements, so that styles such as `currentColor` apply to the icon.
+//! The optional arguments are added to the root `svg` element,
+//! replacing the attributes of the same name in the file.
+//!
+//! # Asset URLs
+//! An attribute value in the form `asset("images/logo.png")` is resolved as an asset URL.
+//! ```
+//! # /*
+//! @asset_resolver crate::assets::url
+//!
+//! img(src = asset("images/logo.png"));
+//! # */
+//! ```
+//! becomes
+//! ```html
+//! <img src={crate::assets::url("images/logo.png")} />
+//! ```
+//! The resolver function can perform cache-busting lookups,
+//! e.g. from a manifest generated by a build script.
+//!
+//! Without `@asset_resolver`, the path is prefixed with the value of
+//! the `DEFY_ASSET_BASE` environment variable at compile time, if it is set.
+//! Changing the environment variable rebuilds the crates using `defy!`.
+//!
+//! # Route URLs
+//! An attribute value in the form `route!(route)` is the path of a `yew_router::Routable` value:
+//! ```
+//! # /*
+//! a(href = route!(Route::Post { id })) { + "Read more"; }
+//! # */
+//! ```
+//! becomes
+//! ```html
+//! <a href={Routable::to_path(&Route::Post { id })}>{ "Read more" }</a>
+//! ```
+//! prefixed with the path of the `<base href>` element,
+//! which `yew_router::BrowserRouter` uses as its basename by default.
+//! The element is read once, when the first link is rendered.
+//! Server-rendered pages have no document to read,
+//! so the server sets the same prefix with [`ssr::set_route_base`] at startup,
+//! to render the `href`s that the client hydrates.
+//! The calling crate must depend on `yew_router`.
+//!
+//! # Responsive images
+//! ```
+//! # /*
+//! picture "images/hero.jpg"(
+//!     widths = [480, 960],
+//!     formats = ["avif", "webp"],
+//!     sizes = "(max-width: 600px) 100vw, 50vw",
+//!     alt = "Hero",
+//! );
+//! # */
+//! ```
+//! becomes
+//! ```html
+//! <picture>
+//!     <source type="image/avif" srcset="images/hero-480w.avif 480w, images/hero-960w.avif 960w" sizes="..." />
+//!     <source type="image/webp" srcset="images/hero-480w.webp 480w, images/hero-960w.webp 960w" sizes="..." />
+//!     <img src="images/hero.jpg" srcset="images/hero-480w.jpg 480w, images/hero-960w.jpg 960w" sizes="..." alt="Hero" />
+//! </picture>
+//! ```
+//! The variants of each width and format are expected next to the image,
+//! e.g. generated by a build script.
+//! All URLs are resolved like [asset URLs](#asset-urls).
+//! `formats` and `sizes` are optional, and other arguments are passed to the `img` element.
+//!
+//! # Document head
+//! ```
+//! # /*
+//! document_head {
+//!     title { +page_title; }
+//!     meta(name = "description", content = desc);
+//! }
+//! # */
+//! ```
+//! renders the contents of the block into the `<head>` of the document,
+//! so that any component can contribute tags like `og` blocks to the head.
+//! The tags are removed when the component is unmounted.
+//! A `title` with only text children sets the document title instead,
+//! which is restored when the component is unmounted.
+//!
+//! The contents are not rendered during server-side rendering.
+//! `head { ... }` is a `<head>` element as usual.
+//!
+//! # Full documents
+//! ```
+//! # /*
+//! doctype html;
+//! html(lang = "en") {
+//!     head {
+//!         meta(charset = "utf-8");
+//!         title { + "Not found"; }
+//!     }
+//!     body { h1 { + "404"; } }
+//! }
+//! # */
+//! ```
+//! renders a complete page, e.g. for server-rendered error pages and emails.
+//! `doctype html;` renders `<!DOCTYPE html>`.
+//! Since nothing can precede the doctype,
+//! render the page with `yew::LocalServerRenderer::hydratable(false)`,
+//! which omits the comments that mark components for hydration.
+//!
+//! # Layouts
+//! ```
+//! # /*
+//! @blocks blocks
+//! header { block header { h1 { + "My site"; } } }
+//! main { block content {} }
+//! # */
+//! ```
+//! defines the blocks of a layout function taking `blocks: defy::layout::Blocks`,
+//! which render their children unless a page overrides them:
+//! ```
+//! # /*
+//! extend base_layout {
+//!     block content { p { + "About us"; } }
+//! }
+//! # */
+//! ```
+//! See the `layout` module for details.
+//!
+//! # OpenGraph tags
+//! ```
+//! # /*
+//! og {
+//!     title "Defy";
+//!     description "Rust-idiomatic HTML templates";
+//!     image image_url;
+//! }
+//! # */
+//! ```
+//! becomes
+//! ```html
+//! <meta property="og:title" content="Defy" />
+//! <meta name="twitter:title" content="Defy" />
+//! <meta property="og:description" content="Rust-idiomatic HTML templates" />
+//! <meta name="twitter:description" content="Rust-idiomatic HTML templates" />
+//! <meta property="og:image" content="..." />
+//! <meta name="twitter:image" content="..." />
+//! <meta name="twitter:card" content="summary_large_image" />
+//! ```
+//! Each value is converted with `ToString`.
+//! The supported properties are
+//! `title`, `description`, `image`, `image_alt`, `image_width`, `image_height`,
+//! `url`, `type`, `site_name` and `locale` for OpenGraph,
+//! and `card`, `site` and `creator` for Twitter cards.
+//! `card` defaults to `summary_large_image` with an `image`, or `summary` otherwise.
+//!
+//! # JSON data
+//! ```
+//! # /*
+//! json_script(id = "page-data") = &page_state;
+//! # */
+//! ```
+//! serializes `page_state` with `serde_json` into
+//! ```html
+//! <script type="application/json" id="page-data">{"user":"\u003c/script\u003e"}</script>
+//! ```
+//! so that client scripts can read it with `JSON.parse(document.getElementById("page-data").textContent)`.
+//! `<`, `>` and `&` are escaped, so strings in the value cannot close the script element.
+//! This requires the `json` feature.
+//!
+//! # XML documents
+//! `@xml` renders elements as XML instead of HTML,
+//! for sitemaps, RSS feeds and other documents rendered on the server:
+//! ```
+//! # /*
+//! @xml
+//! xml_decl(version = "1.0", encoding = "UTF-8");
+//! urlset(xmlns = "http://www.sitemaps.org/schemas/sitemap/0.9", xmlns:image = "http://www.google.com/schemas/sitemap-image/1.1") {
+//!     url {
+//!         loc { + page.url; }
+//!         image::image { image::loc { + page.image; } }
+//!     }
+//! }
+//! # */
+//! ```
+//! Elements without children are rendered as empty-element tags, e.g. `<br/>`,
+//! and `::` in element names and `:` in attribute names are rendered as namespace prefixes.
+//! Element and attribute names are not checked against HTML,
+//! and HTML void elements such as `link` can have children.
+//! Attributes must have values, which are converted with `ToString`,
+//! and event handlers, `bind:` and props are not supported.
+//!
+//! `xml_decl(...)` renders an XML declaration with the string literal arguments in order.
+//! Since nothing can precede the declaration,
+//! render the document with `yew::LocalServerRenderer::hydratable(false)`,
+//! which omits the comments that mark components for hydration.
+//!
+//! ```
+//! # /*
+//! @csp_nonce props.nonce
+//! script(src = "/app.js") {}
+//! # */
+//! ```
+//! adds `nonce={props.nonce.clone()}` to every `script` and `style` element,
+//! including those generated by `json_script`,
+//! so that they are allowed by a strict `Content-Security-Policy`.
+//! Elements with an explicit `nonce` are unchanged.
+//! The expression cannot contain binary operators unless it is parenthesized.
+//!
+//! # Scoped classes
+//! ```
+//! # /*
+//! @scope "card"
+//!
+//! div(class = "title large");
 //! # */
 //! ```
 //! becomes
-//! ```text
-//! `{ expr }`
+//! ```html
+//! <div class="card__title card__large" />
 //! ```
+//! Only string literals and the classes of [class maps](#conditional-classes) passed to `class`
+//! are rewritten.
 //!
-//! # Local variables
-//! Local variables can be defined in the form of normal `let` statements.
-//! However they must precede all non-`let` statements in a `{}` block
-//! in order to preserve evaluation order.
+//! If the `DEFY_SCOPE_MAP_DIR` environment variable is set at compile time,
+//! the mapping of each scope is written to `$DEFY_SCOPE_MAP_DIR/<scope>.classes`,
+//! one `original scoped` pair per line, for use by the stylesheet build pipeline.
 //!
-//! If executing a `let` statement after other contents is really necessary,
-//! place them under a separate `if true {}` block.
+//! # Scoped styles
+//! ```
+//! # /*
+//! div(class = "card") {
+//!     style { r#" padding: 1rem; &:hover { color: red; } "# }
+//!     + "content";
+//! }
+//! # */
+//! ```
+//! creates a [`stylist`](https://docs.rs/stylist) scoped stylesheet from the string literal,
+//! which is parsed at compile time by `stylist::css!`,
+//! and adds its generated class name to the enclosing element.
+//! The calling crate must depend on `stylist`.
 //!
-//! # If, If-else, For
-//! Same as the normal Rust syntax, except the contents in braces are automatically `defy!`-ed.
+//! # Design tokens
+//! ```
+//! # /*
+//! @theme "theme.toml"
 //!
-//! # Match
-//! Same as the normal Rust syntax, except match arm bodies must be surrounded in braces,
-//! and the contents inside are automatically `defy!`-ed.
+//! div(style = format!("color: {}", token!(color.primary))) {
+//!     + token!(brand.name);
+//! }
+//! # */
+//! ```
+//! reads the TOML file at the given path (relative to `CARGO_MANIFEST_DIR`),
+//! and replaces each `token!(dotted.key)` in attribute values and text values
+//! with the literal value of the key in the file.
+//! Unknown keys are reported as compile errors.
+//!
+//! # Lints
+//! defy reports compiler warnings for likely mistakes in the macro input:
+//!
+//! - `unknown_tag`: a lowercase element name that is not a known HTML or SVG tag, e.g. `dvi`.
+//! - `unknown_attr`: an attribute that is not valid for the HTML element, e.g. `div(href = ...)`.
+//!   `data-*`, `aria-*` and event handler attributes are always allowed.
+//! - `duplicate_attr`: an attribute specified more than once,
+//!   which is denied by default since yew would keep only one of them.
+//! - `obsolete`: an obsolete element such as `center` or `font`,
+//!   or a deprecated presentational attribute such as `align` or `bgcolor`,
+//!   with a suggestion for the CSS replacement.
+//! - `unsafe_url`: a URL literal that runs scripts, e.g. `a(href = "javascript:void(0)")`,
+//!   which is denied by default.
+//!   `vbscript:` URLs and `data:` URLs in `href`, `action` and `formaction` are also reported.
+//! - `dynamic_srcdoc`: an `iframe` without `sandbox` whose `srcdoc` is not a string literal,
+//!   since untrusted content in it would run scripts in the origin of the page.
+//!
+//! The following accessibility lints are allowed by default,
+//! and can be enabled together through the lint group `a11y`:
+//!
+//! - `img_alt`: an `img` element without `alt`.
+//! - `click_role`: an `onclick` handler on a non-interactive element without `role` or `tabindex`.
+//! - `anchor_href`: an `a` element without `href`.
+//!
+//! The level of each lint can be configured with `@allow(...)`, `@warn(...)` and `@deny(...)`:
+//! ```
+//! # /*
+//! @warn(a11y)
+//! @deny(unknown_tag)
+//! @allow(unknown_attr)
+//! # */
+//! ```
+//!
+//! Lint levels can also be configured for a node and its descendants
+//! with `#[allow(...)]`, `#[warn(...)]` and `#[deny(...)]` attributes:
+//! ```
+//! # /*
+//! #[allow(unknown_tag)]
+//! widget { + "Custom"; }
+//! # */
+//! ```
+//!
+//! `@strict` denies all lints, including those allowed by default,
+//! unless their level is configured explicitly.
+//!
+//! On stable Rust, warnings are reported as uses of deprecated items named after the lint.
+//! With the `nightly` feature enabled,
+//! they are emitted as native compiler warnings through `proc_macro::Diagnostic` instead.
+//!
+//! # Diagnostic codes
+//! Each error and lint message starts with a stable code, e.g. `DEFY0001: let statements must ...`.
+//!
+//! | Code | Meaning |
+//! | --- | --- |
+//! | `DEFY0001` | `let` statement after other statements in a block |
+//! | `DEFY0002` | `style` block outside an element |
+//! | `DEFY0003` | children in a void element |
+//! | `DEFY0004` | rest arguments on an icon |
+//! | `DEFY0005` | `style` block in an element with rest arguments |
+//! | `DEFY0006` | `t` statement without `@i18n` |
+//! | `DEFY0007` | message not defined in `@i18n_resources` |
+//! | `DEFY0008` | markdown file without the `markdown` feature |
+//! | `DEFY0009` | dynamic markdown without the `markdown-runtime` feature |
+//! | `DEFY0010` | undefined theme token |
+//! | `DEFY0011` | theme token that is not a string, finite number or boolean |
+//! | `DEFY0012` | malformed `token!(...)` |
+//! | `DEFY0013` | icon file without a root `<svg>` |
+//! | `DEFY0014` | unknown lint name |
+//! | `DEFY0016` | `break` or `continue` outside a `for` loop |
+//! | `DEFY0017` | `bind:` other than `bind:value = ...` and `bind:checked = ...` |
+//! | `DEFY0020` | a file cannot be read |
+//! | `DEFY0021` | a file cannot be parsed |
+//! | `DEFY0022` | a file cannot be written |
+//! | `DEFY0023` | invalid `defy.toml` |
+//! | `DEFY0024` | `#[derive(Form)]` on a type other than a struct with named fields, or an unknown `#[form(...)]` option |
+//! | `DEFY0025` | `picture` without `widths`, with malformed arguments, or with a path without extension |
+//! | `DEFY0026` | `json_script` without the `json` feature |
+//! | `DEFY0027` | unknown or duplicate property in an `og` block |
+//! | `DEFY0028` | unknown character reference |
+//! | `DEFY0029` | `testid` without a value |
+//! | `DEFY0030` | invalid `#[stories]` module or `#[story]` function |
+//! | `DEFY0031` | key filter on a non-keyboard event or with an unknown modifier |
+//! | `DEFY0032` | unknown or invalid event modifier |
+//! | `DEFY0033` | unknown, duplicate or missing `transition` or `virtual for` argument |
+//! | `DEFY0034` | `island` without the `json` feature, or with an element, children or arguments other than props |
+//! | `DEFY0035` | invalid attribute in `@xml` mode or `xml_decl`, or a namespaced attribute without `@xml` |
+//! | `DEFY0036` | `block` without `@blocks`, or a duplicate block or other statement in `extend` |
+//! | `DEFY0037` | unbalanced or improperly nested tags in `raw` |
+//! | `DEFY0038` | arguments other than `props = value` in `dyn` |
+//! | `DEFY0039` | an HTML element or children other than `fallback` in `lazy` |
+//! | `DEFY0040` | element or attribute name in an icon file that is not dash-separated identifiers |
+//! | `DEFY0041` | `bind:` without a `UseStateHandle` value |
+//! | `DEFY0101` | text node without `+` |
+//! | `DEFY0102` | braces in an unparenthesized `if`, `match` or `for` head |
+//! | `DEFY0103` | element without `;` or children |
+//! | `DEFY0104` | `await`, `with` or `maybe` block without `as` |
+//! | `DEFY0106` | `wrap_if` element without children |
+//! | `DEFY0107` | `options from` without `value` |
+//! | `DEFY0108` | `@event` without a handler |
+//! | `DEFY0109` | `virtual for` without `(name = value, ...)` arguments |
+//! | `DEFY0110` | `doctype` other than `doctype html;` |
+//! | `DEFY0111` | `include` without a string literal path |
+//! | `DEFY1001` | lint `unknown_tag` |
+//! | `DEFY1002` | lint `unknown_attr` |
+//! | `DEFY1003` | lint `duplicate_attr` |
+//! | `DEFY1004` | lint `obsolete` |
+//! | `DEFY1005` | lint `img_alt` |
+//! | `DEFY1006` | lint `click_role` |
+//! | `DEFY1007` | lint `anchor_href` |
+//! | `DEFY1008` | lint `unsafe_url` |
+//! | `DEFY1009` | lint `dynamic_srcdoc` |
+//!
+//! Other syntax errors have no code.
+//! Codes are never reused for a different meaning.
+//!
+//! To collect the diagnostics for CI tooling,
+//! set `diagnostics` in the [workspace defaults](#workspace-defaults) to a directory
+//! relative to `defy.toml`, e.g. `diagnostics = "target/defy-diagnostics"`.
+//! Each invocation writes its errors and warnings as JSON lines
+//! to a file in the directory named after its location, e.g. `src_main.rs-3-9.jsonl`:
+//! ```json
+//! {"level":"warning","code":"DEFY1001","lint":"unknown_tag","message":"`dvi` is not a known HTML or SVG tag","file":"src/main.rs","line":3,"column":9}
+//! ```
+//! The file is replaced whenever the invocation is expanded, and removed if it has no diagnostics,
+//! so fixed diagnostics do not remain.
+//! Files of invocations that were moved or deleted remain until the directory is removed.
+//!
+//! # Sharing configs
+//! `@export name` defines a `macro_rules!` macro `name`
+//! that invokes `defy!` with the other configs of the invocation
+//! and the macro path it uses, including one from the [workspace defaults](#workspace-defaults).
+//! `@debug_print`, `@debug_loc` and `@emit_notes` only apply to the invocation itself.
+//! An invocation with only configs and no nodes can be used in item position,
+//! so that the helper functions of a module can share the configs:
+//! ```
+//! # /*
+//! defy::defy! {
+//!     @export my_defy
+//!     @macro_path ::yew::html_nested
+//! }
+//!
+//! fn item() -> yew::virtual_dom::VNode {
+//!     my_defy! { li; }
+//! }
+//! # */
+//! ```
+//! Otherwise, the macro is only available to the invocations nested inside the expansion.
+//!
+//! # Workspace defaults
+//! Defaults for all invocations can be configured in a `defy.toml`
+//! in the crate directory or any of its ancestors, e.g. the workspace root,
+//! or in the file specified by the `DEFY_CONFIG` environment variable:
+//! ```toml
+//! macro_path = "::yew::html_nested"
+//! strict = false
+//!
+//! [lints]
+//! a11y = "warn"
+//! unknown_tag = "deny"
+//! ```
+//! Configs in the invocation override these defaults.
+//!
+//! # Debugging
+//! `@debug_print` prints the generated code to stdout during compilation,
+//! formatted with prettyplease.
+//! To write it to a file relative to `CARGO_MANIFEST_DIR` instead,
+//! which is useful for diffing expansions across refactors:
+//! ```
+//! # /*
+//! @debug_print("target/defy/login.rs")
+//! # */
+//! ```
+//!
+//! `@debug_print(note)` reports it as a compiler warning at the config instead,
+//! which is visible in `cargo check` output and editors.
+//!
+//! `@debug_loc` adds a `data-defy-loc="src/views/cart.rs:42"` attribute with the template location
+//! to each HTML element in debug builds, so that elements found with the browser's DOM inspector
+//! can be traced back to their template.
+//! The attribute is omitted in release builds.
+//! It can be enabled for all invocations with `debug_loc = true` in the workspace defaults.
+//!
+//! # Tracing
+//! With the `trace` feature, `@trace` wraps the construction of each top-level statement
+//! in a `tracing` span named `defy`, with the fields `node` (the element name or statement kind)
+//! and `loc` (the source location),
+//! so that server-side rendering profiles show which templates are slow.
+//! It can be enabled for all invocations with `trace = true` in the workspace defaults.
+//! Without the `trace` feature, `@trace` has no effect.
+//!
+//! # Stories
+//! [`#[stories]`](story) generates a gallery component
+//! that previews the `#[story]` functions in a module.
+//!
+//! # Islands
+//! ```
+//! # /*
+//! island Counter(start = 1);
+//! # */
+//! ```
+//! renders the component with markers for hydrating it on the client
+//! without hydrating the rest of a server-rendered page.
+//! Its props are serialized to JSON, which requires the `json` feature.
+//! See the `island` module for details.
+//!
+//! # Server-side rendering
+//! [`ssr::pretty_print`] formats the HTML rendered by yew's server renderer
+//! with indentation, for debugging and reviewing the output of server-rendered pages and emails.
+//!
+//! With the `ssr` feature, `ssr::render_stream` renders a template as a `Stream` of HTML chunks
+//! for streaming response bodies.
+//! A chunk is emitted whenever rendering waits for an `await` block,
+//! so the shell of a page is sent before slow data resolves.
+//!
+//! [`ssr::HtmlResponse`] returns a rendered page from a handler with the HTML `Content-Type`,
+//! implementing axum's `IntoResponse` with the `axum` feature
+//! and actix-web's `Responder` with the `actix-web` feature.
+//!
+//! # Testing
+//!
+//! The `testing` feature enables the [`testing`] module,
+//! which expands templates at runtime for snapshot tests
+//! and compares rendered HTML regardless of formatting.
+//!
+//! For end-to-end tests, `testid = "..."` on an HTML element
+//! renders a `data-testid` attribute in test and debug builds only:
+//! ```
+//! # /*
+//! button(testid = "submit", onclick = submit) { + "Submit"; }
+//! # */
+//! ```
+//! With `@testids`, or `testids = true` in the [workspace defaults](#workspace-defaults),
+//! every HTML element without a `testid` is given one derived from its name
+//! and the names of the enclosing elements and components,
+//! e.g. `login-form-button` for a `button` in a `form` in a `LoginForm` component,
+//! or `submit-span` for a `span` in the button above.
+//! Like the attribute itself, the derived test IDs are omitted in release builds.
 
-use proc_macro2::{Span, TokenStream};
-use quote::{quote, quote_spanned};
-use syn::spanned::Spanned;
-use syn::{Error, Result};
+// allows the expansion to refer to `::defy` in the tests of this crate
+extern crate self as defy;
 
-mod ast;
+pub mod class;
+pub mod dynamic;
+pub mod form;
+#[cfg(feature = "json")]
+pub mod island;
+pub mod layout;
+pub mod lazy;
+pub mod ssr;
+pub mod story;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 mod tests;
 
-/// See crate-level documentation.
-#[proc_macro]
-pub fn defy(ts: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    run(ts.into()).unwrap_or_else(Error::into_compile_error).into()
-}
+pub use defy_macros::{defy, defy_iter};
+pub use story::{stories, story};
 
-struct Config {
-    debug_print: bool,
-    macro_path:  syn::Path,
-}
+/// Items used by the expansion. Not public API.
+#[doc(hidden)]
+pub mod __private {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::rc::Rc;
+    use std::time::Duration;
 
-fn run(ts: TokenStream) -> Result<TokenStream> {
-    let input: ast::Input = syn::parse2(ts)?;
+    #[cfg(feature = "trace")]
+    pub use tracing;
+    use web_sys::wasm_bindgen::JsCast;
+    use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement, KeyboardEvent};
+    use yew::events::{AnimationEvent, TransitionEvent};
+    use yew::html::{IntoPropValue, Properties, TargetCast};
+    use yew::suspense::{use_future_with, Suspense, SuspenseProps};
+    use yew::virtual_dom::{Listener, ListenerKind, VChild, VNode};
+    use yew::{
+        function_component, html, use_effect_with, use_mut_ref, use_state, AttrValue, Callback,
+        Event, Html, HtmlResult, InputEvent, UseStateHandle,
+    };
 
-    let mut config =
-        Config { debug_print: false, macro_path: syn::parse2(quote!(::yew::html)).unwrap() };
-    for ast_config in input.configs {
-        match ast_config {
-            ast::Config::DebugPrint { at: _, kw: _ } => config.debug_print = true,
-            ast::Config::MacroPath { at: _, kw: _, path } => config.macro_path = path,
-        }
+    /// Converts the value of an HTML attribute.
+    pub fn attr<M>(value: impl Attr<M>) -> Option<AttrValue> { value.into_attr() }
+
+    /// Values of HTML attributes.
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` cannot be used as the value of an HTML attribute",
+        label = "expected a string, `AttrValue` or an `Option` of them",
+        note = "convert other values to strings first, e.g. with `.to_string()`"
+    )]
+    pub trait Attr<M> {
+        fn into_attr(self) -> Option<AttrValue>;
     }
 
-    let output = emit(&config.macro_path, Span::call_site(), input.nodes)?;
-    if config.debug_print {
-        println!("{output}")
+    impl<T: IntoPropValue<Option<AttrValue>>> Attr<One> for T {
+        fn into_attr(self) -> Option<AttrValue> { self.into_prop_value() }
     }
-    Ok(output)
-}
 
-fn emit(macro_path: &syn::Path, span: Span, nodes: ast::Nodes) -> Result<TokenStream> {
-    let mut stmts = nodes.stmts.into_iter().peekable();
+    /// Never implemented.
+    ///
+    /// Since two implementations of `Attr` could apply to any type,
+    /// the compiler reports the message of `Attr` instead of the unsatisfied `IntoPropValue` bound.
+    pub trait Unsupported {}
 
-    let mut locals = Vec::new();
-    while let Some(ast::Stmt::Let(..)) = stmts.peek() {
-        let ast::Let { let_, pat, eq, expr, semi } = match stmts.next() {
-            Some(ast::Stmt::Let(stmt)) => stmt,
-            _ => unreachable!(),
-        };
-        locals.push(quote_spanned! { let_.span() =>
-            #let_ #pat #eq #expr #semi
-        });
+    pub struct Never;
+
+    impl<T: Unsupported> Attr<Never> for T {
+        fn into_attr(self) -> Option<AttrValue> { unreachable!() }
+    }
+
+    /// Renders the value of a `+` text node.
+    pub fn text<M>(value: impl Text<M>) -> Html { value.into_html() }
+
+    /// Values that `+` text nodes can render.
+    ///
+    /// `M` distinguishes the implementations, which would overlap otherwise.
+    #[diagnostic::on_unimplemented(
+        message = "`+` cannot render `{Self}`",
+        label = "not text, `Html` or a list of them",
+        note = "`+` accepts values implementing `Display` or `Into<Html>`, and `Option`s, `Vec`s \
+                and iterators of them"
+    )]
+    pub trait Text<M> {
+        fn into_html(self) -> Html;
+    }
+
+    pub struct One;
+    pub struct Optional;
+    pub struct List;
+    pub struct Iter;
+
+    impl<T: Into<Html>> Text<One> for T {
+        fn into_html(self) -> Html { self.into() }
+    }
+
+    impl<T: Into<Html>> Text<Optional> for Option<T> {
+        fn into_html(self) -> Html { self.map(Into::into).unwrap_or_default() }
+    }
+
+    impl<T: Into<Html>> Text<List> for Vec<T> {
+        fn into_html(self) -> Html { self.into_iter().collect() }
+    }
+
+    impl<I: Iterator<Item: Into<Html>>> Text<Iter> for I {
+        fn into_html(self) -> Html { self.map(Into::into).collect() }
+    }
+
+    /// Renders the value of an `embed` statement.
+    pub fn embed(html: impl Embed) -> Html { html.into_html() }
+
+    /// Values that `embed` statements can render.
+    #[diagnostic::on_unimplemented(
+        message = "`embed` requires `Html` or `Option<Html>`, found `{Self}`",
+        label = "not `Html` or `Option<Html>`",
+        note = "use `+ expr;` to render text and other values that implement `Into<Html>`"
+    )]
+    pub trait Embed {
+        fn into_html(self) -> Html;
+    }
+
+    impl Embed for Html {
+        fn into_html(self) -> Html { self }
+    }
+
+    impl Embed for Option<Html> {
+        fn into_html(self) -> Html { self.unwrap_or_default() }
+    }
+
+    /// Renders the body of a `memo` block, which is only re-rendered when `deps` changes.
+    pub fn memo<D: PartialEq + 'static>(deps: D, render: impl Fn(&D) -> Html + 'static) -> Html {
+        VChild::<Memo<D>>::new(MemoProps { deps, render: Rc::new(render) }, None).into()
+    }
+
+    #[derive(Properties)]
+    struct MemoProps<D: PartialEq + 'static> {
+        deps:   D,
+        render: Rc<dyn Fn(&D) -> Html>,
+    }
+
+    // `render` is rebuilt on every render of the parent, so only the dependencies are compared.
+    impl<D: PartialEq + 'static> PartialEq for MemoProps<D> {
+        fn eq(&self, other: &Self) -> bool { self.deps == other.deps }
+    }
+
+    /// Renders `placeholder` until the component is mounted, and the body of a `defer` block
+    /// afterwards.
+    pub fn defer(render: impl Fn() -> Html + 'static, placeholder: Html) -> Html {
+        VChild::<Defer>::new(DeferProps { render: Rc::new(render), placeholder }, None).into()
+    }
+
+    #[derive(Properties)]
+    struct DeferProps {
+        render:      Rc<dyn Fn() -> Html>,
+        placeholder: Html,
+    }
+
+    // the body is re-rendered with the parent like any inline content
+    impl PartialEq for DeferProps {
+        fn eq(&self, _: &Self) -> bool { false }
     }
 
-    let node_html: Vec<_> =
-        stmts.map(|stmt| stmt_to_html(macro_path, stmt)).collect::<Result<_>>()?;
-    Ok(quote_spanned! { span =>
-        #(#locals)*
-        #macro_path! {
-            <>
-                #(#node_html)*
-            </>
+    #[function_component]
+    fn Defer(props: &DeferProps) -> Html {
+        let mounted = use_state(|| false);
+        {
+            let mounted = mounted.clone();
+            use_effect_with((), move |()| mounted.set(true));
         }
-    })
-}
+        if *mounted {
+            (props.render)()
+        } else {
+            props.placeholder.clone()
+        }
+    }
 
-fn stmt_to_html(macro_path: &syn::Path, stmt: ast::Stmt) -> Result<TokenStream> {
-    Ok(match stmt {
-        ast::Stmt::If(ast::If {
-            if_,
-            expr,
-            braces: if_braces,
-            body: if_body,
-            else_: Some(ast::Else { else_, braces: else_braces, body: else_body }),
-        }) => {
-            let if_body = emit(macro_path, if_braces.span.join(), if_body)?;
-            let if_part = quote_spanned! { if_braces.span =>
-                #if_ #expr { #if_body }
-            };
+    /// Renders `content` in a `div` with the `enter` class when it becomes `Some`,
+    /// and keeps rendering its last value with the `leave` class when it becomes `None`,
+    /// until the animation or transition of the `div` ends or `duration` milliseconds pass.
+    pub fn transition(
+        content: Option<Html>,
+        enter: Option<AttrValue>,
+        leave: Option<AttrValue>,
+        duration: u32,
+    ) -> Html {
+        VChild::<Transition>::new(TransitionProps { content, enter, leave, duration }, None).into()
+    }
 
-            let else_body = emit(macro_path, else_braces.span.join(), else_body)?;
-            let else_part = quote_spanned! { else_braces.span =>
-                #else_ { #else_body }
-            };
+    #[derive(PartialEq, Properties)]
+    struct TransitionProps {
+        content:  Option<Html>,
+        enter:    Option<AttrValue>,
+        leave:    Option<AttrValue>,
+        duration: u32,
+    }
 
-            quote_spanned! { if_.span() =>
-                { #if_part #else_part }
-            }
+    #[derive(Clone, Copy, PartialEq)]
+    enum TransitionPhase {
+        Hidden,
+        Entering,
+        Shown,
+        Leaving,
+    }
+
+    #[function_component]
+    fn Transition(props: &TransitionProps) -> Html {
+        let visible = props.content.is_some();
+        let phase =
+            use_state(|| if visible { TransitionPhase::Shown } else { TransitionPhase::Hidden });
+        // the content to render while leaving
+        let last = use_mut_ref(Html::default);
+        if let Some(content) = &props.content {
+            *last.borrow_mut() = content.clone();
         }
-        ast::Stmt::If(ast::If { if_, expr, braces, body, else_: None }) => {
-            let body = emit(macro_path, braces.span.join(), body)?;
-            quote_spanned! { if_.span() =>
-                { #if_ #expr { #body } else { #macro_path! {} } }
-            }
+        // incremented when `visible` changes, so that timers of earlier changes are ignored
+        let generation = use_mut_ref(|| 0_u32);
+        {
+            let phase = phase.clone();
+            let (has_enter, has_leave) = (props.enter.is_some(), props.leave.is_some());
+            let duration = Duration::from_millis(props.duration.into());
+            use_effect_with(visible, move |&visible| {
+                *generation.borrow_mut() += 1;
+                let next = match (visible, *phase) {
+                    (true, TransitionPhase::Hidden | TransitionPhase::Leaving) if has_enter => {
+                        TransitionPhase::Entering
+                    }
+                    (true, TransitionPhase::Hidden | TransitionPhase::Leaving) => {
+                        TransitionPhase::Shown
+                    }
+                    (false, TransitionPhase::Shown | TransitionPhase::Entering) if has_leave => {
+                        TransitionPhase::Leaving
+                    }
+                    (false, TransitionPhase::Shown | TransitionPhase::Entering) => {
+                        TransitionPhase::Hidden
+                    }
+                    _ => return,
+                };
+                phase.set(next);
+                let end = match next {
+                    TransitionPhase::Entering => TransitionPhase::Shown,
+                    TransitionPhase::Leaving => TransitionPhase::Hidden,
+                    _ => return,
+                };
+                let current = *generation.borrow();
+                yew::platform::spawn_local(async move {
+                    yew::platform::time::sleep(duration).await;
+                    if *generation.borrow() == current {
+                        phase.set(end);
+                    }
+                });
+            });
         }
-        ast::Stmt::Match(ast::Match { match_, expr, braces, arms }) => {
-            let arms: TokenStream = arms
-                .into_iter()
-                .map(|ast::Arm { pat, guard, fat_arrow, braces, body }| {
-                    let guard = guard.map(|(if_, expr)| quote!(#if_ #expr));
-                    let body = emit(macro_path, braces.span.join(), body)?;
-                    Ok(quote_spanned! { braces.span =>
-                        #pat #guard #fat_arrow { #body }
-                    })
-                })
-                .collect::<Result<_>>()?;
-
-            quote_spanned! { braces.span =>
-                { #match_ #expr {
-                    #arms
-                } }
+
+        let (class, end) = match *phase {
+            TransitionPhase::Hidden => return Html::default(),
+            TransitionPhase::Entering => (props.enter.clone(), Some(TransitionPhase::Shown)),
+            TransitionPhase::Shown => (None, None),
+            TransitionPhase::Leaving => (props.leave.clone(), Some(TransitionPhase::Hidden)),
+        };
+        let finish = Rc::new(move |event: &Event| {
+            // ignore animations of the children, which bubble to the wrapper
+            if let Some(end) = end {
+                if event.target() == event.current_target() {
+                    phase.set(end);
+                }
             }
+        });
+        let onanimationend = {
+            let finish = finish.clone();
+            Callback::from(move |event: AnimationEvent| finish(&event))
+        };
+        let ontransitionend = Callback::from(move |event: TransitionEvent| finish(&event));
+        let content = last.borrow().clone();
+        html! {
+            <div {class} {onanimationend} {ontransitionend}>{ content }</div>
         }
-        ast::Stmt::For(ast::For { for_, pat, iter, in_, braces, body }) => {
-            let body = emit(macro_path, braces.span.join(), body)?;
-            quote_spanned! { in_.span() =>
-                { #for_ ::std::iter::IntoIterator::into_iter(#iter).map(|#pat| { #body }) }
-            }
+    }
+
+    /// Renders `matches` while the media query matches,
+    /// and `otherwise` if it does not match or before the component is mounted.
+    pub fn media(query: impl Into<AttrValue>, matches: Html, otherwise: Html) -> Html {
+        VChild::<Media>::new(MediaProps { query: query.into(), matches, otherwise }, None).into()
+    }
+
+    #[derive(PartialEq, Properties)]
+    struct MediaProps {
+        query:     AttrValue,
+        matches:   Html,
+        otherwise: Html,
+    }
+
+    #[function_component]
+    fn Media(props: &MediaProps) -> Html {
+        // false until mounted, so that hydration matches the server-side rendered `else` branch
+        let matches = use_state(|| false);
+        #[cfg(target_arch = "wasm32")]
+        {
+            use web_sys::wasm_bindgen::closure::Closure;
+            use web_sys::MediaQueryListEvent;
+
+            let matches = matches.clone();
+            use_effect_with(props.query.clone(), move |query| {
+                let list =
+                    web_sys::window().and_then(|window| window.match_media(query).ok().flatten());
+                let listener = list.map(|list| {
+                    matches.set(list.matches());
+                    let listener = Closure::<dyn Fn(MediaQueryListEvent)>::new(
+                        move |event: MediaQueryListEvent| matches.set(event.matches()),
+                    );
+                    let _ = list.add_event_listener_with_callback(
+                        "change",
+                        listener.as_ref().unchecked_ref(),
+                    );
+                    (list, listener)
+                });
+                move || {
+                    if let Some((list, listener)) = listener {
+                        let _ = list.remove_event_listener_with_callback(
+                            "change",
+                            listener.as_ref().unchecked_ref(),
+                        );
+                    }
+                }
+            });
         }
-        ast::Stmt::Let(ast::Let { let_, .. }) => {
-            return Err(Error::new_spanned(
-                let_,
-                "let statements must precede all other statements in a block",
-            ))
+        if *matches {
+            props.matches.clone()
+        } else {
+            props.otherwise.clone()
         }
-        ast::Stmt::Text(ast::Text { add, expr, semi: _ }) => {
-            quote_spanned! { add.span =>
-                { #expr }
-            }
+    }
+
+    /// The number of rows rendered above and below the viewport of a `virtual for` block.
+    const OVERSCAN: u32 = 2;
+
+    /// Renders the rows of a `virtual for` block that are visible
+    /// in a scrollable `div` of `viewport` pixels, where each row is `height` pixels high.
+    pub fn virtual_list<T: 'static>(
+        items: impl Into<Rc<[T]>>,
+        height: u32,
+        viewport: u32,
+        render: impl Fn(&T) -> Html + 'static,
+    ) -> Html {
+        let props =
+            VirtualListProps { items: items.into(), height, viewport, render: Rc::new(render) };
+        VChild::<VirtualList<T>>::new(props, None).into()
+    }
+
+    #[derive(Properties)]
+    struct VirtualListProps<T: 'static> {
+        items:    Rc<[T]>,
+        height:   u32,
+        viewport: u32,
+        render:   Rc<dyn Fn(&T) -> Html>,
+    }
+
+    // the rows are re-rendered with the parent like any inline content
+    impl<T: 'static> PartialEq for VirtualListProps<T> {
+        fn eq(&self, _: &Self) -> bool { false }
+    }
+
+    #[function_component]
+    fn VirtualList<T: 'static>(props: &VirtualListProps<T>) -> Html {
+        let scroll_top = use_state(|| 0_u32);
+        let onscroll = {
+            let scroll_top = scroll_top.clone();
+            Callback::from(move |event: Event| {
+                let element: web_sys::Element = event.target_unchecked_into();
+                scroll_top.set(u32::try_from(element.scroll_top()).unwrap_or(0));
+            })
+        };
+
+        let (height, viewport) = (props.height.max(1), props.viewport);
+        let len = u32::try_from(props.items.len()).unwrap_or(u32::MAX);
+        let first = (*scroll_top / height).saturating_sub(OVERSCAN).min(len);
+        let last = (*scroll_top / height + viewport.div_ceil(height) + OVERSCAN).min(len);
+        let rows = (first..last).map(|index| (props.render)(&props.items[index as usize]));
+
+        let outer = format!("height: {viewport}px; overflow-y: auto;");
+        let inner = format!(
+            "box-sizing: border-box; height: {}px; padding-top: {}px;",
+            u64::from(len) * u64::from(height),
+            first * height,
+        );
+        html! {
+            <div style={outer} {onscroll}>
+                <div style={inner}>{ for rows }</div>
+            </div>
         }
-        ast::Stmt::Node(ast::Node { element, args, body }) => {
-            let args = args_to_html(args)?;
-            match body {
-                ast::NodeBody::Semi(semi) => quote_spanned! { semi.span =>
-                    <#element #args />
-                },
-                ast::NodeBody::Braced { braces, children } => {
-                    let children = emit(macro_path, braces.span.join(), children)?;
-                    quote_spanned! { braces.span =>
-                        <#element #args>
-                            { #children }
-                        </#element>
+    }
+
+    /// Renders `pending` until the future returned by `init` completes,
+    /// and the body of an `await` block with its output afterwards.
+    /// The future is recreated when `deps` changes.
+    pub fn suspend<D: Clone + PartialEq + 'static, F: Future + 'static>(
+        deps: D,
+        init: impl Fn(D) -> F + 'static,
+        render: impl Fn(&F::Output) -> Html + 'static,
+        pending: Html,
+    ) -> Html {
+        let props = AwaitProps {
+            deps,
+            init: Rc::new(move |deps| {
+                Box::pin(init(deps)) as Pin<Box<dyn Future<Output = F::Output>>>
+            }),
+            render: Rc::new(render),
+        };
+        let children = VChild::<Await<D, F::Output>>::new(props, None).into();
+        VChild::<Suspense>::new(SuspenseProps { children, fallback: pending }, None).into()
+    }
+
+    type AwaitInit<D, O> = dyn Fn(D) -> Pin<Box<dyn Future<Output = O>>>;
+
+    #[derive(Properties)]
+    struct AwaitProps<D: PartialEq + 'static, O: 'static> {
+        deps:   D,
+        init:   Rc<AwaitInit<D, O>>,
+        render: Rc<dyn Fn(&O) -> Html>,
+    }
+
+    // the future is only recreated when the dependencies change,
+    // but the body is re-rendered with the parent
+    impl<D: PartialEq + 'static, O: 'static> PartialEq for AwaitProps<D, O> {
+        fn eq(&self, _: &Self) -> bool { false }
+    }
+
+    #[function_component]
+    fn Await<D: Clone + PartialEq + 'static, O: 'static>(props: &AwaitProps<D, O>) -> HtmlResult {
+        let output = use_future_with(props.deps.clone(), |deps| (props.init)((*deps).clone()))?;
+        Ok((props.render)(&output))
+    }
+
+    /// Renders the contents of a `head` block into the document head,
+    /// and sets the document title while the block is rendered.
+    #[cfg(target_arch = "wasm32")]
+    pub fn head(title: Option<String>, children: Html) -> Html {
+        VChild::<Head>::new(HeadProps { title, children }, None).into()
+    }
+
+    // there is no document head to render into during server-side rendering
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn head(_: Option<String>, _: Html) -> Html { Html::default() }
+
+    #[cfg(target_arch = "wasm32")]
+    #[derive(PartialEq, Properties)]
+    struct HeadProps {
+        title:    Option<String>,
+        children: Html,
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[function_component]
+    fn Head(props: &HeadProps) -> Html {
+        let document = web_sys::window().and_then(|window| window.document());
+        {
+            let document = document.clone();
+            use_effect_with(props.title.clone(), move |title| {
+                let previous = match (&document, title) {
+                    (Some(document), Some(title)) => {
+                        let previous = document.title();
+                        document.set_title(title);
+                        Some(previous)
+                    }
+                    _ => None,
+                };
+                move || {
+                    if let (Some(document), Some(previous)) = (document, previous) {
+                        document.set_title(&previous);
                     }
                 }
-            }
+            });
         }
-    })
-}
+        match document.and_then(|document| document.head()) {
+            Some(head) => yew::html::create_portal(props.children.clone(), head.into()),
+            None => Html::default(),
+        }
+    }
 
-fn args_to_html(args: ast::NodeArgs) -> Result<TokenStream> {
-    Ok(match args {
-        ast::NodeArgs::None => TokenStream::new(),
-        ast::NodeArgs::Named { paren: _, args } => args
-            .into_iter()
-            .map(|ast::NodeArg { ident, value }| match value {
-                None => quote_spanned! { ident.span() =>
-                    {#ident}
-                },
-                Some((eq, value)) => quote_spanned! { eq.span =>
-                    #ident = {#value}
-                },
+    /// Renders the body of a `try` block, in which `?` returns the error to the `catch` block.
+    pub fn try_render<E>(render: impl FnOnce() -> Result<Html, E>) -> Result<Html, E> { render() }
+
+    #[function_component]
+    fn Memo<D: PartialEq + 'static>(props: &MemoProps<D>) -> Html {
+        let MemoProps { deps, render } = props;
+        render(deps)
+    }
+
+    /// The `oninput` handler of `bind:value`.
+    pub fn bind_value<T: From<String> + 'static>(
+        handle: &UseStateHandle<T>,
+    ) -> Callback<InputEvent> {
+        let handle = handle.clone();
+        Callback::from(move |event: InputEvent| {
+            let value = if let Some(input) = event.target_dyn_into::<HtmlInputElement>() {
+                input.value()
+            } else if let Some(textarea) = event.target_dyn_into::<HtmlTextAreaElement>() {
+                textarea.value()
+            } else if let Some(select) = event.target_dyn_into::<HtmlSelectElement>() {
+                select.value()
+            } else {
+                return;
+            };
+            handle.set(value.into());
+        })
+    }
+
+    /// Whether an option of `options from` is the current value of the `select`.
+    pub fn is_selected(current: &impl ToString, value: &impl ToString) -> bool {
+        current.to_string() == value.to_string()
+    }
+
+    /// Calls `prevent_default` for the `.prevent` modifier of an event handler.
+    pub fn prevent_default(event: &impl AsRef<web_sys::Event>) { event.as_ref().prevent_default(); }
+
+    /// Calls `stop_propagation` for the `.stop` modifier of an event handler.
+    pub fn stop_propagation(event: &impl AsRef<web_sys::Event>) {
+        event.as_ref().stop_propagation();
+    }
+
+    /// Adds the handler of an `@event.passive` argument to an element as a passive listener.
+    pub fn add_passive_listener<E: JsCast + 'static>(
+        node: &mut Html,
+        event: &'static str,
+        callback: Option<Callback<E>>,
+    ) {
+        if let (VNode::VTag(tag), Some(callback)) = (node, callback) {
+            let callback = callback.reform(|event: web_sys::Event| event.unchecked_into());
+            tag.add_listener(Rc::new(PassiveListener { event, callback }));
+        }
+    }
+
+    struct PassiveListener {
+        event:    &'static str,
+        callback: Callback<web_sys::Event>,
+    }
+
+    impl Listener for PassiveListener {
+        fn kind(&self) -> ListenerKind { ListenerKind::other(self.event.into()) }
+
+        fn handle(&self, event: web_sys::Event) { self.callback.emit(event); }
+
+        fn passive(&self) -> bool { true }
+    }
+
+    /// Whether `event` is for `key` with all of `modifiers` pressed,
+    /// comparing characters case-insensitively so that `"k" with shift` matches `K`.
+    pub fn key_matches(event: &KeyboardEvent, key: &str, modifiers: &[&str]) -> bool {
+        let pressed = event.key();
+        let key_matches = if key.chars().count() == 1 {
+            pressed.to_lowercase() == key.to_lowercase()
+        } else {
+            pressed == key
+        };
+        key_matches
+            && modifiers.iter().all(|&modifier| match modifier {
+                "ctrl" => event.ctrl_key(),
+                "shift" => event.shift_key(),
+                "alt" => event.alt_key(),
+                "meta" => event.meta_key(),
+                _ => false,
             })
-            .collect(),
-        ast::NodeArgs::Rest { eq, arg } => quote_spanned! { eq.span =>
-            ..#arg
-        },
-    })
+    }
+
+    /// The `onchange` handler of `bind:checked`.
+    pub fn bind_checked(handle: &UseStateHandle<bool>) -> Callback<Event> {
+        let handle = handle.clone();
+        Callback::from(move |event: Event| {
+            if let Some(input) = event.target_dyn_into::<HtmlInputElement>() {
+                handle.set(input.checked());
+            }
+        })
+    }
+
+    /// The label of a `field` in a `form` block.
+    pub fn form_label<S: crate::form::Form>(_: &S, field: &str) -> &'static str { S::label(field) }
+
+    /// The HTML constraints of a `field` in a `form` block.
+    pub fn form_constraints<S: crate::form::Form>(_: &S, field: &str) -> crate::form::Constraints {
+        S::constraints(field)
+    }
+
+    /// The validation errors of a `field` in a `form` block.
+    pub fn form_errors(errors: &impl crate::form::FormErrors, field: &str) -> Html {
+        errors
+            .field_errors(field)
+            .into_iter()
+            .map(|message| yew::html! { <span class="form-error">{ message }</span> })
+            .collect()
+    }
+
+    /// The input type of a `field` in a `form` block.
+    pub fn form_input_type<V: crate::form::FormValue>(_: &V) -> &'static str { V::INPUT_TYPE }
+
+    /// The `oninput` handler of a `field` in a `form` block, which updates the field with `set`.
+    pub fn form_oninput<S: Clone + 'static, V: crate::form::FormValue>(
+        handle: &UseStateHandle<S>,
+        set: impl Fn(&mut S, V) + 'static,
+    ) -> Callback<InputEvent> {
+        let handle = handle.clone();
+        Callback::from(move |event: InputEvent| {
+            let Some(input) = event.target_dyn_into::<HtmlInputElement>() else { return };
+            if let Some(value) = V::from_input(&input.value(), input.checked()) {
+                let mut form = (*handle).clone();
+                set(&mut form, value);
+                handle.set(form);
+            }
+        })
+    }
+
+    /// Serializes the contents of a `json_script` element.
+    ///
+    /// `<`, `>` and `&` are escaped as JSON unicode escapes,
+    /// so that the value cannot close the script element or start a comment.
+    /// A value that cannot be serialized is reported as a warning and rendered as `null`.
+    #[cfg(feature = "json")]
+    pub fn json_script(value: &impl serde::Serialize) -> Html {
+        let json = serde_json::to_string(value).unwrap_or_else(|err| {
+            warn(&format!("defy: the value of a `json_script` cannot be serialized: {err}"));
+            "null".to_string()
+        });
+        let json = json.replace('<', "\\u003c").replace('>', "\\u003e").replace('&', "\\u0026");
+        Html::from_html_unchecked(json.into())
+    }
+
+    /// Renders the component of an `island` statement
+    /// inside a `div` with the attributes read by [`island::hydrate`](crate::island).
+    /// Props that cannot be serialized are reported as a warning,
+    /// and the component is rendered without the markers.
+    #[cfg(feature = "json")]
+    pub fn island<C>(props: C::Properties) -> Html
+    where
+        C: yew::html::BaseComponent,
+        C::Properties: serde::Serialize,
+    {
+        let json = serde_json::to_string(&props);
+        let component: Html = VChild::<C>::new(props, None).into();
+        let json = match json {
+            Ok(json) => json,
+            Err(err) => {
+                let name = crate::island::name::<C>();
+                warn(&format!(
+                    "defy: the props of the island `{name}` cannot be serialized: {err}"
+                ));
+                return component;
+            }
+        };
+        html! {
+            <div data-defy-island={crate::island::name::<C>()} data-defy-props={json}>
+                { component }
+            </div>
+        }
+    }
+
+    /// Renders the start tag of an element in `@xml` mode,
+    /// or an empty-element tag if `empty` is true.
+    pub fn xml_tag(name: &'static str, attrs: &[(&'static str, String)], empty: bool) -> Html {
+        let mut tag = format!("<{name}");
+        for (attr, value) in attrs {
+            tag.push_str(&format!(" {attr}=\"{}\"", xml_escape(value)));
+        }
+        tag.push_str(if empty { "/>" } else { ">" });
+        Html::from_html_unchecked(tag.into())
+    }
+
+    /// Renders the end tag of an element in `@xml` mode.
+    pub fn xml_close(name: &'static str) -> Html {
+        Html::from_html_unchecked(format!("</{name}>").into())
+    }
+
+    fn xml_escape(value: &str) -> String {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&apos;")
+    }
+
+    /// Prefixes the path of a `route!(...)` attribute with the route base,
+    /// which is read from the `<base href>` element once on wasm,
+    /// or set with `ssr::set_route_base` on the server.
+    pub fn route_href(path: String) -> AttrValue {
+        let base = crate::ssr::ROUTE_BASE.get_or_init(document_route_base);
+        if base.is_empty() {
+            path.into()
+        } else {
+            format!("{base}{path}").into()
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn document_route_base() -> String {
+        let base = web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.query_selector("base[href]").ok().flatten())
+            .and_then(|base| base.get_attribute("href"));
+        base.map_or_else(String::new, |base| crate::ssr::route_base_path(&base))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn document_route_base() -> String { String::new() }
+
+    /// Reports a `for` loop whose items have no `key`, in debug builds.
+    pub fn warn_unkeyed_list(loc: &str) {
+        let message = format!(
+            "defy: the items of the `for` loop at {loc} have no `key`, so yew cannot reuse their \
+             DOM nodes when the list changes; add `key = ...` to the root element of the loop body"
+        );
+        warn(&message);
+    }
+
+    /// Prints a warning to the browser console, or stderr during server-side rendering.
+    fn warn(message: &str) {
+        #[cfg(target_arch = "wasm32")]
+        web_sys::console::warn_1(&message.into());
+        #[cfg(not(target_arch = "wasm32"))]
+        eprintln!("{message}");
+    }
 }
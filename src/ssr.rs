@@ -0,0 +1,276 @@
+//! Server-side rendering helpers.
+//!
+//! With the `ssr` feature, `render_stream` renders a template as a stream of HTML chunks.
+//! [`HtmlResponse`] returns a rendered page from the handlers of axum or actix-web.
+//! [`set_route_base`] sets the prefix of `route!(...)` links rendered on the server.
+//!
+//! [`pretty_print`] post-processes HTML rendered by yew's server renderer:
+//!
+//! ```
+//! let html = r#"<div class="card"><h1>Title</h1><p>Hello, <b>world</b></p><br></div>"#;
+//! assert_eq!(
+//!     defy::ssr::pretty_print(html),
+//!     r#"<div class="card">
+//!   <h1>Title</h1>
+//!   <p>
+//!     Hello,
+//!     <b>world</b>
+//!   </p>
+//!   <br>
+//! </div>
+//! "#,
+//! );
+//! ```
+
+use std::sync::OnceLock;
+
+#[cfg(feature = "ssr")]
+pub use stream::render_stream;
+
+/// The path prefix of `route!(...)` links, without a trailing `/`.
+pub(crate) static ROUTE_BASE: OnceLock<String> = OnceLock::new();
+
+/// Sets the path prefix of `route!(...)` links rendered on the server,
+/// which should be the `href` of the `<base>` element of the page, e.g. `/app/`.
+///
+/// # Panics
+/// Panics if the prefix was already set, or a `route!(...)` link was already rendered.
+pub fn set_route_base(base: &str) {
+    let base = route_base_path(base);
+    assert!(ROUTE_BASE.set(base).is_ok(), "the route base is already set");
+}
+
+/// The path of a `<base href>` value without the origin and the trailing `/`.
+pub(crate) fn route_base_path(base: &str) -> String {
+    let base = match base.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("", |pos| &rest[pos..]),
+        None => base,
+    };
+    base.trim_end_matches('/').to_string()
+}
+
+/// The `Content-Type` of rendered HTML pages.
+pub const HTML_CONTENT_TYPE: &str = "text/html; charset=utf-8";
+
+/// A rendered HTML page returned by the handlers of web frameworks
+/// with the `Content-Type` [`HTML_CONTENT_TYPE`].
+///
+/// With the `axum` feature, it implements `axum::response::IntoResponse`:
+///
+/// ```
+/// # /*
+/// async fn page() -> defy::ssr::HtmlResponse {
+///     defy::ssr::HtmlResponse(yew::ServerRenderer::<App>::new().render().await)
+/// }
+/// # */
+/// ```
+///
+/// With the `actix-web` feature, it implements `actix_web::Responder` in the same way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlResponse(pub String);
+
+impl From<String> for HtmlResponse {
+    fn from(html: String) -> Self { Self(html) }
+}
+
+#[cfg(feature = "axum")]
+impl axum::response::IntoResponse for HtmlResponse {
+    fn into_response(self) -> axum::response::Response {
+        ([(axum::http::header::CONTENT_TYPE, HTML_CONTENT_TYPE)], self.0).into_response()
+    }
+}
+
+#[cfg(feature = "actix-web")]
+impl actix_web::Responder for HtmlResponse {
+    type Body = String;
+
+    fn respond_to(self, _: &actix_web::HttpRequest) -> actix_web::HttpResponse<String> {
+        actix_web::HttpResponse::Ok().content_type(HTML_CONTENT_TYPE).message_body(self.0).unwrap()
+    }
+}
+
+#[cfg(feature = "ssr")]
+mod stream {
+    use std::rc::Rc;
+
+    use futures::Stream;
+    use yew::{function_component, Html, Properties, ServerRenderer};
+
+    /// Renders the `Html` returned by `render` with yew's server renderer
+    /// as a stream of HTML chunks, e.g. for the streaming body of an HTTP response.
+    ///
+    /// A chunk is emitted whenever rendering waits for an `await` block,
+    /// so the content before the block, such as the shell of the page,
+    /// is sent before its future resolves.
+    /// The output is hydratable, i.e. it contains the markers of yew components.
+    pub fn render_stream(
+        render: impl Fn() -> Html + Send + 'static,
+    ) -> impl Stream<Item = String> + Send {
+        ServerRenderer::<Root>::with_props(move || RootProps { render: Rc::new(render) })
+            .render_stream()
+    }
+
+    #[derive(Properties)]
+    struct RootProps {
+        render: Rc<dyn Fn() -> Html>,
+    }
+
+    // the root component is never re-rendered
+    impl PartialEq for RootProps {
+        fn eq(&self, _: &Self) -> bool { false }
+    }
+
+    #[function_component]
+    fn Root(props: &RootProps) -> Html {
+        let RootProps { render } = props;
+        render()
+    }
+}
+
+/// Elements whose contents are not HTML, or whose whitespace is significant.
+const RAW_ELEMENTS: &[&str] = &["pre", "textarea", "script", "style"];
+
+/// Elements without closing tags.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Formats HTML with each element on its own line, indented by two spaces per level.
+///
+/// Elements containing only text are kept on one line,
+/// and the contents of `pre`, `textarea`, `script` and `style` elements are unchanged.
+/// Since whitespace between elements is added and whitespace around text is removed,
+/// the output is intended for debugging and review, e.g. of emails,
+/// rather than for serving.
+pub fn pretty_print(html: &str) -> String {
+    let tokens = tokenize(html);
+    let mut output = String::new();
+    let mut depth = 0_usize;
+    let mut index = 0;
+    while index < tokens.len() {
+        let line = match tokens[index] {
+            Token::Open { name, raw } => match (tokens.get(index + 1), tokens.get(index + 2)) {
+                (Some(Token::Close { name: close, raw: close_raw }), _) if *close == name => {
+                    index += 1;
+                    format!("{raw}{close_raw}")
+                }
+                (Some(Token::Text(text)), Some(Token::Close { name: close, raw: close_raw }))
+                    if *close == name =>
+                {
+                    index += 2;
+                    format!("{raw}{text}{close_raw}")
+                }
+                _ if VOID_ELEMENTS.contains(&name) => raw.to_string(),
+                _ => {
+                    push_line(&mut output, depth, raw);
+                    depth += 1;
+                    index += 1;
+                    continue;
+                }
+            },
+            Token::Close { name: _, raw } => {
+                depth = depth.saturating_sub(1);
+                raw.to_string()
+            }
+            Token::Other(raw) | Token::Text(raw) => raw.to_string(),
+        };
+        push_line(&mut output, depth, &line);
+        index += 1;
+    }
+    output
+}
+
+fn push_line(output: &mut String, depth: usize, line: &str) {
+    output.push_str(&"  ".repeat(depth));
+    output.push_str(line);
+    output.push('\n');
+}
+
+enum Token<'a> {
+    /// An opening tag with its element name.
+    Open {
+        name: &'a str,
+        raw:  &'a str,
+    },
+    Close {
+        name: &'a str,
+        raw:  &'a str,
+    },
+    /// Text without surrounding whitespace, never empty.
+    Text(&'a str),
+    /// A comment, a doctype, a self-closing tag or a raw text element with its contents.
+    Other(&'a str),
+}
+
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            push_text(&mut tokens, rest);
+            break;
+        };
+        push_text(&mut tokens, &rest[..start]);
+        rest = &rest[start..];
+
+        let end = if rest.starts_with("<!--") {
+            rest.find("-->").map_or(rest.len(), |end| end + 3)
+        } else {
+            tag_end(rest)
+        };
+        let raw = &rest[..end];
+        rest = &rest[end..];
+
+        if raw.starts_with("<!") || raw.ends_with("/>") {
+            tokens.push(Token::Other(raw));
+        } else if let Some(name) = raw.strip_prefix("</") {
+            tokens.push(Token::Close { name: tag_name(name), raw });
+        } else {
+            let name = tag_name(&raw[1..]);
+            if RAW_ELEMENTS.iter().any(|element| element.eq_ignore_ascii_case(name)) {
+                // keep the contents and the closing tag unchanged
+                let close = find_ignore_case(rest, &format!("</{name}"));
+                let end = close.map_or(rest.len(), |close| close + tag_end(&rest[close..]));
+                let len = raw.len() + end;
+                let start = html.len() - rest.len() - raw.len();
+                tokens.push(Token::Other(&html[start..start + len]));
+                rest = &rest[end..];
+            } else {
+                tokens.push(Token::Open { name, raw });
+            }
+        }
+    }
+    tokens
+}
+
+fn push_text<'a>(tokens: &mut Vec<Token<'a>>, text: &'a str) {
+    let text = text.trim();
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+}
+
+/// The element name at the start of a tag without `<` or `</`.
+fn tag_name(tag: &str) -> &str {
+    let end = tag.find(|ch: char| ch.is_ascii_whitespace() || ch == '>' || ch == '/');
+    &tag[..end.unwrap_or(tag.len())]
+}
+
+/// The length of the tag at the start of `html`, ignoring `>` in quoted attribute values.
+fn tag_end(html: &str) -> usize {
+    let mut quote = None;
+    for (index, ch) in html.char_indices() {
+        match (quote, ch) {
+            (None, '"' | '\'') => quote = Some(ch),
+            (Some(open), _) if open == ch => quote = None,
+            (None, '>') => return index + 1,
+            _ => {}
+        }
+    }
+    html.len()
+}
+
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack.to_ascii_lowercase().find(&needle.to_ascii_lowercase())
+}
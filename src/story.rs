@@ -0,0 +1,79 @@
+//! Component previews, like a storybook built with yew.
+//!
+//! `#[stories]` on an inline module collects its functions marked with `#[story]`
+//! and adds a `Stories` component that lists them and renders the selected one:
+//!
+//! ```
+//! #[defy::stories]
+//! mod stories {
+//!     /// Named `Primary button` after the function.
+//!     #[story]
+//!     fn primary_button() -> yew::Html {
+//!         defy::defy! { button(class = "primary") { + "Save"; } }
+//!     }
+//!
+//!     #[story("Disabled button")]
+//!     fn disabled() -> yew::Html {
+//!         defy::defy! { button(disabled = true) { + "Save"; } }
+//!     }
+//! }
+//!
+//! assert_eq!(stories::STORIES[0].name, "Primary button");
+//! # let _ = yew::html! { <stories::Stories /> };
+//! ```
+//!
+//! The module also gets a `STORIES` constant with the list of [`Story`] values,
+//! e.g. for rendering the stories in snapshot tests or a custom [`Gallery`] layout.
+//! Since macros cannot discover items in other modules,
+//! all stories to be listed in a gallery must be defined in the same module,
+//! or the `STORIES` of several modules can be concatenated for a custom gallery.
+//!
+//! Serve the `Stories` component as the root of a separate entry point or route,
+//! so that the stories are excluded from the production app.
+
+pub use defy_macros::{stories, story};
+use yew::{function_component, html, use_state, Callback, Html, Properties};
+
+/// A named preview of a component, defined with `#[story]`.
+#[derive(Debug, Clone, Copy)]
+pub struct Story {
+    /// The name shown in the gallery,
+    /// which defaults to the name of the function in sentence case.
+    pub name:   &'static str,
+    /// Renders the story.
+    pub render: fn() -> Html,
+}
+
+// stories are identified by name, since function pointers cannot be compared reliably
+impl PartialEq for Story {
+    fn eq(&self, other: &Self) -> bool { self.name == other.name }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct GalleryProps {
+    pub stories: &'static [Story],
+}
+
+/// Lists the names of `stories` in a `nav` and renders the selected story in a `main` element.
+///
+/// The button of the selected story has `aria-current="page"`.
+#[function_component]
+pub fn Gallery(props: &GalleryProps) -> Html {
+    let selected = use_state(|| 0);
+    let links = props.stories.iter().enumerate().map(|(index, story)| {
+        let onclick = {
+            let selected = selected.clone();
+            Callback::from(move |_| selected.set(index))
+        };
+        let current = (index == *selected).then_some("page");
+        html! {
+            <li><button type="button" aria-current={current} {onclick}>{ story.name }</button></li>
+        }
+    });
+    html! {
+        <div class="defy-gallery">
+            <nav><ul>{ for links }</ul></nav>
+            <main>{ props.stories.get(*selected).map(|story| (story.render)()) }</main>
+        </div>
+    }
+}
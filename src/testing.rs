@@ -0,0 +1,117 @@
+//! Support for testing templates.
+//!
+//! ```
+//! use defy::testing;
+//!
+//! let expansion = testing::expand_to_string(quote::quote! { div { + "hello"; } });
+//! assert!(expansion.contains("::yew::html!"));
+//!
+//! testing::assert_html_eq(
+//!     "<div>\n    <span>hello  world</span>\n</div><!--<[app::Test]>-->",
+//!     "<div><span>hello world</span></div>",
+//! );
+//! ```
+//!
+//! [`assert_defy_renders!`](crate::assert_defy_renders) renders a template with yew's server renderer:
+//!
+//! ```
+//! let name = "world";
+//! defy::assert_defy_renders!({ p { + "Hello, "; b { + name; } } }, "<p>Hello,<b>world</b></p>");
+//! ```
+
+use proc_macro2::TokenStream;
+use yew::{Html, LocalServerRenderer};
+
+/// Expands the contents of a `defy!` invocation to formatted Rust code.
+///
+/// Errors are included in the output as `compile_error!` invocations,
+/// and lint warnings as the deprecated items that report them.
+pub fn expand_to_string(tokens: TokenStream) -> String {
+    defy_codegen::pretty_print(&defy_codegen::expand(tokens))
+}
+
+/// Canonicalizes HTML so that differences in formatting do not affect comparison.
+///
+/// Comments are removed, including the component markers emitted by yew's server renderer.
+/// Whitespace around text is removed, and other runs of whitespace are collapsed to a single space.
+pub fn canonicalize(html: &str) -> String {
+    let mut without_comments = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find("<!--") {
+        without_comments.push_str(&rest[..start]);
+        rest = rest[start..].find("-->").map_or("", |end| &rest[start + end + 3..]);
+    }
+    without_comments.push_str(rest);
+
+    let mut output = String::new();
+    for (index, text) in without_comments.split('<').enumerate() {
+        if index > 0 {
+            output.push('<');
+        }
+        let (tag, text) = match text.find('>') {
+            Some(end) if index > 0 => text.split_at(end + 1),
+            _ => ("", text),
+        };
+        output.push_str(tag);
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        output.push_str(&text);
+    }
+    output
+}
+
+/// Asserts that two HTML strings are equal after [canonicalization](canonicalize).
+#[track_caller]
+pub fn assert_html_eq(actual: &str, expected: &str) {
+    assert_eq!(canonicalize(actual), canonicalize(expected), "rendered HTML differs");
+}
+
+/// Renders HTML to a string with yew's server renderer.
+///
+/// This starts a single-threaded tokio runtime,
+/// so it must not be called from within an async runtime, e.g. in `#[tokio::test]`.
+pub fn render(html: Html) -> String {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .expect("cannot start a tokio runtime for rendering");
+    let renderer =
+        LocalServerRenderer::<Render>::with_props(RenderProps { html }).hydratable(false);
+    tokio::task::LocalSet::new().block_on(&runtime, renderer.render())
+}
+
+#[derive(PartialEq, yew::Properties)]
+struct RenderProps {
+    html: Html,
+}
+
+#[yew::function_component]
+fn Render(props: &RenderProps) -> Html {
+    let RenderProps { html } = props;
+    html.clone()
+}
+
+/// Renders a `defy!` template and compares the HTML with an expected string
+/// after [canonicalization](canonicalize).
+///
+/// Local variables are in scope inside the template.
+/// Without an expected string, the canonicalized HTML is compared with an
+/// [insta](https://docs.rs/insta) snapshot instead,
+/// which requires `insta` to be a dependency of the calling crate.
+///
+/// ```ignore
+/// assert_defy_renders!({ ul { for item in &items { li { + item; } } } }, "<ul><li>a</li></ul>");
+/// assert_defy_renders!({ ul { for item in &items { li { + item; } } } });
+/// ```
+#[macro_export]
+macro_rules! assert_defy_renders {
+    ({ $($template:tt)* }, $expected:expr $(,)?) => {
+        $crate::testing::assert_html_eq(
+            &$crate::testing::render($crate::defy! { $($template)* }),
+            $expected,
+        )
+    };
+    ({ $($template:tt)* } $(,)?) => {
+        ::insta::assert_snapshot!($crate::testing::canonicalize(&$crate::testing::render(
+            $crate::defy! { $($template)* }
+        )))
+    };
+}
@@ -2,36 +2,1196 @@
 
 use quote::quote;
 
-use crate::ast;
+use crate::testing;
 
 #[test]
-fn test_if_else() {
-    let _: ast::Input = syn::parse2(quote! {
-        if foo {
-            + "";
-        } else {
-            + "";
+fn test_expand_to_string() {
+    let expansion = testing::expand_to_string(quote! { @scope "card" div(class = "title"); });
+    assert!(expansion.starts_with("fn expansion() {"));
+    assert!(expansion.contains("class = {"));
+
+    let error = testing::expand_to_string(quote! { "text"; });
+    assert!(error.contains("compile_error!"));
+}
+
+#[test]
+fn test_canonicalize() {
+    assert_eq!(
+        testing::canonicalize(
+            "<!--<[app::Test]>-->\n<p class=\"a  b\">\n  Hello,\n  <b>world</b>\n</p>"
+        ),
+        "<p class=\"a  b\">Hello,<b>world</b></p>",
+    );
+}
+
+#[test]
+fn test_pretty_print() {
+    let html = testing::render(crate::defy! {
+        ul(class = "a>b") {
+            li { + "one"; }
+            li {}
+        }
+        pre { verbatim "  x\n<y>"; }
+        input(value = "v");
+    });
+    assert_eq!(
+        crate::ssr::pretty_print(&html),
+        "<ul class=\"a&gt;b\">\n  <li>one</li>\n  <li></li>\n</ul>\n<pre>  \
+         x\n&lt;y&gt;</pre>\n<input value=\"v\">\n",
+    );
+}
+
+#[test]
+fn test_assert_defy_renders() {
+    let items = ["a", "b"];
+    crate::assert_defy_renders!(
+        {
+            ul {
+                for item in items {
+                    li(class = "item") { + item; }
+                }
+            }
+        },
+        r#"
+            <ul>
+                <li class="item">a</li>
+                <li class="item">b</li>
+            </ul>
+        "#,
+    );
+}
+
+#[test]
+fn test_memo() {
+    let items = vec!["a", "b"];
+    let title = "List";
+    crate::assert_defy_renders!(
+        {
+            memo(items, heading = title.to_uppercase()) {
+                h2 { + heading; }
+                for item in items.iter() {
+                    li { + item; }
+                }
+            }
+        },
+        "<h2>LIST</h2><li>a</li><li>b</li>",
+    );
+}
+
+#[test]
+fn test_once() {
+    crate::assert_defy_renders!(
+        {
+            once {
+                p { + "static"; }
+            }
+        },
+        "<p>static</p>",
+    );
+}
+
+#[test]
+fn test_defer() {
+    crate::assert_defy_renders!(
+        {
+            defer { p { + "content"; } } placeholder { p { + "loading"; } }
+            defer { p { + "content"; } }
+        },
+        "<p>loading</p>",
+    );
+}
+
+#[test]
+fn test_await() {
+    let base = 40;
+    crate::assert_defy_renders!(
+        {
+            await async move { base + 2 } as answer { p { + answer; } } pending { + "loading"; }
+        },
+        "<p>42</p>",
+    );
+}
+
+#[test]
+fn test_await_deps() {
+    let id = 2;
+    crate::assert_defy_renders!(
+        {
+            await(id) async move { id * 10 } as user { p { + user; } }
+            await(double = id * 2) async move { double } as double { p { + double; } }
+        },
+        "<p>20</p><p>4</p>",
+    );
+}
+
+#[test]
+fn test_lazy() {
+    use std::sync::Mutex;
+
+    use yew::prelude::*;
+
+    static LOADED: Mutex<Vec<&str>> = Mutex::new(Vec::new());
+
+    #[derive(PartialEq, Properties)]
+    struct PanelProps {
+        title: AttrValue,
+    }
+
+    #[function_component]
+    fn Panel(props: &PanelProps) -> Html {
+        crate::defy! { section { + &props.title; } }
+    }
+
+    crate::lazy::set_loader(|name| {
+        LOADED.lock().unwrap().push(name);
+        Box::pin(async {})
+    });
+    let title = AttrValue::from("Settings");
+    crate::assert_defy_renders!(
+        {
+            lazy Panel(title = title.clone()) { fallback { + "loading"; } }
+            lazy Panel(title = "Help");
+        },
+        "<section>Settings</section><section>Help</section>",
+    );
+    assert!(LOADED.lock().unwrap().contains(&crate::lazy::name::<Panel>()));
+}
+
+#[test]
+fn test_try() {
+    let (input, invalid) = ("21", "x");
+    crate::assert_defy_renders!(
+        {
+            try {
+                let count = input.parse::<u32>()?;
+                + count * 2;
+            } catch err {
+                + err.to_string();
+            }
+            try {
+                + invalid.parse::<u32>()?;
+            } catch err: std::num::ParseIntError {
+                p { + err.to_string(); }
+            }
+            try { + invalid.parse::<u32>()?; }
+        },
+        "42<p>invalid digit found in string</p>",
+    );
+}
+
+#[test]
+fn test_let_type() {
+    let (input, words) = ("21", "a b");
+    crate::assert_defy_renders!(
+        {
+            let words: Vec<_> = words.split(' ').collect();
+            try {
+                let count: u32 = input.parse()?;
+                + count * 2;
+            }
+            + words.len();
+        },
+        "422",
+    );
+}
+
+#[test]
+fn test_for_else() {
+    let (items, empty) = (["a", "b"], Vec::<&str>::new());
+    crate::assert_defy_renders!(
+        {
+            for item in items { li { + item; } } else { + "none"; }
+            for item in empty { li { + item; } } else { p { + "none"; } }
+        },
+        "<li>a</li><li>b</li><p>none</p>",
+    );
+}
+
+#[test]
+fn test_loop_control() {
+    crate::assert_defy_renders!(
+        {
+            for i in 0..10 {
+                if i % 2 == 0 { continue; }
+                if i > 5 { break; }
+                li { + i; }
+            }
+            for i in 0..3 {
+                match i % 2 {
+                    0 => { continue; }
+                    _ => { continue; }
+                }
+            } else {
+                + "none";
+            }
+            for i in 0..5 {
+                with i * 2 as j {
+                    if j > 4 { break; }
+                    li { + j; }
+                }
+            }
+        },
+        "<li>1</li><li>3</li><li>5</li>none<li>0</li><li>2</li><li>4</li>",
+    );
+}
+
+#[test]
+fn test_repeat() {
+    let n = 2;
+    crate::assert_defy_renders!(
+        {
+            repeat 3 { br; }
+            repeat n + 1 as i { + i; }
+        },
+        "<br><br><br>012",
+    );
+}
+
+#[test]
+fn test_with() {
+    let words = ["a", "bc"];
+    crate::assert_defy_renders!(
+        {
+            br;
+            with words.join(" ") as text {
+                p { + text; }
+            }
+        },
+        "<br><p>a bc</p>",
+    );
+}
+
+#[test]
+fn test_maybe() {
+    for (avatar, expected) in [(Some("a.png"), r#"<img src="a.png">"#), (None, "<span>?</span>")] {
+        crate::assert_defy_renders!(
+            {
+                maybe avatar as url { img(src = url); } else { span { + "?"; } }
+                maybe avatar as url { + url; }
+            },
+            &format!("{expected}{}", avatar.unwrap_or_default()),
+        );
+    }
+}
+
+#[test]
+fn test_table() {
+    let rows = [("alice", 30), ("bob", 25)];
+    crate::assert_defy_renders!(
+        {
+            table(class = "people") for (name, age) in rows {
+                column "Name" { b { + name; } }
+                column "Age" { + age; }
+            }
+        },
+        r#"<table class="people">
+            <thead><tr><th scope="col">Name</th><th scope="col">Age</th></tr></thead>
+            <tbody>
+                <tr><td><b>alice</b></td><td>30</td></tr>
+                <tr><td><b>bob</b></td><td>25</td></tr>
+            </tbody>
+        </table>"#,
+    );
+}
+
+#[test]
+fn test_options() {
+    struct Country {
+        code: &'static str,
+        name: &'static str,
+    }
+
+    #[yew::function_component]
+    fn CountrySelect() -> yew::Html {
+        let countries =
+            [Country { code: "fr", name: "France" }, Country { code: "jp", name: "Japan" }];
+        let selected = yew::use_state(|| String::from("jp"));
+        crate::defy! {
+            select(bind:value = selected) {
+                options from countries { |c| (value = c.code) { + c.name; } }
+            }
+        }
+    }
+
+    crate::assert_defy_renders!(
+        {
+            CountrySelect;
+            select {
+                options from ["a", "b"] { |n| (value = n); }
+            }
+        },
+        r#"<select value="jp">
+            <option value="fr">France</option>
+            <option value="jp" selected="selected">Japan</option>
+        </select>
+        <select><option value="a"></option><option value="b"></option></select>"#,
+    );
+}
+
+#[test]
+fn test_picture() {
+    crate::assert_defy_renders!(
+        {
+            picture "img/hero.jpg"(
+                widths = [480, 960],
+                formats = ["avif"],
+                sizes = "100vw",
+                alt = "Hero",
+            );
+        },
+        r#"<picture>
+            <source type="image/avif" srcset="img/hero-480w.avif 480w, img/hero-960w.avif 960w" sizes="100vw">
+            <img src="img/hero.jpg" srcset="img/hero-480w.jpg 480w, img/hero-960w.jpg 960w" sizes="100vw" alt="Hero">
+        </picture>"#,
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn test_json_script() {
+    let data = std::collections::BTreeMap::from([("title", "</script><!-- & more")]);
+    crate::assert_defy_renders!(
+        {
+            json_script(id = "page-data") = &data;
+        },
+        r#"<script type="application/json" id="page-data">{"title":"\u003c/script\u003e\u003c!-- \u0026 more"}</script>"#,
+    );
+
+    // JSON object keys must be strings
+    let points = std::collections::BTreeMap::from([((1, 2), "a")]);
+    crate::assert_defy_renders!(
+        {
+            json_script(id = "points") = &points;
+        },
+        r#"<script type="application/json" id="points">null</script>"#,
+    );
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn test_island() {
+    #[derive(PartialEq, yew::Properties, serde::Serialize)]
+    struct CounterProps {
+        start: u32,
+    }
+
+    #[yew::function_component]
+    fn Counter(props: &CounterProps) -> yew::Html {
+        yew::html! { <button>{ props.start }</button> }
+    }
+
+    let name = crate::island::name::<Counter>();
+    crate::assert_defy_renders!(
+        {
+            island Counter(start = 1);
+            island Counter = CounterProps { start: 2 };
+        },
+        &format!(
+            r#"<div data-defy-island="{name}" data-defy-props="{{&quot;start&quot;:1}}"><button>1</button></div>
+               <div data-defy-island="{name}" data-defy-props="{{&quot;start&quot;:2}}"><button>2</button></div>"#
+        ),
+    );
+
+    #[derive(PartialEq, yew::Properties, serde::Serialize)]
+    struct ChartProps {
+        points: std::collections::BTreeMap<(u32, u32), u32>,
+    }
+
+    #[yew::function_component]
+    fn Chart(props: &ChartProps) -> yew::Html {
+        yew::html! { <p>{ props.points.len() }</p> }
+    }
+
+    // JSON object keys must be strings, so the chart is rendered without hydration markers
+    let points = std::collections::BTreeMap::from([((1, 2), 3)]);
+    crate::assert_defy_renders!({ island Chart(points = points); }, "<p>1</p>");
+}
+
+#[test]
+fn test_generic_component() {
+    #[derive(PartialEq, yew::Properties)]
+    struct ListProps<T: PartialEq> {
+        items: Vec<T>,
+    }
+
+    #[yew::function_component]
+    fn List<T: PartialEq + std::fmt::Display + 'static, const N: usize>(
+        props: &ListProps<T>,
+    ) -> yew::Html {
+        crate::defy! {
+            for item in props.items.iter().take(N) { + item.to_string(); }
+        }
+    }
+
+    crate::assert_defy_renders!(
+        {
+            List::<Box<u8>, 2>(items = vec![Box::new(1), Box::new(2), Box::new(3)]);
+            List<String, { usize::MAX / usize::MAX }>(items = vec!["a".to_string(), "b".to_string()]);
+        },
+        "12a",
+    );
+}
+
+#[test]
+fn test_attr_values() {
+    let id = yew::AttrValue::from("a");
+    let title = String::from("b");
+    let lang: Option<&str> = None;
+    crate::assert_defy_renders!(
+        {
+            p(id = &id, title = title, lang = lang, hidden = true);
+        },
+        r#"<p id="a" title="b" hidden="hidden"></p>"#,
+    );
+}
+
+#[test]
+fn test_text() {
+    let html: yew::Html = crate::defy! { b { + "bold"; } };
+    let names = ["a", "b"];
+    crate::assert_defy_renders!(
+        {
+            + "text ";
+            + 1.5;
+            + html;
+            + Some("some");
+            + None::<yew::Html>;
+            + vec![1, 2];
+            + names.iter().map(|name| crate::defy! { i { + name; } });
+        },
+        "text 1.5<b>bold</b>some12<i>a</i><i>b</i>",
+    );
+}
+
+#[test]
+fn test_embed() {
+    let sidebar: yew::Html = crate::defy! { aside { + "Links"; } };
+    let banner: Option<yew::Html> = None;
+    crate::assert_defy_renders!(
+        {
+            embed sidebar;
+            embed banner;
+            embed Some(crate::defy! { br; });
+            embed(src = "movie.swf");
+        },
+        r#"<aside>Links</aside><br><embed src="movie.swf">"#,
+    );
+}
+
+#[test]
+fn test_dyn() {
+    use yew::prelude::*;
+
+    #[derive(PartialEq, Properties)]
+    struct WidgetProps {
+        label: AttrValue,
+    }
+
+    #[function_component]
+    fn Button(props: &WidgetProps) -> Html {
+        crate::defy! { button { + &props.label; } }
+    }
+
+    fn link(props: WidgetProps) -> Html {
+        crate::defy! { a(href = "#") { + props.label; } }
+    }
+
+    let widgets: [fn(WidgetProps) -> Html; 2] = [crate::dynamic::component::<Button>(), link];
+    let boxed: Box<dyn Fn(()) -> Html> = Box::new(|()| crate::defy! { hr; });
+    crate::assert_defy_renders!(
+        {
+            for widget in widgets {
+                dyn widget(props = WidgetProps { label: "Open".into() });
+            }
+            dyn boxed;
+        },
+        r##"<button>Open</button><a href="#">Open</a><hr>"##,
+    );
+}
+
+#[test]
+fn test_defy_iter() {
+    let extra = crate::defy! { li { + "c"; } };
+    let items: Vec<yew::Html> = crate::defy_iter! {
+        let first = "a";
+        li { + first; }
+        #[cfg(any())]
+        li { + "hidden"; }
+        li { + "b"; }
+    }
+    .chain([extra])
+    .collect();
+    assert_eq!(items.len(), 3);
+    crate::assert_defy_renders!({ ul { + items; } }, "<ul><li>a</li><li>b</li><li>c</li></ul>");
+
+    let hidden: Vec<yew::Html> = crate::defy_iter! {
+        #[cfg(any())]
+        br;
+    }
+    .collect();
+    assert!(hidden.is_empty());
+}
+
+#[test]
+fn test_passthrough() {
+    let name = "x";
+    crate::assert_defy_renders!(
+        { div { passthrough { <span class={name}>{ "a" }</span><br /> } passthrough {} } },
+        r#"<div><span class="x">a</span><br></div>"#,
+    );
+}
+
+#[test]
+fn test_raw() {
+    crate::assert_defy_renders!(
+        { div { raw "<b>Hello</b><br>world"; } },
+        "<div><b>Hello</b><br>world</div>",
+    );
+}
+
+#[test]
+fn test_layout() {
+    use crate::layout::Blocks;
+
+    fn base_layout(blocks: Blocks) -> yew::Html {
+        crate::defy! {
+            @blocks blocks
+            header { block header { h1 { + "Site"; } } }
+            main { block content {} }
+        }
+    }
+
+    fn docs_layout(blocks: Blocks) -> yew::Html {
+        crate::defy! {
+            @blocks blocks
+            extend base_layout {
+                block content {
+                    nav { + "Docs"; }
+                    block content {}
+                }
+            }
         }
-    })
-    .unwrap();
+    }
+
+    crate::assert_defy_renders!({ extend base_layout {} }, "<header><h1>Site</h1></header><main></main>");
+    crate::assert_defy_renders!(
+        {
+            extend docs_layout {
+                block header { h1 { + "Page"; } }
+                block content { p { + "Text"; } }
+            }
+        },
+        "<header><h1>Site</h1></header><main><nav>Docs</nav><p>Text</p></main>",
+    );
+}
+
+#[test]
+fn test_document() {
+    assert_eq!(
+        crate::testing::render(crate::defy! {
+            doctype html;
+            html(lang = "en") {
+                #[cfg(all())]
+                head {
+                    meta(charset = "utf-8");
+                    title { + "Not found"; }
+                }
+                body { h1 { + "404"; } }
+            }
+        }),
+        concat!(
+            "<!DOCTYPE html>",
+            r#"<html lang="en"><head><meta charset="utf-8"><title>Not found</title></head>"#,
+            "<body><h1>404</h1></body></html>",
+        ),
+    );
+}
+
+#[test]
+fn test_xml() {
+    let loc = "https://example.com/?a=1&b=2";
+    assert_eq!(
+        crate::testing::render(crate::defy! {
+            @xml
+            xml_decl(version = "1.0", encoding = "UTF-8");
+            urlset(xmlns = "http://www.sitemaps.org/schemas/sitemap/0.9", xmlns:image = "urn:image") {
+                url {
+                    loc { + loc; }
+                    image::image(r#type = 1) { image::loc { + "/a.png"; } }
+                }
+                link { + "/feed"; }
+                br;
+            }
+        }),
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9" xmlns:image="urn:image">"#,
+            r#"<url><loc>https://example.com/?a=1&amp;b=2</loc>"#,
+            r#"<image:image type="1"><image:loc>/a.png</image:loc></image:image></url>"#,
+            r#"<link>/feed</link><br/></urlset>"#,
+        ),
+    );
+}
+
+#[test]
+#[cfg(feature = "ssr")]
+fn test_render_stream() {
+    use futures::StreamExt;
+
+    let stream = crate::ssr::render_stream(|| {
+        crate::defy! {
+            main {
+                h1 { + "Shell"; }
+                await async { 42 } as answer { p { + answer; } }
+            }
+        }
+    });
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let chunks: Vec<String> = runtime.block_on(stream.collect());
+    testing::assert_html_eq(&chunks.concat(), "<main><h1>Shell</h1><p>42</p></main>");
+}
+
+#[test]
+fn test_route_base() {
+    crate::ssr::set_route_base("https://example.com/app/");
+    assert_eq!(crate::__private::route_href("/posts/1".to_string()), "/app/posts/1");
+}
+
+#[test]
+#[cfg(feature = "axum")]
+fn test_html_response_axum() {
+    use axum::response::IntoResponse;
+
+    let response = crate::ssr::HtmlResponse("<p>Hi</p>".to_string()).into_response();
+    assert_eq!(response.headers()["content-type"], crate::ssr::HTML_CONTENT_TYPE);
+    let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let body = runtime.block_on(axum::body::to_bytes(response.into_body(), usize::MAX)).unwrap();
+    assert_eq!(body, "<p>Hi</p>");
+}
+
+#[test]
+#[cfg(feature = "actix-web")]
+fn test_html_response_actix_web() {
+    use actix_web::Responder;
+
+    let request = actix_web::test::TestRequest::default().to_http_request();
+    let response = crate::ssr::HtmlResponse("<p>Hi</p>".to_string()).respond_to(&request);
+    let content_type = response.headers().get("content-type").unwrap();
+    assert_eq!(content_type, crate::ssr::HTML_CONTENT_TYPE);
+    assert_eq!(response.into_body(), "<p>Hi</p>");
+}
+
+#[test]
+fn test_head() {
+    let page_title = "Home";
+    crate::assert_defy_renders!(
+        {
+            document_head {
+                title { +page_title; +" | Defy"; }
+                meta(name = "description", content = "Home page");
+            }
+            head { meta(charset = "utf-8"); }
+        },
+        r#"<head><meta charset="utf-8"></head>"#,
+    );
+}
+
+#[test]
+fn test_csp_nonce() {
+    let nonce = String::from("r4nd0m");
+    crate::assert_defy_renders!(
+        {
+            @csp_nonce nonce
+            + "text";
+            script(src = "/app.js") {}
+            style(nonce = "other") { + "p {}"; }
+            link(rel = "stylesheet", href = "/app.css");
+        },
+        r#"text
+        <script src="/app.js" nonce="r4nd0m"></script>
+        <style nonce="other">p {}</style>
+        <link rel="stylesheet" href="/app.css">"#,
+    );
+}
+
+#[test]
+fn test_og() {
+    let url = "https://example.com/";
+    crate::assert_defy_renders!(
+        {
+            og {
+                title "Defy";
+                type "website";
+                url url;
+            }
+        },
+        r#"<meta property="og:title" content="Defy">
+        <meta name="twitter:title" content="Defy">
+        <meta property="og:type" content="website">
+        <meta property="og:url" content="https://example.com/">
+        <meta name="twitter:card" content="summary">"#,
+    );
+}
+
+#[test]
+fn test_space() {
+    assert_eq!(
+        testing::render(crate::defy! {
+            b { + "bold"; }
+            space;
+            + "text";
+            i { + "a"; space; + "b"; }
+        }),
+        "<b>bold</b> text<i>a b</i>",
+    );
+}
+
+#[test]
+fn test_verbatim() {
+    assert_eq!(
+        testing::render(crate::defy! {
+            pre {
+                verbatim r#"
+                    +---+
+                    | a |
+                      b
+                "#;
+            }
+        }),
+        "<pre>+---+\n| a |\n  b\n</pre>",
+    );
+}
+
+#[test]
+fn test_entity() {
+    crate::assert_defy_renders!(
+        {
+            span { + "a"; entity nbsp; + "b"; }
+            span { &{ "mdash" } &{ "#x2014" } &{ "#169" } entity amp; }
+        },
+        "<span>a\u{a0}b</span><span>——©&amp;</span>",
+    );
+}
+
+#[test]
+fn test_wrap_if() {
+    for (link, expected) in [(true, r#"<a href="/">home</a>"#), (false, "home")] {
+        crate::assert_defy_renders!(
+            {
+                wrap_if link a(href = "/") { + "home"; }
+            },
+            expected,
+        );
+    }
+}
+
+#[test]
+fn test_transition() {
+    for (visible, expected) in [(true, "<div><p>hello</p></div>"), (false, "")] {
+        crate::assert_defy_renders!(
+            {
+                transition(enter = "fade-in", leave = "fade-out", duration = 200) if visible {
+                    p { + "hello"; }
+                }
+            },
+            expected,
+        );
+    }
+}
+
+#[test]
+fn test_media() {
+    crate::assert_defy_renders!(
+        {
+            if media "(max-width: 600px)" { p { + "mobile"; } } else { p { + "desktop"; } }
+            if media "print" { p { + "print"; } }
+        },
+        "<p>desktop</p>",
+    );
+}
+
+#[test]
+fn test_virtual_for() {
+    let rows: std::rc::Rc<[u32]> = (0..100).collect();
+    crate::assert_defy_renders!(
+        {
+            virtual for row in rows.clone() (height = 10, viewport = 30) { p { + row; } }
+        },
+        "<div style=\"height: 30px; overflow-y: auto;\">\
+         <div style=\"box-sizing: border-box; height: 1000px; padding-top: 0px;\">\
+         <p>0</p><p>1</p><p>2</p><p>3</p><p>4</p>\
+         </div></div>",
+    );
+}
+
+#[test]
+fn test_do() {
+    let log = std::cell::RefCell::new(Vec::new());
+    crate::assert_defy_renders!(
+        {
+            do log.borrow_mut().push(1);
+            br;
+            do log.borrow_mut().push(2);
+        },
+        "<br>",
+    );
+    assert_eq!(*log.borrow(), [1, 2]);
+}
+
+#[test]
+fn test_bind() {
+    #[yew::function_component]
+    fn Form() -> yew::Html {
+        let name = yew::use_state(|| String::from("alice"));
+        let subscribed = yew::use_state(|| true);
+        crate::defy! {
+            input(bind:value = name);
+            input(type = "checkbox", bind:checked = subscribed);
+        }
+    }
+
+    crate::assert_defy_renders!(
+        {
+            Form;
+        },
+        r#"<input value="alice"><input checked type="checkbox">"#,
+    );
+}
+
+#[test]
+fn test_form() {
+    #[derive(Clone, PartialEq, crate::form::Form)]
+    struct Signup {
+        #[form(label = "E-mail")]
+        email:          String,
+        age:            Option<u32>,
+        terms_accepted: bool,
+    }
+
+    #[yew::function_component]
+    fn SignupForm() -> yew::Html {
+        let signup = yew::use_state(|| Signup {
+            email:          "a@b.c".to_string(),
+            age:            None,
+            terms_accepted: true,
+        });
+        crate::defy! {
+            form for signup {
+                field email(required = true);
+                field age(min = "0");
+                field terms_accepted;
+            }
+        }
+    }
+
+    crate::assert_defy_renders!(
+        {
+            SignupForm;
+        },
+        r#"<label for="email">E-mail</label>
+        <input value="a@b.c" id="email" name="email" type="text" required="required">
+        <label for="age">Age</label>
+        <input value="" id="age" name="age" type="number" min="0">
+        <label for="terms_accepted">Terms accepted</label>
+        <input value="true" checked id="terms_accepted" name="terms_accepted" type="checkbox">"#,
+    );
+}
+
+#[test]
+fn test_form_validation() {
+    use std::collections::HashMap;
+
+    #[derive(Clone, PartialEq, crate::form::Form)]
+    struct Account {
+        #[validate(email, length(max = 64))]
+        email:    String,
+        #[validate(length(min = 3, message = "too short"), custom(function = "check"))]
+        #[form(pattern = "[a-z]+")]
+        username: String,
+        #[validate(range(min = -10, max = 10))]
+        offset:   i32,
+    }
+
+    #[yew::function_component]
+    fn AccountForm() -> yew::Html {
+        let account = yew::use_state(|| Account {
+            email:    "a@b.c".to_string(),
+            username: "ab".to_string(),
+            offset:   0,
+        });
+        let errors = HashMap::from([("username", vec!["too short"])]);
+        crate::defy! {
+            form for account, errors = errors {
+                field email;
+                field username(minlength = "2");
+                field offset(type = "range");
+            }
+        }
+    }
+
+    crate::assert_defy_renders!(
+        {
+            AccountForm;
+        },
+        r#"<label for="email">Email</label>
+        <input value="a@b.c" id="email" name="email" type="email" maxlength="64">
+        <label for="username">Username</label>
+        <input value="ab" id="username" name="username" type="text" pattern="[a-z]+" minlength="2">
+        <span class="form-error">too short</span>
+        <label for="offset">Offset</label>
+        <input value="0" id="offset" name="offset" min="-10" max="10" type="range">"#,
+    );
+}
+
+#[test]
+fn test_use() {
+    mod components {
+        #[yew::function_component]
+        pub fn Greeting() -> yew::Html {
+            yew::html! { <p>{ "hi" }</p> }
+        }
+    }
+
+    crate::assert_defy_renders!(
+        {
+            div {
+                Greeting;
+                use components::Greeting;
+            }
+        },
+        "<div><p>hi</p></div>",
+    );
+}
+
+#[test]
+fn test_const_static() {
+    crate::assert_defy_renders!(
+        {
+            const MAX_SHOWN: usize = 2;
+            static SEPARATOR: &str = ", ";
+            + ["a", "b", "c"][..MAX_SHOWN].join(SEPARATOR);
+        },
+        "a, b",
+    );
+}
+
+#[test]
+fn test_fn_item() {
+    crate::assert_defy_renders!(
+        {
+            fn cell(text: &str) -> yew::Html {
+                crate::defy! { td { + text.to_uppercase(); } }
+            }
+            fn double(n: u32) -> u32 { n * 2 }
+
+            tr {
+                + cell("a");
+                + cell(&double(2).to_string());
+            }
+        },
+        "<tr><td>A</td><td>4</td></tr>",
+    );
+}
+
+#[test]
+fn test_notes() {
+    crate::assert_defy_renders!(
+        {
+            /// doc note
+            br;
+            note "stmt note";
+        },
+        "<br>",
+    );
+    assert_eq!(
+        testing::render(crate::defy! {
+            @emit_notes
+            /// doc -- note
+            let x = 1;
+            + x;
+            note "stmt note";
+        }),
+        "<!-- doc - - note -->1<!-- stmt note -->",
+    );
+}
+
+#[test]
+fn test_cfg() {
+    crate::assert_defy_renders!(
+        {
+            #[cfg(test)]
+            let x = 1;
+            #[cfg(not(test))]
+            let x = 2;
+
+            + x;
+            #[cfg(test)]
+            p { + "test"; }
+            #[cfg(not(test))]
+            #[allow(unknown_tag)]
+            admin_panel;
+            #[cfg(all(test, not(test)))]
+            for _ in 0..1 { br; }
+        },
+        "1<p>test</p>",
+    );
+}
+
+#[test]
+fn test_class_map() {
+    let open = true;
+    crate::assert_defy_renders!(
+        {
+            div(class = { "is-open": open, "is-disabled": !open, "wide": 1 + 1 == 2 }) {}
+        },
+        r#"<div class="is-open wide"></div>"#,
+    );
 }
 
 #[test]
-fn test_match() {
-    let _: ast::Input = syn::parse2(quote! {
-        match route {
-            Route::List | Route::Post => {
-                pages::comp;
+fn test_classes() {
+    let open = false;
+    let names = vec!["a".to_string(), " b ".to_string()];
+    assert_eq!(crate::classes![], "");
+    assert_eq!(crate::classes!["x", { "open": open }], "x");
+    assert_eq!(
+        crate::classes![{ "open": !open, "wide": true, }, &names, None::<&str>, ["", "c"][..].to_vec()],
+        "open wide a b c",
+    );
+    assert_eq!(crate::classes![&names[..], Some(String::from("d")),], "a b d");
+}
+
+#[test]
+fn test_attribute_order() {
+    crate::assert_defy_renders!(
+        {
+            a(id = "x", href = "#", data - b = "1", class = "c");
+        },
+        r##"<a id="x" href="#" data-b="1" class="c"></a>"##,
+    );
+    crate::assert_defy_renders!(
+        {
+            @sort_attrs
+            a(id = "x", href = "#", data-b = "1", class = "c");
+        },
+        r##"<a data-b="1" href="#" id="x" class="c"></a>"##,
+    );
+}
+
+#[test]
+fn test_key_filters() {
+    let submit = yew::Callback::from(|_: yew::KeyboardEvent| {});
+    crate::assert_defy_renders!(
+        {
+            input(@keydown(Enter) = submit.clone());
+            input(@keyup(Escape | "k" with ctrl + meta) = |_| {});
+            form(@submit.prevent = |_| {}) {
+                button(@click.stop.prevent);
+                input(@keydown(Enter).prevent = &submit);
+            }
+            div(id = "list", @wheel.passive = |_: yew::WheelEvent| {}) {}
+        },
+        r#"<input><input><form><button></button><input></form><div id="list"></div>"#,
+    );
+}
+
+#[test]
+fn test_link() {
+    struct Counter;
+    enum Msg {
+        Increment,
+        Set(i32),
+    }
+    impl yew::Component for Counter {
+        type Message = Msg;
+        type Properties = ();
+
+        fn create(_: &yew::Context<Self>) -> Self { Counter }
+
+        fn update(&mut self, _: &yew::Context<Self>, msg: Msg) -> bool {
+            match msg {
+                Msg::Increment => true,
+                Msg::Set(value) => value != 0,
             }
         }
-    })
-    .unwrap();
+
+        fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+            let step = 2;
+            crate::defy! {
+                @link ctx
+                button(@click = Msg::Increment) { + "+"; }
+                button(@click = Msg::Set(step)) { + "2"; }
+                input(@input = |_: yew::InputEvent| Msg::Set(0));
+                a(@mouseover = Msg::Set(1));
+                input(@keydown(Enter | "s" with ctrl) = Msg::Increment);
+                input(@keyup(Escape) = |_: yew::KeyboardEvent| Msg::Set(0));
+                form(@submit.prevent = Msg::Increment) {}
+                div(@scroll.passive = Msg::Set(3)) {}
+            }
+        }
+    }
+    crate::assert_defy_renders!(
+        {
+            Counter;
+        },
+        "<button>+</button><button>2</button><input><a></a><input><input><form></form><div></div>",
+    );
+}
+
+#[crate::stories]
+mod stories {
+    #[story]
+    fn primary_button() -> yew::Html {
+        crate::defy! { button { + "Save"; } }
+    }
+
+    #[crate::story("Link")]
+    fn anchor() -> yew::Html {
+        crate::defy! { a(href = "#") { + "More"; } }
+    }
+}
+
+#[test]
+fn test_stories() {
+    let names: Vec<_> = stories::STORIES.iter().map(|story| story.name).collect();
+    assert_eq!(names, ["Primary button", "Link"]);
+    crate::assert_defy_renders!(
+        {
+            stories::Stories;
+        },
+        r#"<div class="defy-gallery">
+            <nav><ul>
+                <li><button type="button" aria-current="page">Primary button</button></li>
+                <li><button type="button">Link</button></li>
+            </ul></nav>
+            <main><button>Save</button></main>
+        </div>"#,
+    );
+}
+
+#[test]
+fn test_testids() {
+    crate::assert_defy_renders!(
+        {
+            @testids
+            form { button(testid = "submit") { span; } }
+        },
+        r#"<form data-testid="form"><button data-testid="submit"><span data-testid="submit-span"></span></button></form>"#,
+    );
 }
 
 #[test]
-fn test_keyword() {
-    let _: ast::Input = syn::parse2(quote! {
-        input(type = "checkbox");
-    })
-    .unwrap();
+fn test_attributes() {
+    crate::assert_defy_renders!(
+        {
+            #[allow(unused_variables)]
+            let unused = 1;
+            #[allow(unknown_tag)]
+            #[rustfmt::skip]
+            my_widget;
+            #[allow(clippy::never_loop)]
+            for x in 0..2 { + x; }
+        },
+        "<my_widget></my_widget>01",
+    );
 }
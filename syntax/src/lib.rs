@@ -0,0 +1,2279 @@
+//! Parser for the syntax of the [`defy!`](https://docs.rs/defy) macro.
+//!
+//! This crate allows editors, linters and code generators
+//! to work with defy syntax without invoking the macro.
+//! All syntax tree types implement [`syn::parse::Parse`] and [`quote::ToTokens`],
+//! and retain the spans of their tokens.
+//!
+//! ```
+//! let input: defy_syntax::Input = syn::parse_str(
+//!     r#"
+//!     @scope "card"
+//!     div(class = "title") { + "Hello"; }
+//! "#,
+//! )
+//! .unwrap();
+//! assert_eq!(input.configs.len(), 1);
+//!
+//! let defy_syntax::Stmt::Node(node) = &input.nodes.stmts[0] else { unreachable!() };
+//! assert!(node.element.is_ident("div"));
+//! ```
+
+use proc_macro2::{Delimiter, Spacing, Span, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::ext::IdentExt;
+use syn::parse::discouraged::Speculative;
+use syn::parse::{Parse, ParseStream, Peek};
+use syn::punctuated::Punctuated;
+use syn::Result;
+
+mod tests;
+mod tokens;
+
+/// The contents of a `defy!` invocation.
+pub struct Input {
+    /// Each config with the tokens it was parsed from.
+    pub configs: Vec<(Config, TokenStream)>,
+    pub nodes:   Nodes,
+}
+impl Parse for Input {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut configs = Vec::new();
+        while input.peek(syn::Token![@]) {
+            let begin = input.cursor();
+            let config = input.parse()?;
+
+            let mut tokens = TokenStream::new();
+            let mut cursor = begin;
+            while cursor != input.cursor() {
+                let Some((tt, next)) = cursor.token_tree() else { break };
+                tokens.extend([tt]);
+                cursor = next;
+            }
+            configs.push((config, tokens));
+        }
+
+        Ok(Self { configs, nodes: input.parse()? })
+    }
+}
+
+/// Statement keywords.
+pub mod kw {
+    syn::custom_keyword!(include);
+    syn::custom_keyword!(markdown);
+    syn::custom_keyword!(t);
+    syn::custom_keyword!(icon);
+    syn::custom_keyword!(style);
+    syn::custom_keyword!(memo);
+    syn::custom_keyword!(once);
+    syn::custom_keyword!(defer);
+    syn::custom_keyword!(placeholder);
+    syn::custom_keyword!(pending);
+    syn::custom_keyword!(catch);
+    syn::custom_keyword!(repeat);
+    syn::custom_keyword!(with);
+    syn::custom_keyword!(wrap_if);
+    syn::custom_keyword!(maybe);
+    syn::custom_keyword!(transition);
+    syn::custom_keyword!(media);
+    syn::custom_keyword!(island);
+    syn::custom_keyword!(lazy);
+    syn::custom_keyword!(xml_decl);
+    syn::custom_keyword!(doctype);
+    syn::custom_keyword!(block);
+    syn::custom_keyword!(extend);
+    syn::custom_keyword!(raw);
+    syn::custom_keyword!(embed);
+    syn::custom_keyword!(bind);
+    syn::custom_keyword!(form);
+    syn::custom_keyword!(field);
+    syn::custom_keyword!(errors);
+    syn::custom_keyword!(table);
+    syn::custom_keyword!(column);
+    syn::custom_keyword!(options);
+    syn::custom_keyword!(from);
+    syn::custom_keyword!(picture);
+    syn::custom_keyword!(json_script);
+    syn::custom_keyword!(og);
+    syn::custom_keyword!(document_head);
+    syn::custom_keyword!(entity);
+    syn::custom_keyword!(space);
+    syn::custom_keyword!(verbatim);
+    syn::custom_keyword!(passthrough);
+    syn::custom_keyword!(note);
+}
+
+/// Whether the input starts with the keyword `kw` used as a statement keyword.
+///
+/// Keywords are not reserved, so an element with the same name
+/// (followed by arguments, a body or a path continuation) still parses as a node.
+fn peek_stmt_keyword(input: ParseStream, kw: impl Peek) -> bool {
+    input.peek(kw)
+        && !(input.peek2(syn::token::Paren)
+            || input.peek2(syn::token::Brace)
+            || input.peek2(syn::Token![;])
+            || input.peek2(syn::Token![=])
+            || input.peek2(syn::Token![::])
+            || input.peek2(syn::Token![<]))
+}
+
+/// Config keywords, used after `@`.
+pub mod config_kw {
+    syn::custom_keyword!(__debug_print);
+    syn::custom_keyword!(debug_print);
+    syn::custom_keyword!(debug_loc);
+    syn::custom_keyword!(emit_notes);
+    syn::custom_keyword!(note);
+    syn::custom_keyword!(macro_path);
+    syn::custom_keyword!(i18n);
+    syn::custom_keyword!(i18n_resources);
+    syn::custom_keyword!(asset_resolver);
+    syn::custom_keyword!(scope);
+    syn::custom_keyword!(theme);
+    syn::custom_keyword!(allow);
+    syn::custom_keyword!(warn);
+    syn::custom_keyword!(deny);
+    syn::custom_keyword!(strict);
+    syn::custom_keyword!(trace);
+    syn::custom_keyword!(export);
+    syn::custom_keyword!(csp_nonce);
+    syn::custom_keyword!(minify);
+    syn::custom_keyword!(sort_attrs);
+    syn::custom_keyword!(testids);
+    syn::custom_keyword!(xml);
+    syn::custom_keyword!(link);
+    syn::custom_keyword!(blocks);
+}
+/// A config in the form `@name ...`, which must precede all statements.
+pub enum Config {
+    DebugPrint {
+        at:     syn::Token![@],
+        /// `debug_print` or `__debug_print`.
+        kw:     syn::Ident,
+        target: Option<(syn::token::Paren, DebugPrintTarget)>,
+    },
+    DebugLoc {
+        at: syn::Token![@],
+        kw: config_kw::debug_loc,
+    },
+    EmitNotes {
+        at: syn::Token![@],
+        kw: config_kw::emit_notes,
+    },
+    MacroPath {
+        at:   syn::Token![@],
+        kw:   config_kw::macro_path,
+        path: syn::Path,
+    },
+    I18n {
+        at:   syn::Token![@],
+        kw:   config_kw::i18n,
+        path: syn::Path,
+    },
+    I18nResources {
+        at:   syn::Token![@],
+        kw:   config_kw::i18n_resources,
+        path: syn::LitStr,
+    },
+    AssetResolver {
+        at:   syn::Token![@],
+        kw:   config_kw::asset_resolver,
+        path: syn::Path,
+    },
+    Scope {
+        at:   syn::Token![@],
+        kw:   config_kw::scope,
+        name: syn::LitStr,
+    },
+    Theme {
+        at:   syn::Token![@],
+        kw:   config_kw::theme,
+        path: syn::LitStr,
+    },
+    Export {
+        at:   syn::Token![@],
+        kw:   config_kw::export,
+        name: syn::Ident,
+    },
+    Strict {
+        at: syn::Token![@],
+        kw: config_kw::strict,
+    },
+    Trace {
+        at: syn::Token![@],
+        kw: config_kw::trace,
+    },
+    Minify {
+        at: syn::Token![@],
+        kw: config_kw::minify,
+    },
+    SortAttrs {
+        at: syn::Token![@],
+        kw: config_kw::sort_attrs,
+    },
+    Testids {
+        at: syn::Token![@],
+        kw: config_kw::testids,
+    },
+    /// Renders elements as XML instead of yew elements.
+    Xml {
+        at: syn::Token![@],
+        kw: config_kw::xml,
+    },
+    /// The `defy::layout::Blocks` that `block` statements of a layout are overridden with.
+    Blocks {
+        at:     syn::Token![@],
+        kw:     config_kw::blocks,
+        blocks: syn::Expr,
+    },
+    Link {
+        at:  syn::Token![@],
+        kw:  config_kw::link,
+        /// The `Context` of a struct component, whose link `@event` handlers send messages to.
+        ctx: syn::Expr,
+    },
+    CspNonce {
+        at:    syn::Token![@],
+        kw:    config_kw::csp_nonce,
+        /// An expression without binary operators, e.g. `props.nonce`.
+        nonce: syn::Expr,
+    },
+    LintLevel {
+        at:    syn::Token![@],
+        level: LintLevel,
+        paren: syn::token::Paren,
+        lints: Punctuated<syn::Ident, syn::Token![,]>,
+    },
+}
+impl Parse for Config {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let at = input.parse()?;
+        let lh = input.lookahead1();
+        Ok(if lh.peek(config_kw::__debug_print) || lh.peek(config_kw::debug_print) {
+            Config::DebugPrint {
+                at,
+                kw: input.call(syn::Ident::parse_any)?,
+                target: if input.peek(syn::token::Paren) {
+                    let inner;
+                    Some((syn::parenthesized!(inner in input), inner.parse()?))
+                } else {
+                    None
+                },
+            }
+        } else if lh.peek(config_kw::debug_loc) {
+            Config::DebugLoc { at, kw: input.parse()? }
+        } else if lh.peek(config_kw::emit_notes) {
+            Config::EmitNotes { at, kw: input.parse()? }
+        } else if lh.peek(config_kw::macro_path) {
+            Config::MacroPath { at, kw: input.parse()?, path: input.parse()? }
+        } else if lh.peek(config_kw::i18n_resources) {
+            Config::I18nResources { at, kw: input.parse()?, path: input.parse()? }
+        } else if lh.peek(config_kw::asset_resolver) {
+            Config::AssetResolver { at, kw: input.parse()?, path: input.parse()? }
+        } else if lh.peek(config_kw::scope) {
+            Config::Scope { at, kw: input.parse()?, name: input.parse()? }
+        } else if lh.peek(config_kw::theme) {
+            Config::Theme { at, kw: input.parse()?, path: input.parse()? }
+        } else if lh.peek(config_kw::export) {
+            Config::Export { at, kw: input.parse()?, name: input.parse()? }
+        } else if lh.peek(config_kw::strict) {
+            Config::Strict { at, kw: input.parse()? }
+        } else if lh.peek(config_kw::trace) {
+            Config::Trace { at, kw: input.parse()? }
+        } else if lh.peek(config_kw::minify) {
+            Config::Minify { at, kw: input.parse()? }
+        } else if lh.peek(config_kw::sort_attrs) {
+            Config::SortAttrs { at, kw: input.parse()? }
+        } else if lh.peek(config_kw::testids) {
+            Config::Testids { at, kw: input.parse()? }
+        } else if lh.peek(config_kw::xml) {
+            Config::Xml { at, kw: input.parse()? }
+        } else if lh.peek(config_kw::blocks) {
+            Config::Blocks { at, kw: input.parse()?, blocks: parse_postfix_expr(input)? }
+        } else if lh.peek(config_kw::link) {
+            Config::Link { at, kw: input.parse()?, ctx: parse_postfix_expr(input)? }
+        } else if lh.peek(config_kw::csp_nonce) {
+            Config::CspNonce { at, kw: input.parse()?, nonce: parse_postfix_expr(input)? }
+        } else if lh.peek(config_kw::allow) || lh.peek(config_kw::warn) || lh.peek(config_kw::deny)
+        {
+            let inner;
+            Config::LintLevel {
+                at,
+                level: input.parse()?,
+                paren: syn::parenthesized!(inner in input),
+                lints: Punctuated::parse_terminated(&inner)?,
+            }
+        } else if lh.peek(config_kw::i18n) {
+            Config::I18n { at, kw: input.parse()?, path: input.parse()? }
+        } else {
+            return Err(lh.error());
+        })
+    }
+}
+
+/// Parses an expression without binary operators, e.g. `props.nonce.clone()`,
+/// so that a following text statement is not parsed as the right operand.
+fn parse_postfix_expr(input: ParseStream) -> Result<syn::Expr> {
+    let mut tokens = TokenStream::new();
+    tokens.extend([input.parse::<TokenTree>()?]);
+    loop {
+        if input.peek(syn::Token![::]) {
+            input.parse::<syn::Token![::]>()?.to_tokens(&mut tokens);
+            tokens.extend([input.parse::<TokenTree>()?]);
+        } else if input.peek(syn::Token![.]) {
+            input.parse::<syn::Token![.]>()?.to_tokens(&mut tokens);
+            tokens.extend([input.parse::<TokenTree>()?]);
+        } else if input.peek(syn::token::Paren) || input.peek(syn::token::Bracket) {
+            tokens.extend([input.parse::<TokenTree>()?]);
+        } else {
+            break;
+        }
+    }
+    syn::parse2(tokens)
+}
+
+/// The argument of `@debug_print(...)`.
+pub enum DebugPrintTarget {
+    /// The file to write the expansion to, relative to `CARGO_MANIFEST_DIR`.
+    File(syn::LitStr),
+    /// Reports the expansion as a compiler note.
+    Note(config_kw::note),
+}
+impl Parse for DebugPrintTarget {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lh = input.lookahead1();
+        Ok(if lh.peek(syn::LitStr) {
+            Self::File(input.parse()?)
+        } else if lh.peek(config_kw::note) {
+            Self::Note(input.parse()?)
+        } else {
+            return Err(lh.error());
+        })
+    }
+}
+
+/// The level in `@allow(...)`, `@warn(...)` and `@deny(...)`.
+pub enum LintLevel {
+    Allow(config_kw::allow),
+    Warn(config_kw::warn),
+    Deny(config_kw::deny),
+}
+impl Parse for LintLevel {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lh = input.lookahead1();
+        Ok(if lh.peek(config_kw::allow) {
+            Self::Allow(input.parse()?)
+        } else if lh.peek(config_kw::warn) {
+            Self::Warn(input.parse()?)
+        } else if lh.peek(config_kw::deny) {
+            Self::Deny(input.parse()?)
+        } else {
+            return Err(lh.error());
+        })
+    }
+}
+
+/// A sequence of statements, e.g. the children of a node.
+pub struct Nodes {
+    pub stmts: Vec<Stmt>,
+}
+impl Parse for Nodes {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut stmts = Vec::new();
+        let mut errors: Option<syn::Error> = None;
+        while !input.is_empty() {
+            let fork = input.fork();
+            match fork.parse() {
+                Ok(stmt) => {
+                    input.advance_to(&fork);
+                    stmts.push(stmt);
+                }
+                Err(err) => {
+                    // recover at the next statement boundary to report further errors
+                    match &mut errors {
+                        Some(errors) => errors.combine(err),
+                        None => errors = Some(err),
+                    }
+                    skip_stmt(input)?;
+                }
+            }
+        }
+
+        match errors {
+            Some(err) => Err(err),
+            None => Ok(Self { stmts }),
+        }
+    }
+}
+
+/// Skips tokens until the end of the current statement,
+/// i.e. after the next `;` or the next braced group that is not followed by `else`.
+fn skip_stmt(input: ParseStream) -> Result<()> {
+    // `let` statements may contain braced groups in the expression, e.g. struct literals
+    let is_let = input.peek(syn::Token![let]);
+    input.step(|cursor| {
+        let mut rest = *cursor;
+        while let Some((tt, next)) = rest.token_tree() {
+            rest = next;
+            match tt {
+                TokenTree::Punct(punct) if punct.as_char() == ';' => break,
+                TokenTree::Group(group)
+                    if group.delimiter() == Delimiter::Brace
+                        && !is_let
+                        && !matches!(next.ident(), Some((ident, _)) if ident == "else") =>
+                {
+                    break
+                }
+                _ => {}
+            }
+        }
+        Ok(((), rest))
+    })
+}
+
+/// A statement in a sequence of nodes.
+pub enum Stmt {
+    If(If),
+    Match(Match),
+    For(For),
+    Let(Let),
+    /// A `use`, `const`, `static` or `fn` item.
+    Item(Box<syn::Item>),
+    Text(Text),
+    Include(Include),
+    Embed(Embed),
+    Dyn(Dyn),
+    Markdown(Markdown),
+    Translate(Translate),
+    Icon(Icon),
+    Style(Style),
+    Memo(Memo),
+    Once(Once),
+    Defer(Defer),
+    Await(Await),
+    Try(Try),
+    Break(Break),
+    Continue(Continue),
+    Repeat(Repeat),
+    With(With),
+    WrapIf(WrapIf),
+    Maybe(Maybe),
+    Transition(Transition),
+    Island(Island),
+    Lazy(Lazy),
+    XmlDecl(XmlDecl),
+    Doctype(Doctype),
+    Block(Block),
+    Extend(Extend),
+    VirtualFor(VirtualFor),
+    Form(Form),
+    Table(Table),
+    Options(Options),
+    Picture(Picture),
+    JsonScript(JsonScript),
+    Og(Og),
+    DocumentHead(DocumentHead),
+    Entity(Entity),
+    Space(Space),
+    Verbatim(Verbatim),
+    Passthrough(Passthrough),
+    Raw(Raw),
+    Do(Do),
+    Note(Note),
+    Attributed(Attributed),
+    Node(Node),
+}
+impl Parse for Stmt {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(syn::Token![#]) && !Note::peek_doc(input) {
+            return Stmt::parse_attributed(input);
+        }
+        if peek_stmt_keyword(input, kw::include) {
+            return Ok(Stmt::Include(input.parse()?));
+        }
+        if peek_stmt_keyword(input, kw::embed) {
+            return Ok(Stmt::Embed(input.parse()?));
+        }
+        if input.peek(syn::Token![dyn]) {
+            return Ok(Stmt::Dyn(input.parse()?));
+        }
+        if input.peek(kw::t) && input.peek2(syn::LitStr) {
+            return Ok(Stmt::Translate(input.parse()?));
+        }
+        if input.peek(kw::picture) && input.peek2(syn::LitStr) {
+            return Ok(Stmt::Picture(input.parse()?));
+        }
+        if input.peek(kw::json_script)
+            && (input.peek2(syn::token::Paren) || input.peek2(syn::Token![=]))
+        {
+            return Ok(Stmt::JsonScript(input.parse()?));
+        }
+        if input.peek(kw::og) && input.peek2(syn::token::Brace) {
+            return Ok(Stmt::Og(input.parse()?));
+        }
+        if input.peek(kw::verbatim) && input.peek2(syn::LitStr) {
+            return Ok(Stmt::Verbatim(input.parse()?));
+        }
+        if input.peek(kw::passthrough) && input.peek2(syn::token::Brace) {
+            return Ok(Stmt::Passthrough(input.parse()?));
+        }
+        if input.peek(kw::raw) && input.peek2(syn::LitStr) {
+            return Ok(Stmt::Raw(input.parse()?));
+        }
+        if input.peek(kw::space) && input.peek2(syn::Token![;]) {
+            return Ok(Stmt::Space(input.parse()?));
+        }
+        if (input.peek(kw::entity) && input.peek2(syn::Ident::peek_any))
+            || (input.peek(syn::Token![&]) && input.peek2(syn::token::Brace))
+        {
+            return Ok(Stmt::Entity(input.parse()?));
+        }
+        if input.peek(kw::document_head) && input.peek2(syn::token::Brace) {
+            return Ok(Stmt::DocumentHead(input.parse()?));
+        }
+        if input.peek(kw::icon) && input.peek2(syn::LitStr) {
+            return Ok(Stmt::Icon(input.parse()?));
+        }
+        if input.peek(kw::style) && input.peek2(syn::token::Brace) && Style::peek_css(input) {
+            return Ok(Stmt::Style(input.parse()?));
+        }
+        if input.peek(kw::memo) && input.peek2(syn::token::Paren) && input.peek3(syn::token::Brace)
+        {
+            return Ok(Stmt::Memo(input.parse()?));
+        }
+        if input.peek(kw::once) && input.peek2(syn::token::Brace) {
+            return Ok(Stmt::Once(input.parse()?));
+        }
+        if input.peek(kw::defer) && input.peek2(syn::token::Brace) {
+            return Ok(Stmt::Defer(input.parse()?));
+        }
+        if (input.peek(kw::note) && input.peek2(syn::LitStr)) || Note::peek_doc(input) {
+            return Ok(Stmt::Note(input.parse()?));
+        }
+        if peek_stmt_keyword(input, kw::repeat) {
+            return Ok(Stmt::Repeat(input.parse()?));
+        }
+        if peek_stmt_keyword(input, kw::with) {
+            return Ok(Stmt::With(input.parse()?));
+        }
+        if peek_stmt_keyword(input, kw::wrap_if) {
+            return Ok(Stmt::WrapIf(input.parse()?));
+        }
+        if input.peek(kw::xml_decl) && input.peek2(syn::token::Paren) && input.peek3(syn::Token![;])
+        {
+            return Ok(Stmt::XmlDecl(input.parse()?));
+        }
+        if input.peek(kw::doctype) && input.peek2(syn::Ident) && input.peek3(syn::Token![;]) {
+            return Ok(Stmt::Doctype(input.parse()?));
+        }
+        if input.peek(kw::block) && input.peek2(syn::Ident) && input.peek3(syn::token::Brace) {
+            return Ok(Stmt::Block(input.parse()?));
+        }
+        if peek_stmt_keyword(input, kw::extend) {
+            return Ok(Stmt::Extend(input.parse()?));
+        }
+        if peek_stmt_keyword(input, kw::island) {
+            return Ok(Stmt::Island(input.parse()?));
+        }
+        if peek_stmt_keyword(input, kw::lazy) {
+            return Ok(Stmt::Lazy(input.parse()?));
+        }
+        if peek_stmt_keyword(input, kw::maybe) {
+            return Ok(Stmt::Maybe(input.parse()?));
+        }
+        if input.peek(kw::transition)
+            && input.peek2(syn::token::Paren)
+            && input.peek3(syn::Token![if])
+        {
+            return Ok(Stmt::Transition(input.parse()?));
+        }
+        if input.peek(kw::form) && input.peek2(syn::Token![for]) {
+            return Ok(Stmt::Form(input.parse()?));
+        }
+        if Table::peek(input) {
+            return Ok(Stmt::Table(input.parse()?));
+        }
+        if input.peek(kw::options) && input.peek2(kw::from) {
+            return Ok(Stmt::Options(input.parse()?));
+        }
+        if peek_stmt_keyword(input, kw::markdown) {
+            return Ok(Stmt::Markdown(input.parse()?));
+        }
+
+        if input.peek(syn::Lit) || (input.peek(syn::Ident) && input.peek2(syn::Token![!])) {
+            return Err(syn::Error::new(
+                input.span(),
+                "DEFY0101: text nodes must be prefixed with `+`, e.g. `+ \"hello\";` or `+ \
+                 format!(...);`",
+            ));
+        }
+
+        let lh = input.lookahead1();
+
+        Ok(if lh.peek(syn::Token![await]) {
+            Stmt::Await(input.parse()?)
+        } else if lh.peek(syn::Token![try]) {
+            Stmt::Try(input.parse()?)
+        } else if lh.peek(syn::Token![do]) {
+            Stmt::Do(input.parse()?)
+        } else if lh.peek(syn::Token![break]) {
+            Stmt::Break(input.parse()?)
+        } else if lh.peek(syn::Token![continue]) {
+            Stmt::Continue(input.parse()?)
+        } else if lh.peek(syn::Token![if]) {
+            Stmt::If(input.parse()?)
+        } else if lh.peek(syn::Token![match]) {
+            Stmt::Match(input.parse()?)
+        } else if lh.peek(syn::Token![for]) {
+            Stmt::For(input.parse()?)
+        } else if lh.peek(syn::Token![virtual]) {
+            Stmt::VirtualFor(input.parse()?)
+        } else if lh.peek(syn::Token![let]) {
+            Stmt::Let(input.parse()?)
+        } else if lh.peek(syn::Token![use]) {
+            Stmt::Item(Box::new(syn::Item::Use(input.parse()?)))
+        } else if lh.peek(syn::Token![const]) {
+            Stmt::Item(Box::new(syn::Item::Const(input.parse()?)))
+        } else if lh.peek(syn::Token![static]) {
+            Stmt::Item(Box::new(syn::Item::Static(input.parse()?)))
+        } else if lh.peek(syn::Token![fn]) {
+            Stmt::Item(Box::new(syn::Item::Fn(input.parse()?)))
+        } else if lh.peek(syn::Token![+]) {
+            Stmt::Text(input.parse()?)
+        } else if lh.peek(syn::Ident) || lh.peek(syn::Token![#]) {
+            Stmt::Node(input.parse()?)
+        } else {
+            return Err(lh.error());
+        })
+    }
+}
+
+impl Stmt {
+    /// Parses a statement with outer attributes.
+    ///
+    /// Lint attributes are kept in the node they apply to, and all attributes are kept in items.
+    fn parse_attributed(input: ParseStream) -> Result<Self> {
+        let mut attrs = input.call(syn::Attribute::parse_outer)?;
+        let mut stmt: Stmt = input.parse()?;
+        match &mut stmt {
+            Stmt::Node(node) => {
+                // attributes other than `cfg` may configure lint levels of the node
+                let (cfgs, others): (Vec<_>, Vec<_>) =
+                    attrs.into_iter().partition(|attr| attr.path().is_ident("cfg"));
+                node.attrs.splice(0..0, others);
+                attrs = cfgs;
+            }
+            Stmt::Item(item) => {
+                let item_attrs = match &mut **item {
+                    syn::Item::Use(item) => &mut item.attrs,
+                    syn::Item::Const(item) => &mut item.attrs,
+                    syn::Item::Static(item) => &mut item.attrs,
+                    syn::Item::Fn(item) => &mut item.attrs,
+                    _ => unreachable!("other items are not parsed"),
+                };
+                item_attrs.splice(0..0, attrs);
+                return Ok(stmt);
+            }
+            _ => {}
+        }
+
+        if attrs.is_empty() {
+            Ok(stmt)
+        } else {
+            Ok(Stmt::Attributed(Attributed { attrs, stmt: Box::new(stmt) }))
+        }
+    }
+}
+
+/// A statement with outer attributes, which are forwarded to the generated code.
+pub struct Attributed {
+    pub attrs: Vec<syn::Attribute>,
+    pub stmt:  Box<Stmt>,
+}
+
+/// Parses the head expression of a control flow statement followed by a braced body.
+///
+/// A braced body directly followed by another braced group is never valid,
+/// so it most likely means that a brace in the head was taken as the start of the body.
+fn parse_head(input: ParseStream, keyword: &str) -> Result<Box<syn::Expr>> {
+    let expr = input.call(syn::Expr::parse_without_eager_brace)?;
+    if input.peek(syn::token::Brace) && input.peek2(syn::token::Brace) {
+        return Err(syn::Error::new(
+            input.span(),
+            format_args!(
+                "DEFY0102: this brace is parsed as the body of `{keyword}`; expressions \
+                 containing braces, such as struct literals, must be parenthesized, e.g. \
+                 `{keyword} (x == Foo {{ .. }}) {{ ... }}`"
+            ),
+        ));
+    }
+    Ok(Box::new(expr))
+}
+
+/// `if expr { ... }` or `if media "query" { ... }`, optionally followed by `else { ... }`.
+pub struct If {
+    pub if_:    syn::Token![if],
+    /// `media` before a media query, which is stored in `expr`.
+    pub media:  Option<kw::media>,
+    pub expr:   Box<syn::Expr>,
+    pub braces: syn::token::Brace,
+    pub body:   Nodes,
+    pub else_:  Option<Else>,
+}
+impl Parse for If {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            if_:    input.parse()?,
+            media:  if input.peek(kw::media) && input.peek2(syn::LitStr) {
+                Some(input.parse()?)
+            } else {
+                None
+            },
+            expr:   parse_head(input, "if")?,
+            braces: syn::braced!(inner in input),
+            body:   inner.parse()?,
+            else_:  if input.peek(syn::Token![else]) { Some(input.parse()?) } else { None },
+        })
+    }
+}
+/// `else { ... }`.
+pub struct Else {
+    pub else_:  syn::Token![else],
+    pub braces: syn::token::Brace,
+    pub body:   Nodes,
+}
+impl Parse for Else {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            else_:  input.parse()?,
+            braces: syn::braced!(inner in input),
+            body:   inner.parse()?,
+        })
+    }
+}
+
+/// `match expr { arms }`.
+pub struct Match {
+    pub match_: syn::Token![match],
+    pub expr:   Box<syn::Expr>,
+    pub braces: syn::token::Brace,
+    pub arms:   Vec<Arm>,
+}
+impl Parse for Match {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            match_: input.parse()?,
+            expr:   parse_head(input, "match")?,
+            braces: syn::braced!(inner in input),
+            arms:   {
+                let mut arms = Vec::new();
+                while !inner.is_empty() {
+                    arms.push(inner.parse()?);
+                }
+                arms
+            },
+        })
+    }
+}
+
+/// `pat if guard => { ... }` in a `match` statement.
+pub struct Arm {
+    pub pat:       syn::Pat,
+    pub guard:     Option<(syn::Token![if], Box<syn::Expr>)>,
+    pub fat_arrow: syn::Token![=>],
+    pub braces:    syn::token::Brace,
+    pub body:      Nodes,
+}
+impl Parse for Arm {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            pat:       syn::Pat::parse_multi_with_leading_vert(input)?,
+            guard:     if input.peek(syn::Token![if]) {
+                Some((input.parse()?, input.parse()?))
+            } else {
+                None
+            },
+            fat_arrow: input.parse()?,
+            braces:    syn::braced!(inner in input),
+            body:      inner.parse()?,
+        })
+    }
+}
+
+/// `for pat in expr { ... }`, optionally followed by `else { ... }`
+/// rendered when the iterator yields no items.
+pub struct For {
+    pub for_:   syn::Token![for],
+    pub pat:    Box<syn::Pat>,
+    pub in_:    syn::Token![in],
+    pub iter:   Box<syn::Expr>,
+    pub braces: syn::token::Brace,
+    pub body:   Nodes,
+    pub else_:  Option<Else>,
+}
+impl Parse for For {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            for_:   input.parse()?,
+            pat:    Box::new(syn::Pat::parse_multi_with_leading_vert(input)?),
+            in_:    input.parse()?,
+            iter:   parse_head(input, "for")?,
+            braces: syn::braced!(inner in input),
+            body:   inner.parse()?,
+            else_:  if input.peek(syn::Token![else]) { Some(input.parse()?) } else { None },
+        })
+    }
+}
+
+/// `virtual for pat in expr (height = ..., viewport = ...) { ... }`,
+/// which only renders the items visible in a scrollable viewport.
+pub struct VirtualFor {
+    pub virtual_: syn::Token![virtual],
+    pub for_:     syn::Token![for],
+    pub pat:      Box<syn::Pat>,
+    pub in_:      syn::Token![in],
+    pub iter:     Box<syn::Expr>,
+    pub paren:    syn::token::Paren,
+    pub args:     Punctuated<BlockArg, syn::Token![,]>,
+    pub braces:   syn::token::Brace,
+    pub body:     Nodes,
+}
+impl Parse for VirtualFor {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let virtual_ = input.parse()?;
+        let for_ = input.parse()?;
+        let pat = Box::new(syn::Pat::parse_multi_with_leading_vert(input)?);
+        let in_ = input.parse()?;
+        // the arguments are parsed as a call of the iterator expression
+        let head = parse_head(input, "virtual for")?;
+        let syn::Expr::Call(call) = *head else {
+            return Err(syn::Error::new_spanned(
+                head,
+                "DEFY0109: `virtual for` requires arguments after the iterator, e.g. `virtual for \
+                 item in items (height = 32, viewport = 600) { ... }`",
+            ));
+        };
+        let mut args = Punctuated::new();
+        for pair in call.args.into_pairs() {
+            let (arg, comma) = pair.into_tuple();
+            let arg = match arg {
+                syn::Expr::Assign(syn::ExprAssign { attrs, left, eq_token, right })
+                    if attrs.is_empty() =>
+                {
+                    match *left {
+                        syn::Expr::Path(path) if path.path.get_ident().is_some() => BlockArg {
+                            ident: path.path.segments[0].ident.clone(),
+                            eq:    eq_token,
+                            value: right,
+                        },
+                        left => {
+                            return Err(syn::Error::new_spanned(
+                                left,
+                                "DEFY0109: expected an argument name",
+                            ))
+                        }
+                    }
+                }
+                arg => {
+                    return Err(syn::Error::new_spanned(
+                        arg,
+                        "DEFY0109: expected `name = value` in the arguments of `virtual for`",
+                    ))
+                }
+            };
+            args.push_value(arg);
+            if let Some(comma) = comma {
+                args.push_punct(comma);
+            }
+        }
+        let inner;
+        Ok(Self {
+            virtual_,
+            for_,
+            pat,
+            in_,
+            iter: call.func,
+            paren: call.paren_token,
+            args,
+            braces: syn::braced!(inner in input),
+            body: inner.parse()?,
+        })
+    }
+}
+
+/// `let pat = expr;` or `let pat: Type = expr;`.
+pub struct Let {
+    pub let_: syn::Token![let],
+    pub pat:  Box<syn::Pat>,
+    pub ty:   Option<(syn::Token![:], Box<syn::Type>)>,
+    pub eq:   syn::Token![=],
+    pub expr: Box<syn::Expr>,
+    pub semi: syn::Token![;],
+}
+impl Parse for Let {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            let_: input.parse()?,
+            pat:  Box::new(syn::Pat::parse_multi_with_leading_vert(input)?),
+            ty:   if input.peek(syn::Token![:]) {
+                Some((input.parse()?, input.parse()?))
+            } else {
+                None
+            },
+            eq:   input.parse()?,
+            expr: input.parse()?,
+            semi: input.parse()?,
+        })
+    }
+}
+
+/// `+ expr;`.
+pub struct Text {
+    pub add:  syn::Token![+],
+    pub expr: Box<syn::Expr>,
+    pub semi: syn::Token![;],
+}
+impl Parse for Text {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self { add: input.parse()?, expr: input.parse()?, semi: input.parse()? })
+    }
+}
+
+/// `include "path";`.
+pub struct Include {
+    pub include_: kw::include,
+    pub path:     syn::LitStr,
+    pub semi:     syn::Token![;],
+}
+impl Parse for Include {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let include_: kw::include = input.parse()?;
+        if !input.peek(syn::LitStr) {
+            return Err(input.error(
+                "DEFY0111: `include` expects the path of a template file as a string literal, \
+                 e.g. `include \"templates/footer.defy\";`",
+            ));
+        }
+        Ok(Self { include_, path: input.parse()?, semi: input.parse()? })
+    }
+}
+
+/// `markdown "path";` or `markdown expr;`.
+pub struct Markdown {
+    pub markdown_: kw::markdown,
+    pub source:    MarkdownSource,
+    pub semi:      syn::Token![;],
+}
+impl Parse for Markdown {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            markdown_: input.parse()?,
+            source:    if input.peek(syn::LitStr) {
+                MarkdownSource::File(input.parse()?)
+            } else {
+                MarkdownSource::Expr(input.parse()?)
+            },
+            semi:      input.parse()?,
+        })
+    }
+}
+
+/// The source of a `markdown` statement.
+pub enum MarkdownSource {
+    File(syn::LitStr),
+    Expr(Box<syn::Expr>),
+}
+
+/// `t "id";` or `t "id" { name = value, ... };`.
+pub struct Translate {
+    pub t:    kw::t,
+    pub id:   syn::LitStr,
+    pub args: Option<(syn::token::Brace, Punctuated<TranslateArg, syn::Token![,]>)>,
+    pub semi: syn::Token![;],
+}
+impl Parse for Translate {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            t:    input.parse()?,
+            id:   input.parse()?,
+            args: if input.peek(syn::token::Brace) {
+                let inner;
+                Some((syn::braced!(inner in input), Punctuated::parse_terminated(&inner)?))
+            } else {
+                None
+            },
+            semi: input.parse()?,
+        })
+    }
+}
+
+/// `name = value` in a `t` statement.
+pub struct TranslateArg {
+    pub name:  syn::Ident,
+    pub eq:    syn::Token![=],
+    pub value: Box<syn::Expr>,
+}
+impl Parse for TranslateArg {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self { name: input.parse()?, eq: input.parse()?, value: input.parse()? })
+    }
+}
+
+/// `icon "path"(args);`.
+pub struct Icon {
+    pub icon_: kw::icon,
+    pub path:  syn::LitStr,
+    pub args:  NodeArgs,
+    pub semi:  syn::Token![;],
+}
+impl Parse for Icon {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            icon_: input.parse()?,
+            path:  input.parse()?,
+            args:  input.parse()?,
+            semi:  input.parse()?,
+        })
+    }
+}
+
+/// `picture "path"(widths = [...], args);`.
+pub struct Picture {
+    pub picture_: kw::picture,
+    pub path:     syn::LitStr,
+    pub args:     NodeArgs,
+    pub semi:     syn::Token![;],
+}
+impl Parse for Picture {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            picture_: input.parse()?,
+            path:     input.parse()?,
+            args:     input.parse()?,
+            semi:     input.parse()?,
+        })
+    }
+}
+
+/// `verbatim "text";`, which renders multiline text with its relative indentation,
+/// e.g. in a `pre` element.
+pub struct Verbatim {
+    pub verbatim_: kw::verbatim,
+    pub text:      syn::LitStr,
+    pub semi:      syn::Token![;],
+}
+impl Parse for Verbatim {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self { verbatim_: input.parse()?, text: input.parse()?, semi: input.parse()? })
+    }
+}
+
+/// `embed expr;`, which renders an `Html` or `Option<Html>` value.
+///
+/// An `<embed>` element is written as `embed(...);`.
+pub struct Embed {
+    pub embed_: kw::embed,
+    pub expr:   Box<syn::Expr>,
+    pub semi:   syn::Token![;],
+}
+impl Parse for Embed {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self { embed_: input.parse()?, expr: input.parse()?, semi: input.parse()? })
+    }
+}
+
+/// `dyn component(props = props);` or `dyn component;`,
+/// which renders a component selected at runtime.
+pub struct Dyn {
+    pub dyn_: syn::Token![dyn],
+    /// The component, followed by `(props = ...)` unless the props are `()`,
+    /// which is split during expansion.
+    pub expr: Box<syn::Expr>,
+    pub semi: syn::Token![;],
+}
+impl Parse for Dyn {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self { dyn_: input.parse()?, expr: input.parse()?, semi: input.parse()? })
+    }
+}
+
+/// `passthrough { ... }`, whose tokens are passed to the `@macro_path` macro unchanged.
+pub struct Passthrough {
+    pub passthrough_: kw::passthrough,
+    pub braces:       syn::token::Brace,
+    pub tokens:       proc_macro2::TokenStream,
+}
+impl Parse for Passthrough {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            passthrough_: input.parse()?,
+            braces:       syn::braced!(inner in input),
+            tokens:       inner.parse()?,
+        })
+    }
+}
+
+/// `raw "html";`, which renders the HTML in the literal without escaping.
+pub struct Raw {
+    pub raw_: kw::raw,
+    pub html: syn::LitStr,
+    pub semi: syn::Token![;],
+}
+impl Parse for Raw {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self { raw_: input.parse()?, html: input.parse()?, semi: input.parse()? })
+    }
+}
+
+/// `space;`, which renders a single space.
+pub struct Space {
+    pub space_: kw::space,
+    pub semi:   syn::Token![;],
+}
+impl Parse for Space {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self { space_: input.parse()?, semi: input.parse()? })
+    }
+}
+
+/// A character reference, e.g. `&nbsp;` in HTML.
+pub enum Entity {
+    /// `entity name;`.
+    Named {
+        entity_: kw::entity,
+        /// May be a keyword, e.g. `in`.
+        name:    syn::Ident,
+        semi:    syn::Token![;],
+    },
+    /// `&{ "name" }`, which also accepts numeric references like `&{ "#x2014" }`.
+    Inline { and: syn::Token![&], braces: syn::token::Brace, name: syn::LitStr },
+}
+impl Entity {
+    /// The name of the character reference without `&` and `;`.
+    pub fn name(&self) -> String {
+        match self {
+            Entity::Named { name, .. } => name.to_string(),
+            Entity::Inline { name, .. } => name.value(),
+        }
+    }
+}
+impl Parse for Entity {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(syn::Token![&]) {
+            let inner;
+            Ok(Entity::Inline {
+                and:    input.parse()?,
+                braces: syn::braced!(inner in input),
+                name:   inner.parse()?,
+            })
+        } else {
+            Ok(Entity::Named {
+                entity_: input.parse()?,
+                name:    input.call(syn::Ident::parse_any)?,
+                semi:    input.parse()?,
+            })
+        }
+    }
+}
+
+/// `document_head { ... }`, which renders its contents into the `<head>` of the document.
+pub struct DocumentHead {
+    pub document_head_: kw::document_head,
+    pub braces:         syn::token::Brace,
+    pub body:           Nodes,
+}
+impl Parse for DocumentHead {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            document_head_: input.parse()?,
+            braces:         syn::braced!(inner in input),
+            body:           inner.parse()?,
+        })
+    }
+}
+
+/// `json_script(args) = expr;`.
+pub struct JsonScript {
+    pub json_script_: kw::json_script,
+    /// `Named` or `None`.
+    pub args:         NodeArgs,
+    pub eq:           syn::Token![=],
+    pub expr:         syn::Expr,
+    pub semi:         syn::Token![;],
+}
+impl Parse for JsonScript {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            json_script_: input.parse()?,
+            // `NodeArgs` would parse `= expr` as rest arguments
+            args:         if input.peek(syn::token::Paren) {
+                input.parse()?
+            } else {
+                NodeArgs::None
+            },
+            eq:           input.parse()?,
+            expr:         input.parse()?,
+            semi:         input.parse()?,
+        })
+    }
+}
+
+/// `og { title "..."; image url; ... }`,
+/// which renders the OpenGraph and Twitter card meta tags of a page.
+pub struct Og {
+    pub og_:        kw::og,
+    pub braces:     syn::token::Brace,
+    pub properties: Vec<OgProperty>,
+}
+impl Parse for Og {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            og_:        input.parse()?,
+            braces:     syn::braced!(inner in input),
+            properties: {
+                let mut properties = Vec::new();
+                while !inner.is_empty() {
+                    properties.push(inner.parse()?);
+                }
+                properties
+            },
+        })
+    }
+}
+
+/// `name value;` in an `og` block.
+pub struct OgProperty {
+    /// May be a keyword, e.g. `type`.
+    pub name:  syn::Ident,
+    pub value: syn::Expr,
+    pub semi:  syn::Token![;],
+}
+impl Parse for OgProperty {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            name:  input.call(syn::Ident::parse_any)?,
+            value: input.parse()?,
+            semi:  input.parse()?,
+        })
+    }
+}
+
+/// `style { "css" }`.
+pub struct Style {
+    pub style_: kw::style,
+    pub braces: syn::token::Brace,
+    pub css:    syn::LitStr,
+}
+impl Style {
+    /// Whether the braces after `style` contain a single string literal,
+    /// as opposed to the children of a `<style>` element.
+    fn peek_css(input: ParseStream) -> bool {
+        fn parse_css(input: ParseStream) -> Result<()> {
+            let inner;
+            input.parse::<kw::style>()?;
+            syn::braced!(inner in input);
+            inner.parse::<syn::LitStr>()?;
+            if inner.is_empty() {
+                Ok(())
+            } else {
+                Err(inner.error("expected a single string literal"))
+            }
+        }
+        parse_css(&input.fork()).is_ok()
+    }
+}
+impl Parse for Style {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            style_: input.parse()?,
+            braces: syn::braced!(inner in input),
+            css:    inner.parse()?,
+        })
+    }
+}
+
+/// `memo(name = expr, ...) { ... }`.
+///
+/// A `memo` element with arguments and children cannot be written,
+/// since it always parses as a memo block.
+pub struct Memo {
+    pub memo_:  kw::memo,
+    pub paren:  syn::token::Paren,
+    pub deps:   Punctuated<MemoDep, syn::Token![,]>,
+    pub braces: syn::token::Brace,
+    pub body:   Nodes,
+}
+impl Parse for Memo {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let (deps, inner);
+        Ok(Self {
+            memo_:  input.parse()?,
+            paren:  syn::parenthesized!(deps in input),
+            deps:   Punctuated::parse_terminated(&deps)?,
+            braces: syn::braced!(inner in input),
+            body:   inner.parse()?,
+        })
+    }
+}
+
+/// `name = expr` or `name` in the dependencies of a `memo` block.
+pub struct MemoDep {
+    pub ident: syn::Ident,
+    pub value: Option<(syn::Token![=], Box<syn::Expr>)>,
+}
+impl Parse for MemoDep {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            ident: input.parse()?,
+            value: if input.peek(syn::Token![=]) {
+                Some((input.parse()?, input.parse()?))
+            } else {
+                None
+            },
+        })
+    }
+}
+
+/// `once { ... }`.
+pub struct Once {
+    pub once_:  kw::once,
+    pub braces: syn::token::Brace,
+    pub body:   Nodes,
+}
+impl Parse for Once {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            once_:  input.parse()?,
+            braces: syn::braced!(inner in input),
+            body:   inner.parse()?,
+        })
+    }
+}
+
+/// `defer { ... }`, optionally followed by `placeholder { ... }`.
+pub struct Defer {
+    pub defer_:      kw::defer,
+    pub braces:      syn::token::Brace,
+    pub body:        Nodes,
+    pub placeholder: Option<Placeholder>,
+}
+impl Parse for Defer {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            defer_:      input.parse()?,
+            braces:      syn::braced!(inner in input),
+            body:        inner.parse()?,
+            placeholder: if input.peek(kw::placeholder) && input.peek2(syn::token::Brace) {
+                Some(input.parse()?)
+            } else {
+                None
+            },
+        })
+    }
+}
+/// `placeholder { ... }`.
+pub struct Placeholder {
+    pub placeholder_: kw::placeholder,
+    pub braces:       syn::token::Brace,
+    pub body:         Nodes,
+}
+impl Parse for Placeholder {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            placeholder_: input.parse()?,
+            braces:       syn::braced!(inner in input),
+            body:         inner.parse()?,
+        })
+    }
+}
+
+/// Parses the expression before the `as` of an `await` or `with` block.
+///
+/// Parsing it as an expression directly would parse `as` as a cast.
+fn parse_before_as(input: ParseStream, span: Span, keyword: &str) -> Result<Box<syn::Expr>> {
+    let mut expr = TokenStream::new();
+    while !input.peek(syn::Token![as]) {
+        if input.is_empty() {
+            return Err(syn::Error::new(
+                span,
+                format_args!(
+                    "DEFY0104: expected `as` and a name after the expression of `{keyword}`"
+                ),
+            ));
+        }
+        expr.extend([input.parse::<TokenTree>()?]);
+    }
+    Ok(Box::new(syn::parse2(expr)?))
+}
+
+/// `await(deps) expr as ident { ... }`, optionally followed by `pending { ... }`.
+pub struct Await {
+    pub await_:  syn::Token![await],
+    /// The dependencies that recreate the future when they change, e.g. `(id)` in `await(id)`.
+    pub deps:    Option<(syn::token::Paren, Punctuated<MemoDep, syn::Token![,]>)>,
+    pub expr:    Box<syn::Expr>,
+    pub as_:     syn::Token![as],
+    pub ident:   syn::Ident,
+    pub braces:  syn::token::Brace,
+    pub body:    Nodes,
+    pub pending: Option<Pending>,
+}
+impl Parse for Await {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let await_: syn::Token![await] = input.parse()?;
+        let deps = if input.peek(syn::token::Paren) {
+            let deps;
+            Some((syn::parenthesized!(deps in input), Punctuated::parse_terminated(&deps)?))
+        } else {
+            None
+        };
+        let expr = parse_before_as(input, await_.span, "await")?;
+        let inner;
+        Ok(Self {
+            await_,
+            deps,
+            expr,
+            as_: input.parse()?,
+            ident: input.parse()?,
+            braces: syn::braced!(inner in input),
+            body: inner.parse()?,
+            pending: if input.peek(kw::pending) && input.peek2(syn::token::Brace) {
+                Some(input.parse()?)
+            } else {
+                None
+            },
+        })
+    }
+}
+/// `pending { ... }`.
+pub struct Pending {
+    pub pending_: kw::pending,
+    pub braces:   syn::token::Brace,
+    pub body:     Nodes,
+}
+impl Parse for Pending {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            pending_: input.parse()?,
+            braces:   syn::braced!(inner in input),
+            body:     inner.parse()?,
+        })
+    }
+}
+
+/// `try { ... }`, optionally followed by `catch ident { ... }` or `catch ident: Type { ... }`.
+pub struct Try {
+    pub try_:   syn::Token![try],
+    pub braces: syn::token::Brace,
+    pub body:   Nodes,
+    pub catch:  Option<Catch>,
+}
+impl Parse for Try {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            try_:   input.parse()?,
+            braces: syn::braced!(inner in input),
+            body:   inner.parse()?,
+            catch:  if input.peek(kw::catch) { Some(input.parse()?) } else { None },
+        })
+    }
+}
+/// `catch ident { ... }` or `catch ident: Type { ... }`.
+pub struct Catch {
+    pub catch_: kw::catch,
+    pub ident:  syn::Ident,
+    pub ty:     Option<(syn::Token![:], Box<syn::Type>)>,
+    pub braces: syn::token::Brace,
+    pub body:   Nodes,
+}
+impl Parse for Catch {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            catch_: input.parse()?,
+            ident:  input.parse()?,
+            ty:     if input.peek(syn::Token![:]) {
+                Some((input.parse()?, input.parse()?))
+            } else {
+                None
+            },
+            braces: syn::braced!(inner in input),
+            body:   inner.parse()?,
+        })
+    }
+}
+
+/// `repeat expr { ... }` or `repeat expr as ident { ... }`.
+pub struct Repeat {
+    pub repeat_: kw::repeat,
+    pub count:   Box<syn::Expr>,
+    pub as_:     Option<(syn::Token![as], syn::Ident)>,
+    pub braces:  syn::token::Brace,
+    pub body:    Nodes,
+}
+impl Parse for Repeat {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let repeat_ = input.parse()?;
+
+        // `as` would otherwise be parsed as a cast
+        let mut count = TokenStream::new();
+        while !(input.is_empty() || input.peek(syn::Token![as]) || input.peek(syn::token::Brace)) {
+            count.extend([input.parse::<TokenTree>()?]);
+        }
+
+        let inner;
+        Ok(Self {
+            repeat_,
+            count: Box::new(syn::parse2(count)?),
+            as_: if input.peek(syn::Token![as]) {
+                Some((input.parse()?, input.parse()?))
+            } else {
+                None
+            },
+            braces: syn::braced!(inner in input),
+            body: inner.parse()?,
+        })
+    }
+}
+
+/// `with expr as ident { ... }`.
+pub struct With {
+    pub with_:  kw::with,
+    pub expr:   Box<syn::Expr>,
+    pub as_:    syn::Token![as],
+    pub ident:  syn::Ident,
+    pub braces: syn::token::Brace,
+    pub body:   Nodes,
+}
+impl Parse for With {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let with_: kw::with = input.parse()?;
+        let inner;
+        Ok(Self {
+            with_,
+            expr: parse_before_as(input, with_.span, "with")?,
+            as_: input.parse()?,
+            ident: input.parse()?,
+            braces: syn::braced!(inner in input),
+            body: inner.parse()?,
+        })
+    }
+}
+
+/// `maybe expr as ident { ... }`, optionally followed by `else { ... }`.
+pub struct Maybe {
+    pub maybe_: kw::maybe,
+    pub expr:   Box<syn::Expr>,
+    pub as_:    syn::Token![as],
+    pub ident:  syn::Ident,
+    pub braces: syn::token::Brace,
+    pub body:   Nodes,
+    pub else_:  Option<Else>,
+}
+impl Parse for Maybe {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let maybe_: kw::maybe = input.parse()?;
+        let inner;
+        Ok(Self {
+            maybe_,
+            expr: parse_before_as(input, maybe_.span, "maybe")?,
+            as_: input.parse()?,
+            ident: input.parse()?,
+            braces: syn::braced!(inner in input),
+            body: inner.parse()?,
+            else_: if input.peek(syn::Token![else]) { Some(input.parse()?) } else { None },
+        })
+    }
+}
+
+/// `form for expr { field name(...); ... }`,
+/// which renders a labeled input for each field of the `UseStateHandle` of a struct.
+///
+/// The head may be followed by `, errors = expr` to render the validation errors of each field.
+pub struct Form {
+    pub form_:  kw::form,
+    pub for_:   syn::Token![for],
+    pub expr:   Box<syn::Expr>,
+    pub errors: Option<FormErrors>,
+    pub braces: syn::token::Brace,
+    pub fields: Vec<FormField>,
+}
+impl Parse for Form {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            form_:  input.parse()?,
+            for_:   input.parse()?,
+            expr:   parse_head(input, "form for")?,
+            errors: if input.peek(syn::Token![,]) { Some(input.parse()?) } else { None },
+            braces: syn::braced!(inner in input),
+            fields: {
+                let mut fields = Vec::new();
+                while !inner.is_empty() {
+                    fields.push(inner.parse()?);
+                }
+                fields
+            },
+        })
+    }
+}
+
+/// `, errors = expr` in the head of a `form` block.
+pub struct FormErrors {
+    pub comma:   syn::Token![,],
+    pub errors_: kw::errors,
+    pub eq:      syn::Token![=],
+    pub expr:    Box<syn::Expr>,
+}
+impl Parse for FormErrors {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            comma:   input.parse()?,
+            errors_: input.parse()?,
+            eq:      input.parse()?,
+            expr:    parse_head(input, "form for")?,
+        })
+    }
+}
+
+/// `field name;` or `field name(attr = value, ...);` in a `form` block.
+pub struct FormField {
+    pub field_: kw::field,
+    pub ident:  syn::Ident,
+    /// Extra attributes of the input element.
+    pub args:   NodeArgs,
+    pub semi:   syn::Token![;],
+}
+impl Parse for FormField {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            field_: input.parse()?,
+            ident:  input.parse()?,
+            args:   input.parse()?,
+            semi:   input.parse()?,
+        })
+    }
+}
+
+/// `table(...) for pat in expr { column header { ... } ... }`,
+/// which renders a table with a header row and a row for each item.
+pub struct Table {
+    pub table_:  kw::table,
+    /// The attributes of the `table` element.
+    pub args:    NodeArgs,
+    pub for_:    syn::Token![for],
+    pub pat:     Box<syn::Pat>,
+    pub in_:     syn::Token![in],
+    pub iter:    Box<syn::Expr>,
+    pub braces:  syn::token::Brace,
+    pub columns: Vec<Column>,
+}
+impl Table {
+    fn peek(input: ParseStream) -> bool {
+        let fork = input.fork();
+        fork.parse::<kw::table>().is_ok()
+            && fork.parse::<NodeArgs>().is_ok()
+            && fork.peek(syn::Token![for])
+    }
+}
+impl Parse for Table {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            table_:  input.parse()?,
+            args:    input.parse()?,
+            for_:    input.parse()?,
+            pat:     Box::new(syn::Pat::parse_multi_with_leading_vert(input)?),
+            in_:     input.parse()?,
+            iter:    parse_head(input, "table for")?,
+            braces:  syn::braced!(inner in input),
+            columns: {
+                let mut columns = Vec::new();
+                while !inner.is_empty() {
+                    columns.push(inner.parse()?);
+                }
+                columns
+            },
+        })
+    }
+}
+
+/// `column header { ... }` in a `table` block.
+pub struct Column {
+    pub column_: kw::column,
+    pub header:  Box<syn::Expr>,
+    pub braces:  syn::token::Brace,
+    /// The contents of the cell in each row.
+    pub body:    Nodes,
+}
+impl Parse for Column {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            column_: input.parse()?,
+            header:  parse_head(input, "column")?,
+            braces:  syn::braced!(inner in input),
+            body:    inner.parse()?,
+        })
+    }
+}
+
+/// `options from expr { |pat| (value = ...) { ... } }`,
+/// which renders an `option` element for each item.
+pub struct Options {
+    pub options_: kw::options,
+    pub from_:    kw::from,
+    pub iter:     Box<syn::Expr>,
+    pub braces:   syn::token::Brace,
+    pub or1:      syn::Token![|],
+    pub pat:      Box<syn::Pat>,
+    pub or2:      syn::Token![|],
+    /// The attributes of each `option` element, which must include `value`.
+    pub args:     NodeArgs,
+    pub body:     NodeBody,
+}
+impl Parse for Options {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        let options = Self {
+            options_: input.parse()?,
+            from_:    input.parse()?,
+            iter:     parse_head(input, "options from")?,
+            braces:   syn::braced!(inner in input),
+            or1:      inner.parse()?,
+            pat:      Box::new(syn::Pat::parse_single(&inner)?),
+            or2:      inner.parse()?,
+            args:     inner.parse()?,
+            body:     inner.parse()?,
+        };
+        if !inner.is_empty() {
+            return Err(inner.error("expected the end of the `options` block"));
+        }
+        if options.value().is_none() {
+            return Err(syn::Error::new(
+                options.or2.span,
+                "DEFY0107: the options of `options from` must have a `value`, e.g. `|item| (value \
+                 = item.id) { ... }`",
+            ));
+        }
+        Ok(options)
+    }
+}
+impl Options {
+    /// The `value` argument of each option.
+    pub fn value(&self) -> Option<&syn::Expr> {
+        let NodeArgs::Named { args, .. } = &self.args else { return None };
+        args.iter().find_map(|arg| match (&arg.value, arg.ident.len()) {
+            (Some((_, value)), 1) if arg.ident[0] == "value" && arg.bind.is_none() => {
+                Some(&**value)
+            }
+            _ => None,
+        })
+    }
+}
+
+/// `wrap_if expr element(...) { ... }`,
+/// which renders the children inside the element only if `expr` is true.
+pub struct WrapIf {
+    pub wrap_if_: kw::wrap_if,
+    pub expr:     Box<syn::Expr>,
+    pub node:     Box<Node>,
+}
+impl Parse for WrapIf {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let wrap_if_ = input.parse()?;
+        let expr = Box::new(input.call(syn::Expr::parse_without_eager_brace)?);
+        let node: Node = input.parse()?;
+        if let NodeBody::Semi(semi) = &node.body {
+            return Err(syn::Error::new_spanned(
+                semi,
+                "DEFY0106: the element of `wrap_if` must have children",
+            ));
+        }
+        Ok(Self { wrap_if_, expr, node: Box::new(node) })
+    }
+}
+
+/// `transition(enter = ..., leave = ...) if expr { ... }`,
+/// which animates the children when `expr` changes.
+pub struct Transition {
+    pub transition_: kw::transition,
+    pub paren:       syn::token::Paren,
+    pub args:        Punctuated<BlockArg, syn::Token![,]>,
+    pub if_:         syn::Token![if],
+    pub cond:        Box<syn::Expr>,
+    pub braces:      syn::token::Brace,
+    pub body:        Nodes,
+}
+impl Parse for Transition {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let args;
+        let inner;
+        Ok(Self {
+            transition_: input.parse()?,
+            paren:       syn::parenthesized!(args in input),
+            args:        args.parse_terminated(BlockArg::parse, syn::Token![,])?,
+            if_:         input.parse()?,
+            cond:        Box::new(input.call(syn::Expr::parse_without_eager_brace)?),
+            braces:      syn::braced!(inner in input),
+            body:        inner.parse()?,
+        })
+    }
+}
+/// `name = expr` in the arguments of a `transition` or `virtual for` block.
+pub struct BlockArg {
+    pub ident: syn::Ident,
+    pub eq:    syn::Token![=],
+    pub value: Box<syn::Expr>,
+}
+impl Parse for BlockArg {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self { ident: input.parse()?, eq: input.parse()?, value: input.parse()? })
+    }
+}
+
+/// `island Component(...);`,
+/// which renders the component with markers for hydrating it on the client.
+pub struct Island {
+    pub island_: kw::island,
+    pub node:    Box<Node>,
+}
+impl Parse for Island {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self { island_: input.parse()?, node: input.parse()? })
+    }
+}
+
+/// `lazy Component(...) { fallback { ... } }` or `lazy Component(...);`,
+/// which renders the component after its code is loaded.
+///
+/// The `fallback` block is parsed as a child node and extracted during expansion.
+pub struct Lazy {
+    pub lazy_: kw::lazy,
+    pub node:  Box<Node>,
+}
+impl Parse for Lazy {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self { lazy_: input.parse()?, node: input.parse()? })
+    }
+}
+
+/// `xml_decl(version = "1.0", ...);`, which renders an XML declaration.
+pub struct XmlDecl {
+    pub xml_decl_: kw::xml_decl,
+    pub args:      NodeArgs,
+    pub semi:      syn::Token![;],
+}
+impl Parse for XmlDecl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self { xml_decl_: input.parse()?, args: input.parse()?, semi: input.parse()? })
+    }
+}
+
+/// `doctype html;`, which renders `<!DOCTYPE html>`.
+pub struct Doctype {
+    pub doctype_: kw::doctype,
+    /// Always `html`.
+    pub name:     syn::Ident,
+    pub semi:     syn::Token![;],
+}
+impl Parse for Doctype {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let doctype_ = input.parse()?;
+        let name: syn::Ident = input.parse()?;
+        if name != "html" {
+            return Err(syn::Error::new(
+                name.span(),
+                "DEFY0110: only `doctype html;` is supported, which renders `<!DOCTYPE html>`",
+            ));
+        }
+        Ok(Self { doctype_, name, semi: input.parse()? })
+    }
+}
+
+/// `block name { ... }`, a region of a layout that `extend` can override.
+///
+/// The children are rendered if the block is not overridden.
+pub struct Block {
+    pub block_: kw::block,
+    pub name:   syn::Ident,
+    pub braces: syn::token::Brace,
+    pub body:   Nodes,
+}
+impl Parse for Block {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            block_: input.parse()?,
+            name:   input.parse()?,
+            braces: syn::braced!(inner in input),
+            body:   inner.parse()?,
+        })
+    }
+}
+
+/// `extend layout { block name { ... } ... }`,
+/// which renders a layout function with some of its blocks overridden.
+pub struct Extend {
+    pub extend_: kw::extend,
+    pub layout:  syn::Path,
+    pub braces:  syn::token::Brace,
+    /// Only `block` statements are valid, which is checked during expansion.
+    pub blocks:  Nodes,
+}
+impl Parse for Extend {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            extend_: input.parse()?,
+            layout:  input.parse()?,
+            braces:  syn::braced!(inner in input),
+            blocks:  inner.parse()?,
+        })
+    }
+}
+
+/// `do expr;`.
+pub struct Do {
+    pub do_:  syn::Token![do],
+    pub expr: Box<syn::Expr>,
+    pub semi: syn::Token![;],
+}
+impl Parse for Do {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self { do_: input.parse()?, expr: input.parse()?, semi: input.parse()? })
+    }
+}
+
+/// `note "text";` or a `///` doc comment.
+pub enum Note {
+    Stmt { note_: kw::note, text: syn::LitStr, semi: syn::Token![;] },
+    Doc(Box<syn::Attribute>),
+}
+impl Note {
+    /// Whether the input starts with a doc comment.
+    fn peek_doc(input: ParseStream) -> bool {
+        input.peek(syn::Token![#])
+            && input
+                .fork()
+                .call(syn::Attribute::parse_outer)
+                .is_ok_and(|attrs| attrs.first().is_some_and(|attr| attr.path().is_ident("doc")))
+    }
+
+    /// The text of the note.
+    pub fn text(&self) -> String {
+        match self {
+            Note::Stmt { text, .. } => text.value(),
+            Note::Doc(attr) => match &attr.meta {
+                syn::Meta::NameValue(syn::MetaNameValue {
+                    value: syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(text), .. }),
+                    ..
+                }) => text.value().trim().to_string(),
+                _ => String::new(),
+            },
+        }
+    }
+}
+impl Parse for Note {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(syn::Token![#]) {
+            // only the first attribute, since consecutive doc comments are separate notes
+            let content;
+            return Ok(Note::Doc(Box::new(syn::Attribute {
+                pound_token:   input.parse()?,
+                style:         syn::AttrStyle::Outer,
+                bracket_token: syn::bracketed!(content in input),
+                meta:          content.parse()?,
+            })));
+        }
+        Ok(Note::Stmt { note_: input.parse()?, text: input.parse()?, semi: input.parse()? })
+    }
+}
+
+/// `break;`.
+pub struct Break {
+    pub break_: syn::Token![break],
+    pub semi:   syn::Token![;],
+}
+impl Parse for Break {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self { break_: input.parse()?, semi: input.parse()? })
+    }
+}
+
+/// `continue;`.
+pub struct Continue {
+    pub continue_: syn::Token![continue],
+    pub semi:      syn::Token![;],
+}
+impl Parse for Continue {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self { continue_: input.parse()?, semi: input.parse()? })
+    }
+}
+
+/// An element or component, e.g. `div(id = "a") { ... }` or `br;`.
+pub struct Node {
+    /// Attributes other than `cfg`, e.g. lint levels like `#[allow(unknown_tag)]`.
+    pub attrs:   Vec<syn::Attribute>,
+    pub element: syn::Path,
+    pub args:    NodeArgs,
+    pub body:    NodeBody,
+}
+impl Parse for Node {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            attrs:   input.call(syn::Attribute::parse_outer)?,
+            element: parse_path_without_paren(input)?,
+            args:    input.parse()?,
+            body:    input.parse()?,
+        })
+    }
+}
+
+/// The `;` or braced children after a node.
+pub enum NodeBody {
+    Semi(syn::Token![;]),
+    Braced { braces: syn::token::Brace, children: Nodes },
+}
+impl Parse for NodeBody {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(if input.peek(syn::Token![;]) {
+            Self::Semi(input.parse()?)
+        } else if input.peek(syn::token::Brace) {
+            let inner;
+            Self::Braced { braces: syn::braced!(inner in input), children: inner.parse()? }
+        } else {
+            return Err(input.error(
+                "DEFY0103: expected `;` for a childless element or `{ ... }` for children after \
+                 the element",
+            ));
+        })
+    }
+}
+
+// Fn/FnMut/FnOnce cannot be a component,
+// so it is safe to steal the parentheses syntax for node arguments.
+fn parse_path_without_paren(input: ParseStream) -> Result<syn::Path> {
+    let leading_colon =
+        if input.peek(syn::Token![::]) { Some(input.parse::<syn::Token![::]>()?) } else { None };
+
+    let mut segments = Punctuated::new();
+    loop {
+        let ident = input.parse()?;
+        // lifetimes, const generics and nested generics are parsed by syn
+        let arguments = if input.peek(syn::Token![<]) {
+            syn::PathArguments::AngleBracketed(input.parse()?)
+        } else if input.peek(syn::Token![::]) && input.peek3(syn::Token![<]) {
+            syn::PathArguments::AngleBracketed(
+                syn::AngleBracketedGenericArguments::parse_turbofish(input)?,
+            )
+        } else {
+            syn::PathArguments::None
+        };
+        segments.push_value(syn::PathSegment { ident, arguments });
+
+        if input.peek(syn::Token![::]) {
+            segments.push_punct(input.parse()?);
+        } else {
+            break;
+        }
+    }
+
+    Ok(syn::Path { leading_colon, segments })
+}
+
+/// The arguments of a node: none, `(name = value, ...)` or `= props`.
+pub enum NodeArgs {
+    None,
+    Named { paren: syn::token::Paren, args: Punctuated<NodeArg, syn::Token![,]> },
+    Rest { eq: syn::Token![=], arg: Box<syn::Expr> },
+}
+impl Parse for NodeArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(if input.peek(syn::Token![=]) {
+            NodeArgs::Rest { eq: input.parse()?, arg: input.parse()? }
+        } else if input.peek(syn::token::Paren) {
+            let inner;
+            NodeArgs::Named {
+                paren: syn::parenthesized!(inner in input),
+                args:  Punctuated::parse_terminated(&inner)?,
+            }
+        } else {
+            // a missing body is reported by `NodeBody`
+            NodeArgs::None
+        })
+    }
+}
+
+/// `name = value`, `name`, `bind:name = value` or `@event = value` in node arguments.
+pub struct NodeArg {
+    /// The `bind:` prefix of a two-way binding to a `UseStateHandle`.
+    pub bind:      Option<(kw::bind, syn::Token![:])>,
+    /// The `@` prefix of an event handler, e.g. `@click` for `onclick`.
+    pub at:        Option<syn::Token![@]>,
+    /// The namespace prefix of an XML attribute, e.g. `xmlns:` in `xmlns:image`.
+    pub prefix:    Option<(syn::Ident, syn::Token![:])>,
+    pub ident:     Punctuated<syn::Ident, syn::Token![-]>,
+    /// The keys that an `@event` handler is called for, e.g. `@keydown(Enter)`.
+    pub keys:      Option<(syn::token::Paren, Punctuated<KeyFilter, syn::Token![|]>)>,
+    /// The modifiers of an `@event` handler, e.g. `.prevent` in `@submit.prevent`.
+    pub modifiers: Vec<(syn::Token![.], syn::Ident)>,
+    pub value:     Option<(syn::Token![=], Box<syn::Expr>)>,
+}
+impl Parse for NodeArg {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let bind = if input.peek(kw::bind) && input.peek2(syn::Token![:]) {
+            Some((input.parse()?, input.parse()?))
+        } else {
+            None
+        };
+        let at: Option<syn::Token![@]> = if bind.is_none() { input.parse()? } else { None };
+        let prefix = if bind.is_none()
+            && at.is_none()
+            && input.peek(syn::Ident::peek_any)
+            && input.peek2(syn::Token![:])
+            && !input.peek2(syn::Token![::])
+        {
+            Some((input.call(syn::Ident::parse_any)?, input.parse()?))
+        } else {
+            None
+        };
+        let ident = Punctuated::parse_separated_nonempty_with(input, syn::Ident::parse_any)?;
+        let keys = if at.is_some() && input.peek(syn::token::Paren) {
+            let inner;
+            Some((
+                syn::parenthesized!(inner in input),
+                Punctuated::parse_separated_nonempty(&inner)?,
+            ))
+        } else {
+            None
+        };
+        let mut modifiers = Vec::new();
+        while at.is_some() && input.peek(syn::Token![.]) {
+            modifiers.push((input.parse()?, input.parse()?));
+        }
+        let value = if input.peek(syn::Token![=]) {
+            let eq = input.parse()?;
+            let value = if ClassMap::peek(input) {
+                // not an expression, so it is kept as tokens and parsed again by the codegen
+                let map: ClassMap = input.parse()?;
+                Box::new(syn::Expr::Verbatim(map.to_token_stream()))
+            } else {
+                input.parse()?
+            };
+            Some((eq, value))
+        } else if let (Some(at), true) = (at, modifiers.is_empty()) {
+            return Err(syn::Error::new(
+                at.span,
+                format_args!(
+                    "DEFY0108: expected `=` and a handler after `@{}`, e.g. `@click = on_click`",
+                    ident.iter().map(ToString::to_string).collect::<Vec<_>>().join("-"),
+                ),
+            ));
+        } else {
+            None
+        };
+        Ok(Self { bind, at, prefix, ident, keys, modifiers, value })
+    }
+}
+
+/// `{ "class": condition, ... }` as the value of a node argument,
+/// which renders each class for which the condition is true.
+pub struct ClassMap {
+    pub braces:  syn::token::Brace,
+    pub entries: Punctuated<ClassEntry, syn::Token![,]>,
+}
+impl ClassMap {
+    /// Whether the input starts with a class map rather than a block expression.
+    pub fn peek(input: ParseStream) -> bool {
+        let Some((inner, ..)) = input.cursor().group(Delimiter::Brace) else {
+            return false;
+        };
+        let Some((lit, rest)) = inner.literal() else { return false };
+        lit.to_string().starts_with(['"', 'r'])
+            && rest.punct().is_some_and(|(punct, _)| {
+                punct.as_char() == ':' && punct.spacing() == Spacing::Alone
+            })
+    }
+}
+impl Parse for ClassMap {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let inner;
+        Ok(Self {
+            braces:  syn::braced!(inner in input),
+            entries: Punctuated::parse_terminated(&inner)?,
+        })
+    }
+}
+
+/// `"class": condition` in a [`ClassMap`].
+pub struct ClassEntry {
+    pub class:     syn::LitStr,
+    pub colon:     syn::Token![:],
+    pub condition: syn::Expr,
+}
+impl Parse for ClassEntry {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self { class: input.parse()?, colon: input.parse()?, condition: input.parse()? })
+    }
+}
+
+/// A key in the filter of a keyboard event handler,
+/// e.g. `Enter` or `"k" with ctrl + shift`.
+pub struct KeyFilter {
+    pub key:       KeyName,
+    /// The modifier keys that must be pressed.
+    pub modifiers: Option<(kw::with, Punctuated<syn::Ident, syn::Token![+]>)>,
+}
+impl Parse for KeyFilter {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            key:       input.parse()?,
+            modifiers: if input.peek(kw::with) {
+                Some((input.parse()?, Punctuated::parse_separated_nonempty(input)?))
+            } else {
+                None
+            },
+        })
+    }
+}
+
+/// The `KeyboardEvent.key` value of a [`KeyFilter`].
+pub enum KeyName {
+    /// A named key, e.g. `Enter` or `ArrowUp`.
+    Ident(syn::Ident),
+    /// A character, e.g. `"k"`.
+    Str(syn::LitStr),
+}
+impl KeyName {
+    pub fn value(&self) -> String {
+        match self {
+            KeyName::Ident(ident) => ident.to_string(),
+            KeyName::Str(lit) => lit.value(),
+        }
+    }
+}
+impl Parse for KeyName {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(if input.peek(syn::LitStr) {
+            KeyName::Str(input.parse()?)
+        } else {
+            KeyName::Ident(input.call(syn::Ident::parse_any)?)
+        })
+    }
+}
@@ -0,0 +1,43 @@
+#![cfg(test)]
+
+use quote::{quote, ToTokens};
+
+use crate::Input;
+
+#[test]
+fn test_to_tokens_round_trip() {
+    let tokens = quote! {
+        @macro_path ::yew::html_nested
+        @deny(a11y, unknown_tag)
+        @debug_print("target/a.rs")
+        let x = 1;
+        #[allow(unknown_tag)]
+        MyWidget(data-x = x, ref) {
+            if a { + "a"; } else { br; }
+            match b { Some(c) if c > 1 => { + c; } _ => {} }
+            for d in e { t "id" { n = d }; }
+            style { "color: red;" }
+            icon "a.svg"(class = "i");
+            markdown "a.md";
+            include "a.defy";
+            await(f, g = h) load(f) as i { + i; }
+        }
+        Comp = props;
+    };
+    let input: Input = syn::parse2(tokens.clone()).unwrap();
+    assert_eq!(input.to_token_stream().to_string(), tokens.to_string());
+}
+
+#[test]
+fn test_generic_component_paths() {
+    for tokens in [
+        quote! { List::<Item<'a>, 3>(items = items); },
+        quote! { widgets::List<Vec<Option<T> >, { N + 1 }> {} },
+        quote! { app::Table::<Row>::Cell<'static>; },
+    ] {
+        let input: Input = syn::parse2(tokens.clone()).unwrap();
+        let [crate::Stmt::Node(node)] = &input.nodes.stmts[..] else { panic!("{tokens}") };
+        assert!(node.element.segments.iter().any(|segment| !segment.arguments.is_none()));
+        assert_eq!(input.to_token_stream().to_string(), tokens.to_string());
+    }
+}
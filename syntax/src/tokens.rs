@@ -0,0 +1,830 @@
+//! [`ToTokens`] implementations that print the syntax tree back into defy syntax.
+
+use proc_macro2::TokenStream;
+use quote::{ToTokens, TokenStreamExt};
+
+use crate::*;
+
+impl ToTokens for Input {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        for (config, _) in &self.configs {
+            config.to_tokens(tokens);
+        }
+        self.nodes.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Config {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Config::DebugPrint { at, kw, target } => {
+                at.to_tokens(tokens);
+                kw.to_tokens(tokens);
+                if let Some((paren, target)) = target {
+                    paren.surround(tokens, |tokens| target.to_tokens(tokens));
+                }
+            }
+            Config::MacroPath { at, kw, path } => {
+                at.to_tokens(tokens);
+                kw.to_tokens(tokens);
+                path.to_tokens(tokens);
+            }
+            Config::I18n { at, kw, path } => {
+                at.to_tokens(tokens);
+                kw.to_tokens(tokens);
+                path.to_tokens(tokens);
+            }
+            Config::I18nResources { at, kw, path } => {
+                at.to_tokens(tokens);
+                kw.to_tokens(tokens);
+                path.to_tokens(tokens);
+            }
+            Config::AssetResolver { at, kw, path } => {
+                at.to_tokens(tokens);
+                kw.to_tokens(tokens);
+                path.to_tokens(tokens);
+            }
+            Config::Scope { at, kw, name } => {
+                at.to_tokens(tokens);
+                kw.to_tokens(tokens);
+                name.to_tokens(tokens);
+            }
+            Config::Theme { at, kw, path } => {
+                at.to_tokens(tokens);
+                kw.to_tokens(tokens);
+                path.to_tokens(tokens);
+            }
+            Config::Export { at, kw, name } => {
+                at.to_tokens(tokens);
+                kw.to_tokens(tokens);
+                name.to_tokens(tokens);
+            }
+            Config::DebugLoc { at, kw } => {
+                at.to_tokens(tokens);
+                kw.to_tokens(tokens);
+            }
+            Config::EmitNotes { at, kw } => {
+                at.to_tokens(tokens);
+                kw.to_tokens(tokens);
+            }
+            Config::Strict { at, kw } => {
+                at.to_tokens(tokens);
+                kw.to_tokens(tokens);
+            }
+            Config::Trace { at, kw } => {
+                at.to_tokens(tokens);
+                kw.to_tokens(tokens);
+            }
+            Config::Blocks { at, kw, blocks } => {
+                at.to_tokens(tokens);
+                kw.to_tokens(tokens);
+                blocks.to_tokens(tokens);
+            }
+            Config::Link { at, kw, ctx } => {
+                at.to_tokens(tokens);
+                kw.to_tokens(tokens);
+                ctx.to_tokens(tokens);
+            }
+            Config::Testids { at, kw } => {
+                at.to_tokens(tokens);
+                kw.to_tokens(tokens);
+            }
+            Config::Xml { at, kw } => {
+                at.to_tokens(tokens);
+                kw.to_tokens(tokens);
+            }
+            Config::SortAttrs { at, kw } => {
+                at.to_tokens(tokens);
+                kw.to_tokens(tokens);
+            }
+            Config::Minify { at, kw } => {
+                at.to_tokens(tokens);
+                kw.to_tokens(tokens);
+            }
+            Config::CspNonce { at, kw, nonce } => {
+                at.to_tokens(tokens);
+                kw.to_tokens(tokens);
+                nonce.to_tokens(tokens);
+            }
+            Config::LintLevel { at, level, paren, lints } => {
+                at.to_tokens(tokens);
+                level.to_tokens(tokens);
+                paren.surround(tokens, |tokens| lints.to_tokens(tokens));
+            }
+        }
+    }
+}
+
+impl ToTokens for DebugPrintTarget {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            DebugPrintTarget::File(lit) => lit.to_tokens(tokens),
+            DebugPrintTarget::Note(kw) => kw.to_tokens(tokens),
+        }
+    }
+}
+
+impl ToTokens for LintLevel {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            LintLevel::Allow(kw) => kw.to_tokens(tokens),
+            LintLevel::Warn(kw) => kw.to_tokens(tokens),
+            LintLevel::Deny(kw) => kw.to_tokens(tokens),
+        }
+    }
+}
+
+impl ToTokens for Nodes {
+    fn to_tokens(&self, tokens: &mut TokenStream) { tokens.append_all(&self.stmts); }
+}
+
+impl ToTokens for Stmt {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Stmt::If(stmt) => stmt.to_tokens(tokens),
+            Stmt::Match(stmt) => stmt.to_tokens(tokens),
+            Stmt::For(stmt) => stmt.to_tokens(tokens),
+            Stmt::Let(stmt) => stmt.to_tokens(tokens),
+            Stmt::Item(stmt) => stmt.to_tokens(tokens),
+            Stmt::Text(stmt) => stmt.to_tokens(tokens),
+            Stmt::Embed(stmt) => stmt.to_tokens(tokens),
+            Stmt::Dyn(stmt) => stmt.to_tokens(tokens),
+            Stmt::Include(stmt) => stmt.to_tokens(tokens),
+            Stmt::Markdown(stmt) => stmt.to_tokens(tokens),
+            Stmt::Translate(stmt) => stmt.to_tokens(tokens),
+            Stmt::Icon(stmt) => stmt.to_tokens(tokens),
+            Stmt::Style(stmt) => stmt.to_tokens(tokens),
+            Stmt::Memo(stmt) => stmt.to_tokens(tokens),
+            Stmt::Once(stmt) => stmt.to_tokens(tokens),
+            Stmt::Defer(stmt) => stmt.to_tokens(tokens),
+            Stmt::Await(stmt) => stmt.to_tokens(tokens),
+            Stmt::Try(stmt) => stmt.to_tokens(tokens),
+            Stmt::Break(stmt) => stmt.to_tokens(tokens),
+            Stmt::Continue(stmt) => stmt.to_tokens(tokens),
+            Stmt::Repeat(stmt) => stmt.to_tokens(tokens),
+            Stmt::With(stmt) => stmt.to_tokens(tokens),
+            Stmt::WrapIf(stmt) => stmt.to_tokens(tokens),
+            Stmt::Transition(stmt) => stmt.to_tokens(tokens),
+            Stmt::VirtualFor(stmt) => stmt.to_tokens(tokens),
+            Stmt::Island(stmt) => stmt.to_tokens(tokens),
+            Stmt::Lazy(stmt) => stmt.to_tokens(tokens),
+            Stmt::Doctype(stmt) => stmt.to_tokens(tokens),
+            Stmt::Block(stmt) => stmt.to_tokens(tokens),
+            Stmt::Extend(stmt) => stmt.to_tokens(tokens),
+            Stmt::XmlDecl(stmt) => stmt.to_tokens(tokens),
+            Stmt::Maybe(stmt) => stmt.to_tokens(tokens),
+            Stmt::Form(stmt) => stmt.to_tokens(tokens),
+            Stmt::Table(stmt) => stmt.to_tokens(tokens),
+            Stmt::Options(stmt) => stmt.to_tokens(tokens),
+            Stmt::Picture(stmt) => stmt.to_tokens(tokens),
+            Stmt::JsonScript(stmt) => stmt.to_tokens(tokens),
+            Stmt::Og(stmt) => stmt.to_tokens(tokens),
+            Stmt::DocumentHead(stmt) => stmt.to_tokens(tokens),
+            Stmt::Entity(stmt) => stmt.to_tokens(tokens),
+            Stmt::Space(stmt) => stmt.to_tokens(tokens),
+            Stmt::Verbatim(stmt) => stmt.to_tokens(tokens),
+            Stmt::Raw(stmt) => stmt.to_tokens(tokens),
+            Stmt::Passthrough(stmt) => stmt.to_tokens(tokens),
+            Stmt::Do(stmt) => stmt.to_tokens(tokens),
+            Stmt::Note(stmt) => stmt.to_tokens(tokens),
+            Stmt::Attributed(stmt) => stmt.to_tokens(tokens),
+            Stmt::Node(stmt) => stmt.to_tokens(tokens),
+        }
+    }
+}
+
+impl ToTokens for If {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.if_.to_tokens(tokens);
+        self.media.to_tokens(tokens);
+        self.expr.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| self.body.to_tokens(tokens));
+        self.else_.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Else {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.else_.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| self.body.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for Match {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.match_.to_tokens(tokens);
+        self.expr.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| tokens.append_all(&self.arms));
+    }
+}
+
+impl ToTokens for Arm {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.pat.to_tokens(tokens);
+        if let Some((if_, guard)) = &self.guard {
+            if_.to_tokens(tokens);
+            guard.to_tokens(tokens);
+        }
+        self.fat_arrow.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| self.body.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for For {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.for_.to_tokens(tokens);
+        self.pat.to_tokens(tokens);
+        self.in_.to_tokens(tokens);
+        self.iter.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| self.body.to_tokens(tokens));
+        self.else_.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Let {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.let_.to_tokens(tokens);
+        self.pat.to_tokens(tokens);
+        if let Some((colon, ty)) = &self.ty {
+            colon.to_tokens(tokens);
+            ty.to_tokens(tokens);
+        }
+        self.eq.to_tokens(tokens);
+        self.expr.to_tokens(tokens);
+        self.semi.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Text {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.add.to_tokens(tokens);
+        self.expr.to_tokens(tokens);
+        self.semi.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Embed {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.embed_.to_tokens(tokens);
+        self.expr.to_tokens(tokens);
+        self.semi.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Dyn {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.dyn_.to_tokens(tokens);
+        self.expr.to_tokens(tokens);
+        self.semi.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Include {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.include_.to_tokens(tokens);
+        self.path.to_tokens(tokens);
+        self.semi.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Markdown {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.markdown_.to_tokens(tokens);
+        match &self.source {
+            MarkdownSource::File(lit) => lit.to_tokens(tokens),
+            MarkdownSource::Expr(expr) => expr.to_tokens(tokens),
+        }
+        self.semi.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Translate {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.t.to_tokens(tokens);
+        self.id.to_tokens(tokens);
+        if let Some((braces, args)) = &self.args {
+            braces.surround(tokens, |tokens| args.to_tokens(tokens));
+        }
+        self.semi.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for TranslateArg {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.name.to_tokens(tokens);
+        self.eq.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Icon {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.icon_.to_tokens(tokens);
+        self.path.to_tokens(tokens);
+        self.args.to_tokens(tokens);
+        self.semi.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Style {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.style_.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| self.css.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for Memo {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.memo_.to_tokens(tokens);
+        self.paren.surround(tokens, |tokens| self.deps.to_tokens(tokens));
+        self.braces.surround(tokens, |tokens| self.body.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for Once {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.once_.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| self.body.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for Defer {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.defer_.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| self.body.to_tokens(tokens));
+        self.placeholder.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Placeholder {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.placeholder_.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| self.body.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for Await {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.await_.to_tokens(tokens);
+        if let Some((paren, deps)) = &self.deps {
+            paren.surround(tokens, |tokens| deps.to_tokens(tokens));
+        }
+        self.expr.to_tokens(tokens);
+        self.as_.to_tokens(tokens);
+        self.ident.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| self.body.to_tokens(tokens));
+        self.pending.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Pending {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.pending_.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| self.body.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for Try {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.try_.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| self.body.to_tokens(tokens));
+        self.catch.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Catch {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.catch_.to_tokens(tokens);
+        self.ident.to_tokens(tokens);
+        if let Some((colon, ty)) = &self.ty {
+            colon.to_tokens(tokens);
+            ty.to_tokens(tokens);
+        }
+        self.braces.surround(tokens, |tokens| self.body.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for Repeat {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.repeat_.to_tokens(tokens);
+        self.count.to_tokens(tokens);
+        if let Some((as_, ident)) = &self.as_ {
+            as_.to_tokens(tokens);
+            ident.to_tokens(tokens);
+        }
+        self.braces.surround(tokens, |tokens| self.body.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for With {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.with_.to_tokens(tokens);
+        self.expr.to_tokens(tokens);
+        self.as_.to_tokens(tokens);
+        self.ident.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| self.body.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for Maybe {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.maybe_.to_tokens(tokens);
+        self.expr.to_tokens(tokens);
+        self.as_.to_tokens(tokens);
+        self.ident.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| self.body.to_tokens(tokens));
+        self.else_.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Form {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.form_.to_tokens(tokens);
+        self.for_.to_tokens(tokens);
+        self.expr.to_tokens(tokens);
+        self.errors.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| {
+            for field in &self.fields {
+                field.to_tokens(tokens);
+            }
+        });
+    }
+}
+
+impl ToTokens for FormErrors {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.comma.to_tokens(tokens);
+        self.errors_.to_tokens(tokens);
+        self.eq.to_tokens(tokens);
+        self.expr.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for FormField {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.field_.to_tokens(tokens);
+        self.ident.to_tokens(tokens);
+        self.args.to_tokens(tokens);
+        self.semi.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Table {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.table_.to_tokens(tokens);
+        self.args.to_tokens(tokens);
+        self.for_.to_tokens(tokens);
+        self.pat.to_tokens(tokens);
+        self.in_.to_tokens(tokens);
+        self.iter.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| {
+            for column in &self.columns {
+                column.to_tokens(tokens);
+            }
+        });
+    }
+}
+
+impl ToTokens for Column {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.column_.to_tokens(tokens);
+        self.header.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| self.body.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for Options {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.options_.to_tokens(tokens);
+        self.from_.to_tokens(tokens);
+        self.iter.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| {
+            self.or1.to_tokens(tokens);
+            self.pat.to_tokens(tokens);
+            self.or2.to_tokens(tokens);
+            self.args.to_tokens(tokens);
+            self.body.to_tokens(tokens);
+        });
+    }
+}
+
+impl ToTokens for Picture {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.picture_.to_tokens(tokens);
+        self.path.to_tokens(tokens);
+        self.args.to_tokens(tokens);
+        self.semi.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for JsonScript {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.json_script_.to_tokens(tokens);
+        self.args.to_tokens(tokens);
+        self.eq.to_tokens(tokens);
+        self.expr.to_tokens(tokens);
+        self.semi.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Verbatim {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.verbatim_.to_tokens(tokens);
+        self.text.to_tokens(tokens);
+        self.semi.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Passthrough {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.passthrough_.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| self.tokens.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for Raw {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.raw_.to_tokens(tokens);
+        self.html.to_tokens(tokens);
+        self.semi.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Space {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.space_.to_tokens(tokens);
+        self.semi.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Entity {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Entity::Named { entity_, name, semi } => {
+                entity_.to_tokens(tokens);
+                name.to_tokens(tokens);
+                semi.to_tokens(tokens);
+            }
+            Entity::Inline { and, braces, name } => {
+                and.to_tokens(tokens);
+                braces.surround(tokens, |tokens| name.to_tokens(tokens));
+            }
+        }
+    }
+}
+
+impl ToTokens for DocumentHead {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.document_head_.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| self.body.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for Og {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.og_.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| {
+            for property in &self.properties {
+                property.to_tokens(tokens);
+            }
+        });
+    }
+}
+
+impl ToTokens for OgProperty {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.name.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+        self.semi.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Transition {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.transition_.to_tokens(tokens);
+        self.paren.surround(tokens, |tokens| self.args.to_tokens(tokens));
+        self.if_.to_tokens(tokens);
+        self.cond.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| self.body.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for XmlDecl {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.xml_decl_.to_tokens(tokens);
+        self.args.to_tokens(tokens);
+        self.semi.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Doctype {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.doctype_.to_tokens(tokens);
+        self.name.to_tokens(tokens);
+        self.semi.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Block {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.block_.to_tokens(tokens);
+        self.name.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| self.body.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for Extend {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.extend_.to_tokens(tokens);
+        self.layout.to_tokens(tokens);
+        self.braces.surround(tokens, |tokens| self.blocks.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for Island {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.island_.to_tokens(tokens);
+        self.node.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Lazy {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.lazy_.to_tokens(tokens);
+        self.node.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for VirtualFor {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.virtual_.to_tokens(tokens);
+        self.for_.to_tokens(tokens);
+        self.pat.to_tokens(tokens);
+        self.in_.to_tokens(tokens);
+        self.iter.to_tokens(tokens);
+        self.paren.surround(tokens, |tokens| self.args.to_tokens(tokens));
+        self.braces.surround(tokens, |tokens| self.body.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for BlockArg {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.ident.to_tokens(tokens);
+        self.eq.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for WrapIf {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.wrap_if_.to_tokens(tokens);
+        self.expr.to_tokens(tokens);
+        self.node.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Do {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.do_.to_tokens(tokens);
+        self.expr.to_tokens(tokens);
+        self.semi.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Note {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Note::Stmt { note_, text, semi } => {
+                note_.to_tokens(tokens);
+                text.to_tokens(tokens);
+                semi.to_tokens(tokens);
+            }
+            Note::Doc(attr) => attr.to_tokens(tokens),
+        }
+    }
+}
+
+impl ToTokens for Attributed {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.append_all(&self.attrs);
+        self.stmt.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Break {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.break_.to_tokens(tokens);
+        self.semi.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for Continue {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.continue_.to_tokens(tokens);
+        self.semi.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for MemoDep {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.ident.to_tokens(tokens);
+        if let Some((eq, value)) = &self.value {
+            eq.to_tokens(tokens);
+            value.to_tokens(tokens);
+        }
+    }
+}
+
+impl ToTokens for Node {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.append_all(&self.attrs);
+        self.element.to_tokens(tokens);
+        self.args.to_tokens(tokens);
+        self.body.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for NodeBody {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            NodeBody::Semi(semi) => semi.to_tokens(tokens),
+            NodeBody::Braced { braces, children } => {
+                braces.surround(tokens, |tokens| children.to_tokens(tokens));
+            }
+        }
+    }
+}
+
+impl ToTokens for NodeArgs {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            NodeArgs::None => {}
+            NodeArgs::Named { paren, args } => {
+                paren.surround(tokens, |tokens| args.to_tokens(tokens))
+            }
+            NodeArgs::Rest { eq, arg } => {
+                eq.to_tokens(tokens);
+                arg.to_tokens(tokens);
+            }
+        }
+    }
+}
+
+impl ToTokens for NodeArg {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        if let Some((bind, colon)) = &self.bind {
+            bind.to_tokens(tokens);
+            colon.to_tokens(tokens);
+        }
+        self.at.to_tokens(tokens);
+        if let Some((prefix, colon)) = &self.prefix {
+            prefix.to_tokens(tokens);
+            colon.to_tokens(tokens);
+        }
+        self.ident.to_tokens(tokens);
+        if let Some((paren, keys)) = &self.keys {
+            paren.surround(tokens, |tokens| keys.to_tokens(tokens));
+        }
+        for (dot, modifier) in &self.modifiers {
+            dot.to_tokens(tokens);
+            modifier.to_tokens(tokens);
+        }
+        if let Some((eq, value)) = &self.value {
+            eq.to_tokens(tokens);
+            value.to_tokens(tokens);
+        }
+    }
+}
+
+impl ToTokens for ClassMap {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.braces.surround(tokens, |tokens| self.entries.to_tokens(tokens));
+    }
+}
+
+impl ToTokens for ClassEntry {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.class.to_tokens(tokens);
+        self.colon.to_tokens(tokens);
+        self.condition.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for KeyFilter {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.key.to_tokens(tokens);
+        if let Some((with, modifiers)) = &self.modifiers {
+            with.to_tokens(tokens);
+            modifiers.to_tokens(tokens);
+        }
+    }
+}
+
+impl ToTokens for KeyName {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            KeyName::Ident(ident) => ident.to_tokens(tokens),
+            KeyName::Str(lit) => lit.to_tokens(tokens),
+        }
+    }
+}